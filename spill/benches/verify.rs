@@ -0,0 +1,109 @@
+//! Compares verifying payments one by one against verifying them as a chain.
+//!
+//! Run with `cargo bench --bench verify`.
+
+use bitcoin::{
+    Amount, EcdsaSighashType, Network, OutPoint, PrivateKey, Psbt, Sequence, TxIn, Txid, Witness,
+    primitives::relative, script::ScriptBuf, secp256k1::SecretKey, sighash::SighashCache,
+};
+use criterion::{Criterion, criterion_group, criterion_main};
+use spill::{Channel, ChannelParams, PrivateKeySigner, SegwitBackend};
+
+/// Number of payments in the verified chain.
+const PAYMENTS: u32 = 32;
+
+fn key(seed: u8) -> PrivateKey {
+    let secret =
+        SecretKey::from_secret_bytes([seed; 32]).expect("seed must give a valid secret key");
+    PrivateKey::from_secp(secret, Network::Regtest)
+}
+
+/// Opens a channel against an unsigned funding transaction and builds a chain
+/// of payments signed by the payer.
+fn setup() -> (Channel<SegwitBackend>, Vec<Psbt>) {
+    let payer = key(1);
+    let params = ChannelParams::new(
+        payer.public_key(),
+        key(2).public_key(),
+        Amount::from_sat_u32(1_000_000),
+        relative::LockTime::from_height(144),
+        SegwitBackend::new(),
+    )
+    .expect("failed to create ChannelParams");
+
+    let mut funding_tx = params.funding_psbt().unsigned_tx;
+    funding_tx.inputs.push(TxIn {
+        previous_output: OutPoint {
+            txid: Txid::from_byte_array([0xab; 32]),
+            vout: 0,
+        },
+        script_sig: ScriptBuf::new(),
+        sequence: Sequence::MAX,
+        witness: Witness::new(),
+    });
+    let outpoint = OutPoint {
+        txid: funding_tx.compute_txid(),
+        vout: 0,
+    };
+    let channel = params
+        .verify_funding_tx(&funding_tx, outpoint)
+        .expect("failed to open channel");
+
+    let signer = PrivateKeySigner::new(payer);
+    let psbts = (1..=PAYMENTS)
+        .map(|i| {
+            let mut psbt = channel
+                .next_payment_to_total(Amount::from_sat_u32(i * 1_000), Amount::from_sat_u32(500))
+                .expect("failed to build payment");
+            channel
+                .sign_payment_as_payer(&mut psbt, &signer)
+                .expect("failed to sign payment");
+            psbt
+        })
+        .collect();
+
+    (channel, psbts)
+}
+
+fn verify(c: &mut Criterion) {
+    let (channel, psbts) = setup();
+
+    c.bench_function("payment_sighash", |b| {
+        let psbt = &psbts[0];
+        let witness_script = psbt.inputs[0]
+            .witness_script
+            .as_ref()
+            .expect("payment must carry the witness script");
+        b.iter(|| {
+            SighashCache::new(&psbt.unsigned_tx)
+                .p2wsh_signature_hash(
+                    0,
+                    witness_script,
+                    Amount::from_sat_u32(1_000_000),
+                    EcdsaSighashType::All,
+                )
+                .expect("input 0 must exist")
+        })
+    });
+
+    c.bench_function("verify_payment_psbt_each", |b| {
+        b.iter(|| {
+            for psbt in &psbts {
+                channel
+                    .verify_payment_psbt(psbt)
+                    .expect("payment must verify");
+            }
+        })
+    });
+
+    c.bench_function("verify_payment_chain", |b| {
+        b.iter(|| {
+            channel
+                .verify_payment_chain(&psbts)
+                .expect("chain must verify")
+        })
+    });
+}
+
+criterion_group!(benches, verify);
+criterion_main!(benches);
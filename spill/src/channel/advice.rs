@@ -0,0 +1,150 @@
+use std::time::{Duration, SystemTime};
+
+use bitcoin::{Amount, BlockHeight, BlockMtp, FeeRate, primitives::relative};
+
+use crate::{Channel, channel::backend::ChannelBackend};
+
+/// Chain and fee conditions considered by [`Channel::close_advice`].
+///
+/// Heights and median time past values must come from a trusted chain
+/// source. The median time past values are only used for channels with a
+/// time-based refund lock time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CloseContext {
+    /// Height of the current chain tip.
+    pub tip_height: u32,
+    /// Median time past of the current chain tip.
+    pub tip_mtp: u32,
+    /// Height of the block that confirmed the funding transaction.
+    pub funding_height: u32,
+    /// Median time past of the block that confirmed the funding transaction.
+    pub funding_mtp: u32,
+    /// Fee rate expected for the closing transaction.
+    pub feerate: FeeRate,
+}
+
+/// Average block interval assumed to convert a block-based refund lock time
+/// into a duration.
+const BLOCK_INTERVAL: Duration = Duration::from_secs(600);
+
+/// Recommended action for a channel, returned by [`Channel::close_advice`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CloseRecommendation {
+    /// The channel can still accept payments and the refund has not matured.
+    KeepOpen,
+    /// The payee should close the channel with the latest payment.
+    CloseCooperatively(CloseReason),
+    /// Nothing was paid and the refund has matured, so the payer should
+    /// reclaim the funds with a refund.
+    Refund,
+}
+
+/// Reason for recommending a cooperative close.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CloseReason {
+    /// The refund lock time has passed, so the payer can reclaim the whole
+    /// capacity at any time.
+    RefundMatured,
+    /// The remaining capacity cannot cover another payment plus the close fee.
+    Exhausted {
+        /// Estimated fee of the closing transaction.
+        close_fee: Amount,
+    },
+}
+
+impl<B: ChannelBackend + Clone> Channel<B> {
+    /// Recommends whether to keep the channel open, close it or refund it.
+    ///
+    /// The recommendation is based on, in order:
+    /// - Refund maturity: once the refund lock time has passed, a channel
+    ///   with payments must be closed before the payer refunds it, and a
+    ///   channel without payments can only be refunded.
+    /// - Economic viability: a channel whose remaining capacity cannot pay
+    ///   for another payment plus the estimated close fee at
+    ///   `context.feerate` is exhausted (see [`Channel::is_exhausted`]).
+    ///
+    /// A tip below the funding height is treated as an immature refund.
+    pub fn close_advice(&self, context: CloseContext) -> CloseRecommendation {
+        let refund_matured = self
+            .params
+            .refund_lock_time
+            .is_satisfied_by(
+                BlockHeight::from_u32(context.tip_height),
+                BlockMtp::from_u32(context.tip_mtp),
+                BlockHeight::from_u32(context.funding_height),
+                BlockMtp::from_u32(context.funding_mtp),
+            )
+            .unwrap_or(false);
+
+        if refund_matured {
+            if self.sent == Amount::ZERO {
+                return CloseRecommendation::Refund;
+            }
+            return CloseRecommendation::CloseCooperatively(CloseReason::RefundMatured);
+        }
+
+        let close_fee = self
+            .params
+            .estimate_lifecycle_cost(context.feerate, 0)
+            .close_fee;
+        if self.is_exhausted(close_fee) {
+            return CloseRecommendation::CloseCooperatively(CloseReason::Exhausted { close_fee });
+        }
+
+        CloseRecommendation::KeepOpen
+    }
+
+    /// Returns when the channel was opened.
+    ///
+    /// This is the local time at which the funding transaction was verified
+    /// with [`ChannelParams::verify_funding_tx`], unless overridden with
+    /// [`Channel::with_opened_at`]. Splicing in or reducing the capacity
+    /// moves the channel to a new funding output, whose refund lock time
+    /// starts over, so it resets the opening time. It is part of the
+    /// channel backup.
+    ///
+    /// [`ChannelParams::verify_funding_tx`]: crate::ChannelParams::verify_funding_tx
+    pub fn opened_at(&self) -> SystemTime {
+        self.opened_at
+    }
+
+    /// Sets when the channel was opened.
+    ///
+    /// Used to restore the opening time of a channel resumed with
+    /// [`ChannelParams::resume_channel`], which otherwise counts as opened
+    /// when resumed.
+    ///
+    /// [`ChannelParams::resume_channel`]: crate::ChannelParams::resume_channel
+    pub fn with_opened_at(mut self, opened_at: SystemTime) -> Self {
+        self.opened_at = opened_at;
+        self
+    }
+
+    /// Returns how long the channel has been open at `now`.
+    ///
+    /// Returns zero if `now` is before [`Channel::opened_at`].
+    pub fn age(&self, now: SystemTime) -> Duration {
+        now.duration_since(self.opened_at).unwrap_or_default()
+    }
+
+    /// Returns whether the channel should be closed because the refund lock
+    /// time is less than `safety_margin` away at `now`.
+    ///
+    /// Once the refund lock time passes, the payer can reclaim the whole
+    /// capacity, so the payee must close the channel with the latest payment
+    /// well before. The refund delay is measured from the opening time
+    /// rather than from the funding confirmation, and block-based lock times
+    /// are converted assuming one block every ten minutes, so this is only
+    /// an estimate. [`Channel::close_advice`] gives an exact answer from
+    /// chain data.
+    pub fn should_close_before_refund(&self, now: SystemTime, safety_margin: Duration) -> bool {
+        let refund_delay = match self.params.refund_lock_time {
+            relative::LockTime::Blocks(blocks) => BLOCK_INTERVAL * u32::from(blocks.to_height()),
+            relative::LockTime::Time(time) => Duration::from_secs(u64::from(time.to_seconds())),
+        };
+
+        self.age(now).saturating_add(safety_margin) >= refund_delay
+    }
+}
@@ -1,6 +1,9 @@
-use bitcoin::{Amount, Psbt, PublicKey, ScriptPubKeyBuf, TxOut, primitives::relative};
+use bitcoin::{
+    Amount, EcdsaSighashType, Psbt, PublicKey, ScriptPubKeyBuf, TxOut, Witness,
+    primitives::relative, secp256k1::Message,
+};
 
-use crate::SpillError;
+use crate::{ChannelKind, SpendKind, SpillError};
 
 /// Abstraction for different channel implementations (e.g., SegWit, Taproot).
 ///
@@ -12,23 +15,30 @@ use crate::SpillError;
 /// Implementing this trait allows `ChannelParams` and `Channel` to remain
 /// agnostic to the underlying script mechanics (e.g., P2WSH vs P2TR).
 pub trait ChannelBackend {
+    /// Returns the funding script template implemented by this backend.
+    fn kind(&self) -> ChannelKind;
+
     /// Builds the funding `script_pubkey` for this channel type.
     ///
     /// This method constructs the locking script that defines the channel’s
     /// spending conditions (payment path and refund path) according to the
-    /// backend’s rules.
+    /// backend’s rules. The payment path requires `threshold` signatures from
+    /// `cooperative_keys`, which are given in script order (payer, payee, then
+    /// any additional cosigners).
     fn script_pubkey(
         &mut self,
         payer: &PublicKey,
-        payee: &PublicKey,
+        cooperative_keys: &[PublicKey],
+        threshold: usize,
         refund_lock_time: relative::LockTime,
     ) -> Result<ScriptPubKeyBuf, SpillError>;
 
     /// Populates backend-specific fields in the funding PSBT.
     ///
     /// This method fills any script, witness, or proprietary fields required
-    /// for the funding transaction to be valid under this backend.
-    fn populate_funding_psbt(&self, psbt: &mut Psbt);
+    /// for the funding transaction to be valid under this backend. The
+    /// funding output is the output at index `vout`.
+    fn populate_funding_psbt(&self, psbt: &mut Psbt, vout: usize);
 
     /// Populates backend-specific fields in the refund PSBT.
     ///
@@ -48,17 +58,47 @@ pub trait ChannelBackend {
     /// to the payee outside the channel.
     fn payee_script(&self, payee: &PublicKey) -> Result<ScriptPubKeyBuf, SpillError>;
 
-    /// Verifies that a payment PSBT is valid under this backend.
+    /// Verifies the backend-specific structure of a payment PSBT.
     ///
-    /// Checks that the transaction structure, outputs, and amounts
-    /// respect the channel rules and do not exceed the channel capacity.
-    fn verify_payment(
+    /// Checks that the script or witness data required to spend the
+    /// channel matches the channel's funding script.
+    fn verify_payment_structure(&self, psbt: &Psbt) -> Result<(), SpillError>;
+
+    /// Computes the sighash signed by the channel keys on the funding input
+    /// of a payment or refund PSBT of value `capacity`.
+    fn signature_hash(
         &self,
         psbt: &Psbt,
-        payer: &PublicKey,
+        capacity: Amount,
+        sighash_type: EcdsaSighashType,
+    ) -> Message;
+
+    /// Verifies a signature by `public_key` on a payment PSBT.
+    ///
+    /// Checks that the signature is present, uses an accepted sighash
+    /// type and is valid for the funding input of value `capacity`. The
+    /// signature does not depend on the spending branch, so this is also
+    /// used to verify refund signatures.
+    fn verify_payment_signature(
+        &self,
+        psbt: &Psbt,
+        public_key: &PublicKey,
         capacity: Amount,
     ) -> Result<(), SpillError>;
 
+    /// Returns the maximum serialized size, in bytes, of a finalized payment
+    /// witness carrying `threshold` signatures.
+    fn payment_witness_size(&self, threshold: usize) -> usize;
+
+    /// Returns the maximum serialized size, in bytes, of a finalized refund witness.
+    fn refund_witness_size(&self) -> usize;
+
+    /// Classifies a witness spending the funding output by the branch of
+    /// the funding script it executes.
+    ///
+    /// Returns `None` if the witness does not spend this channel's funding script.
+    fn classify_spend(&self, witness: &Witness) -> Option<SpendKind>;
+
     /// Finalizes the refund PSBT.
     ///
     /// Completes any backend-specific witness or script data
@@ -69,11 +109,12 @@ pub trait ChannelBackend {
     ///
     /// Completes any backend-specific witness or script data
     /// required to produce a fully valid payment transaction, ready to be broadcast.
+    /// Signatures from `threshold` of the `cooperative_keys` are required.
     fn finalize_payment_tx(
         &self,
         psbt: &mut Psbt,
-        payer: &PublicKey,
-        payee: &PublicKey,
+        cooperative_keys: &[PublicKey],
+        threshold: usize,
     ) -> Result<(), SpillError>;
 }
 
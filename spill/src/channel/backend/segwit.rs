@@ -1,13 +1,21 @@
+use std::time::{Duration, UNIX_EPOCH};
+
 use bitcoin::{
-    Amount, EcdsaSighashType, Psbt, PublicKey, ScriptPubKeyBuf, TxOut, Witness, WitnessScriptBuf,
+    Amount, EcdsaSighashType, OutPoint, Psbt, PublicKey, ScriptPubKeyBuf, TxOut, Txid, Witness,
+    WitnessScript, WitnessScriptBuf,
+    bech32::{self, Bech32m, Hrp, primitives::decode::CheckedHrpstring},
     opcodes::all::{OP_CHECKMULTISIG, OP_CHECKSIG, OP_CSV, OP_DROP, OP_ELSE, OP_ENDIF, OP_IF},
     primitives::relative,
-    script::{self, ScriptBufExt, WitnessScriptExt},
+    psbt,
+    script::{self, Instruction, ScriptBufExt, ScriptExt, WScriptHash, WitnessScriptExt},
     secp256k1,
     sighash::SighashCache,
 };
 
-use crate::{FinalizeError, PaymentError, SpillError, channel::backend::ChannelBackend};
+use crate::{
+    BackupError, Channel, ChannelKind, ChannelParams, ConfigError, FinalizeError, PaymentError,
+    SpendKind, SpillError, channel::backend::ChannelBackend,
+};
 
 /// SegWit v0 (P2WSH) backend for the channel.
 ///
@@ -16,7 +24,8 @@ use crate::{FinalizeError, PaymentError, SpillError, channel::backend::ChannelBa
 /// as two spending paths:
 ///
 /// - **Cooperative payment path**:
-///   A 2-of-2 multisig between payer and payee.
+///   A 2-of-2 multisig between payer and payee (or, for threshold channels,
+///   an m-of-n multisig that also includes additional cosigners).
 ///   When both signatures are provided, the payee can claim
 ///   the latest signed payment.
 ///
@@ -34,42 +43,581 @@ impl SegwitBackend {
     }
 }
 
+impl ChannelParams<SegwitBackend> {
+    /// Reconstructs channel parameters from a funding witness script.
+    ///
+    /// Intended for recovery tools and third parties that only have the
+    /// on-chain P2WSH witness script. The payer key, cooperative keys,
+    /// threshold and refund lock time are extracted from the script, which
+    /// must exactly match the layout produced by [`SegwitBackend`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `SpillError::Config(ConfigError::UnrecognizedFundingScript)` if the
+    /// script doesn't match the expected template, or any error returned by
+    /// [`ChannelParams::new_threshold`].
+    pub fn from_funding_script(
+        script: &WitnessScript,
+        capacity: Amount,
+    ) -> Result<ChannelParams<SegwitBackend>, SpillError> {
+        let template = parse_funding_script(script)?;
+
+        let [payer, payee, cosigners @ ..] = template.cooperative_keys.as_slice() else {
+            return Err(ConfigError::UnrecognizedFundingScript.into());
+        };
+
+        if *payer != template.payer {
+            return Err(ConfigError::UnrecognizedFundingScript.into());
+        }
+
+        ChannelParams::new_threshold(
+            *payer,
+            *payee,
+            cosigners.to_vec(),
+            template.threshold,
+            capacity,
+            template.refund_lock_time,
+            SegwitBackend::new(),
+        )
+    }
+
+    /// Returns the hash of the funding witness script.
+    ///
+    /// This is the witness program of the P2WSH funding output returned by
+    /// [`ChannelParams::script_pubkey`], exposed for tooling that builds or
+    /// checks funding outputs without re-hashing the witness script.
+    pub fn witness_script_hash(&self) -> WScriptHash {
+        self.backend
+            .funding_script
+            .as_ref()
+            .expect("Segwit funding_script: internal invariant violated (funding_script must be built at this point)")
+            .wscript_hash()
+            .expect("witness_script_hash: internal invariant violated (funding script must be valid p2wsh)")
+    }
+
+    /// Returns the script code committed to by signatures on the funding input.
+    ///
+    /// Under BIP143, the script code of a P2WSH input is its full witness
+    /// script, serialized with its length prefix, with no `OP_CODESEPARATOR`
+    /// handling since the funding script contains none. Both the payment and
+    /// refund branches use the same script code. Auditors can reproduce the
+    /// sighash of a channel transaction independently by feeding this script,
+    /// the channel capacity as the input amount and the signature's sighash
+    /// type to a BIP143 implementation, e.g.
+    /// [`SighashCache::p2wsh_signature_hash`] for input 0.
+    ///
+    /// # Examples
+    ///
+    /// Reproducing the sighash of a payment and checking the payer's
+    /// signature against it:
+    ///
+    /// ```
+    /// use bitcoin::{
+    ///     Amount, EcdsaSighashType, Network, OutPoint, PrivateKey, Sequence, TxIn, Txid,
+    ///     Witness, primitives::relative, script::ScriptBuf, secp256k1, sighash::SighashCache,
+    /// };
+    /// use spill::{ChannelParams, PrivateKeySigner, SegwitBackend, Signer};
+    ///
+    /// let key = |seed| {
+    ///     let secret = secp256k1::SecretKey::from_secret_bytes([seed; 32]).unwrap();
+    ///     PrivateKey::from_secp(secret, Network::Regtest)
+    /// };
+    /// let payer = PrivateKeySigner::new(key(1));
+    /// let capacity = Amount::from_sat_u32(40_000);
+    /// let params = ChannelParams::new(
+    ///     payer.public_key(),
+    ///     key(2).public_key(),
+    ///     capacity,
+    ///     relative::LockTime::from_height(10),
+    ///     SegwitBackend::new(),
+    /// )?;
+    ///
+    /// let mut funding_tx = params.funding_psbt().unsigned_tx;
+    /// funding_tx.inputs.push(TxIn {
+    ///     previous_output: OutPoint { txid: Txid::from_byte_array([0xab; 32]), vout: 0 },
+    ///     script_sig: ScriptBuf::new(),
+    ///     sequence: Sequence::MAX,
+    ///     witness: Witness::new(),
+    /// });
+    /// let outpoint = OutPoint { txid: funding_tx.compute_txid(), vout: 0 };
+    /// let channel = params.verify_funding_tx(&funding_tx, outpoint)?;
+    ///
+    /// let mut psbt = channel.next_payment(Amount::from_sat_u32(10_000), Amount::from_sat_u32(1_000))?;
+    /// channel.sign_payment_as_payer(&mut psbt, &payer)?;
+    ///
+    /// let sighash = SighashCache::new(&psbt.unsigned_tx)
+    ///     .p2wsh_signature_hash(0, params.sighash_scriptcode(), capacity, EcdsaSighashType::All)
+    ///     .expect("input 0 exists");
+    /// let msg = secp256k1::Message::from_digest(sighash.to_byte_array());
+    /// let sig = &psbt.inputs[0].partial_sigs[&payer.public_key()];
+    /// assert!(secp256k1::ecdsa::verify(&sig.signature, msg, &payer.public_key().to_inner()).is_ok());
+    /// # Ok::<(), spill::SpillError>(())
+    /// ```
+    pub fn sighash_scriptcode(&self) -> &WitnessScript {
+        self.backend
+            .funding_script
+            .as_ref()
+            .expect("Segwit funding_script: internal invariant violated (funding_script must be built at this point)")
+    }
+}
+
+impl Channel<SegwitBackend> {
+    /// Serializes the minimal state needed to resume and claim the channel.
+    ///
+    /// The backup is a compact, versioned binary blob intended for on-device
+    /// storage, laid out as follows (integers are little-endian):
+    ///
+    /// | Field                      | Size         |
+    /// |----------------------------|--------------|
+    /// | Format version (`3`)       | 1            |
+    /// | Funding txid               | 32           |
+    /// | Funding vout               | 4            |
+    /// | Capacity (sats)            | 8            |
+    /// | Amount sent (sats)         | 8            |
+    /// | Payer change (sats)        | 8            |
+    /// | Opened at (Unix seconds)   | 8            |
+    /// | Payee script length        | 2            |
+    /// | Payee script               | variable     |
+    /// | Witness script length      | 2            |
+    /// | Witness script             | variable     |
+    ///
+    /// The payee script is only stored if it was set with
+    /// [`ChannelParams::with_payee_script`], and has a length of zero
+    /// otherwise. The channel policy is local configuration and is not
+    /// included. Use [`ChannelParams::from_backup`] to restore the channel.
+    pub fn to_backup(&self) -> Vec<u8> {
+        let funding_script = self.params.backend.funding_script.as_ref().expect("Segwit funding_script: internal invariant violated (funding_script must be built at this point)");
+        let script_len = u16::try_from(funding_script.len())
+            .expect("to_backup: internal invariant violated (funding script must fit in u16)");
+
+        // Sub-second precision is dropped, and times before the Unix epoch are stored as the epoch.
+        let opened_at = self
+            .opened_at
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since_epoch| since_epoch.as_secs());
+
+        let payee_script = self
+            .params
+            .payee_script
+            .as_ref()
+            .map_or(&[][..], |script| script.as_bytes());
+        let payee_script_len = u16::try_from(payee_script.len())
+            .expect("to_backup: internal invariant violated (payee script must fit in u16)");
+
+        let mut bytes =
+            Vec::with_capacity(BACKUP_HEADER_SIZE + payee_script.len() + funding_script.len());
+        bytes.push(BACKUP_VERSION);
+        bytes.extend_from_slice(&self.funding_outpoint.txid.to_byte_array());
+        bytes.extend_from_slice(&self.funding_outpoint.vout.to_le_bytes());
+        bytes.extend_from_slice(&self.params.capacity.to_sat().to_le_bytes());
+        bytes.extend_from_slice(&self.sent.to_sat().to_le_bytes());
+        bytes.extend_from_slice(&self.payer_change.to_sat().to_le_bytes());
+        bytes.extend_from_slice(&opened_at.to_le_bytes());
+        bytes.extend_from_slice(&payee_script_len.to_le_bytes());
+        bytes.extend_from_slice(payee_script);
+        bytes.extend_from_slice(&script_len.to_le_bytes());
+        bytes.extend_from_slice(funding_script.as_bytes());
+
+        bytes
+    }
+
+    /// Serializes the channel backup as a bech32m string.
+    ///
+    /// Encodes the output of [`Channel::to_backup`] with the `spillbak`
+    /// human-readable part. Unlike the binary backup, the string can be
+    /// copied by hand, and its checksum detects transcription errors. Use
+    /// [`ChannelParams::from_bech32_backup`] to restore the channel.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SpillError::Backup(BackupError::TooLarge)` if the backup
+    /// exceeds the bech32m length limit, which can happen for channels with
+    /// many cosigners.
+    pub fn to_bech32_backup(&self) -> Result<String, SpillError> {
+        let bytes = self.to_backup();
+        bech32::encode::<Bech32m>(BACKUP_HRP, &bytes)
+            .map_err(|_| BackupError::TooLarge { size: bytes.len() }.into())
+    }
+}
+
+impl ChannelParams<SegwitBackend> {
+    /// Restores a channel from a backup produced by [`Channel::to_bech32_backup`].
+    ///
+    /// The string is matched case-insensitively, as allowed by bech32m.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SpillError::Backup(BackupError::InvalidEncoding)` if `backup`
+    /// is not a bech32m string with the `spillbak` human-readable part and a
+    /// valid checksum, or any error returned by [`ChannelParams::from_backup`].
+    pub fn from_bech32_backup(backup: &str) -> Result<Channel<SegwitBackend>, SpillError> {
+        let checked =
+            CheckedHrpstring::new::<Bech32m>(backup).map_err(|_| BackupError::InvalidEncoding)?;
+        if checked.hrp() != BACKUP_HRP {
+            return Err(BackupError::InvalidEncoding.into());
+        }
+
+        let bytes: Vec<u8> = checked.byte_iter().collect();
+        ChannelParams::from_backup(&bytes)
+    }
+
+    /// Restores a channel from a backup produced by [`Channel::to_backup`].
+    ///
+    /// The channel parameters are rebuilt from the backed-up funding script
+    /// with [`ChannelParams::from_funding_script`], using the default policy,
+    /// and the backed-up payee script, if any. Backups in the version 1
+    /// format, which has no opening time, are restored as opened now, and
+    /// backups in the version 1 and 2 formats, which have no payee script,
+    /// pay the payee to the default P2WPKH script.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SpillError::Backup` variant if:
+    /// - `UnsupportedVersion`: The backup uses an unknown format version.
+    /// - `Malformed`: The backup is truncated, has trailing data, holds
+    ///   inconsistent amounts or an unsupported payee script.
+    ///
+    /// Also returns any error returned by [`ChannelParams::from_funding_script`].
+    pub fn from_backup(bytes: &[u8]) -> Result<Channel<SegwitBackend>, SpillError> {
+        let (&version, rest) = bytes.split_first().ok_or(BackupError::Malformed)?;
+        if !(BACKUP_VERSION_V1..=BACKUP_VERSION).contains(&version) {
+            return Err(BackupError::UnsupportedVersion { version }.into());
+        }
+
+        let (txid, rest) = rest
+            .split_first_chunk::<32>()
+            .ok_or(BackupError::Malformed)?;
+        let (vout, rest) = rest
+            .split_first_chunk::<4>()
+            .ok_or(BackupError::Malformed)?;
+        let (capacity, rest) = rest
+            .split_first_chunk::<8>()
+            .ok_or(BackupError::Malformed)?;
+        let (sent, rest) = rest
+            .split_first_chunk::<8>()
+            .ok_or(BackupError::Malformed)?;
+        let (payer_change, rest) = rest
+            .split_first_chunk::<8>()
+            .ok_or(BackupError::Malformed)?;
+        let (opened_at, rest) = if version == BACKUP_VERSION_V1 {
+            (None, rest)
+        } else {
+            let (opened_at, rest) = rest
+                .split_first_chunk::<8>()
+                .ok_or(BackupError::Malformed)?;
+            (Some(u64::from_le_bytes(*opened_at)), rest)
+        };
+        let (payee_script, rest) = if version < BACKUP_VERSION_PAYEE_SCRIPT {
+            (None, rest)
+        } else {
+            let (len, rest) = rest
+                .split_first_chunk::<2>()
+                .ok_or(BackupError::Malformed)?;
+            let (payee_script, rest) = rest
+                .split_at_checked(usize::from(u16::from_le_bytes(*len)))
+                .ok_or(BackupError::Malformed)?;
+            let payee_script = (!payee_script.is_empty())
+                .then(|| ScriptPubKeyBuf::from_bytes(payee_script.to_vec()));
+            (payee_script, rest)
+        };
+        let (script_len, script) = rest
+            .split_first_chunk::<2>()
+            .ok_or(BackupError::Malformed)?;
+
+        if script.len() != usize::from(u16::from_le_bytes(*script_len)) {
+            return Err(BackupError::Malformed.into());
+        }
+
+        let amount = |bytes: &[u8; 8]| {
+            Amount::from_sat(u64::from_le_bytes(*bytes)).map_err(|_| BackupError::Malformed)
+        };
+        let capacity = amount(capacity)?;
+        let sent = amount(sent)?;
+        let payer_change = amount(payer_change)?;
+
+        if (sent + payer_change)
+            .into_result()
+            .map_or(true, |total| total > capacity)
+        {
+            return Err(BackupError::Malformed.into());
+        }
+
+        let funding_script = WitnessScriptBuf::from_bytes(script.to_vec());
+        let mut params = ChannelParams::from_funding_script(&funding_script, capacity)?;
+        if let Some(payee_script) = payee_script {
+            params = params
+                .with_payee_script(payee_script)
+                .map_err(|_| BackupError::Malformed)?;
+        }
+
+        let channel = params.resume_channel(
+            OutPoint {
+                txid: Txid::from_byte_array(*txid),
+                vout: u32::from_le_bytes(*vout),
+            },
+            params.expected_funding_output(),
+            sent,
+            payer_change,
+        )?;
+
+        match opened_at {
+            Some(seconds) => {
+                let opened_at = UNIX_EPOCH
+                    .checked_add(Duration::from_secs(seconds))
+                    .ok_or(BackupError::Malformed)?;
+                Ok(channel.with_opened_at(opened_at))
+            }
+            None => Ok(channel),
+        }
+    }
+}
+
+/// Current version of the channel backup format.
+const BACKUP_VERSION: u8 = 3;
+
+/// First version of the channel backup format storing the payee script.
+const BACKUP_VERSION_PAYEE_SCRIPT: u8 = 3;
+
+/// Version of the channel backup format without the opening time, still
+/// accepted by [`ChannelParams::from_backup`].
+const BACKUP_VERSION_V1: u8 = 1;
+
+/// Human-readable part of bech32m-encoded channel backups.
+const BACKUP_HRP: Hrp = Hrp::parse_unchecked("spillbak");
+
+/// Size of the fixed-length part of a channel backup.
+const BACKUP_HEADER_SIZE: usize = 1 + 32 + 4 + 8 + 8 + 8 + 8 + 2 + 2;
+
+/// Maximum size of a DER-encoded ECDSA signature followed by its sighash byte.
+///
+/// Witness size estimates use this size for every signature, including
+/// low-R ones, which are at most 71 bytes.
+const MAX_SIGNATURE_SIZE: usize = 73;
+
+/// Keys and lock time encoded in a SegWit channel funding script.
+struct FundingScriptTemplate {
+    payer: PublicKey,
+    cooperative_keys: Vec<PublicKey>,
+    threshold: usize,
+    refund_lock_time: relative::LockTime,
+}
+
+/// Returns whether `sighash_type` is one of the ALL-family types accepted for
+/// channel signatures.
+fn is_all_sighash(sighash_type: EcdsaSighashType) -> bool {
+    sighash_type == EcdsaSighashType::All || sighash_type == EcdsaSighashType::AllPlusAnyoneCanPay
+}
+
+/// Checks that the sighash type recorded in a PSBT input, if any, is a
+/// defined ECDSA sighash type.
+///
+/// Signatures only ever carry a defined sighash type, but the PSBT field is
+/// a raw `u32` that an external updater may have set to any value.
+fn check_recorded_sighash(input: &psbt::Input) -> Result<(), SpillError> {
+    if let Some(sighash_type) = input.sighash_type
+        && sighash_type.ecdsa_hash_ty().is_err()
+    {
+        return Err(FinalizeError::UndefinedSighashType {
+            sighash_type: sighash_type.to_u32(),
+        }
+        .into());
+    }
+
+    Ok(())
+}
+
+/// Serializes a signature by `public_key` for a finalized witness.
+///
+/// The signature is encoded as strict DER followed by its sighash byte.
+/// Signatures with a sighash type outside the ALL family would not match
+/// what verification accepted, and signatures with a high S value, e.g.
+/// inserted by an external signer, would make the witness non-standard,
+/// so both are rejected.
+fn witness_signature(
+    sig: &bitcoin::ecdsa::Signature,
+    public_key: &PublicKey,
+) -> Result<Vec<u8>, SpillError> {
+    if !is_all_sighash(sig.sighash_type) {
+        return Err(FinalizeError::InconsistentSighash {
+            public_key: *public_key,
+        }
+        .into());
+    }
+
+    let mut normalized = sig.signature;
+    normalized.normalize_s();
+    if normalized != sig.signature {
+        return Err(FinalizeError::NonCanonicalSignature {
+            public_key: *public_key,
+        }
+        .into());
+    }
+
+    let mut bytes = sig.signature.serialize_der().to_vec();
+    bytes.push(sig.sighash_type.to_u32() as u8);
+    debug_assert!(
+        bitcoin::ecdsa::Signature::from_slice(&bytes).is_ok_and(|parsed| parsed == *sig),
+        "witness_signature: encoding must parse back as strict DER with a defined sighash byte"
+    );
+    Ok(bytes)
+}
+
+/// Builds the channel's witness script:
+///
+/// `OP_IF <m> <keys...> <n> OP_CHECKMULTISIG OP_ELSE <lock time> OP_CSV OP_DROP <payer> OP_CHECKSIG OP_ENDIF`
+fn build_funding_script(
+    payer: &PublicKey,
+    cooperative_keys: &[PublicKey],
+    threshold: usize,
+    refund_lock_time: relative::LockTime,
+) -> WitnessScriptBuf {
+    let mut builder = script::Builder::new()
+        .push_opcode(OP_IF)
+        .push_int(threshold as i32)
+        .expect(
+            "Segwit funding_script: internal invariant violated (integer must be valid in scipt)",
+        );
+
+    for key in cooperative_keys {
+        debug_assert_eq!(
+            key.to_bytes().len(),
+            33,
+            "Segwit funding_script: internal invariant violated (keys must be compressed)"
+        );
+        builder = builder.push_key(*key);
+    }
+
+    builder
+        .push_int(cooperative_keys.len() as i32)
+        .expect(
+            "Segwit funding_script: internal invariant violated (integer must be valid in scipt)",
+        )
+        .push_opcode(OP_CHECKMULTISIG)
+        .push_opcode(OP_ELSE)
+        .push_relative_lock_time(refund_lock_time)
+        .push_opcode(OP_CSV)
+        .push_opcode(OP_DROP)
+        .push_key(*payer)
+        .push_opcode(OP_CHECKSIG)
+        .push_opcode(OP_ENDIF)
+        .into_script()
+}
+
+/// Parses a funding witness script built by [`build_funding_script`].
+///
+/// The extracted values are used to rebuild the script, which must be
+/// byte-for-byte identical to the input, so any deviation from the template
+/// is rejected: extra or missing opcodes, a different branch or time lock
+/// opcode, non-minimal pushes, a key count not matching the keys or a
+/// threshold the multisig can never satisfy.
+fn parse_funding_script(script: &WitnessScript) -> Result<FundingScriptTemplate, SpillError> {
+    let instructions = script
+        .instructions_minimal()
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| ConfigError::UnrecognizedFundingScript)?;
+
+    let key_count = instructions
+        .iter()
+        .position(|i| *i == Instruction::Op(OP_CHECKMULTISIG))
+        .and_then(|pos| pos.checked_sub(3))
+        .ok_or(ConfigError::UnrecognizedFundingScript)?;
+
+    let [Instruction::Op(OP_IF), threshold, keys @ ..] = &instructions[..key_count + 2] else {
+        return Err(ConfigError::UnrecognizedFundingScript.into());
+    };
+
+    let [
+        _key_count,
+        Instruction::Op(OP_CHECKMULTISIG),
+        Instruction::Op(OP_ELSE),
+        lock_time,
+        Instruction::Op(OP_CSV),
+        Instruction::Op(OP_DROP),
+        Instruction::PushBytes(payer),
+        Instruction::Op(OP_CHECKSIG),
+        Instruction::Op(OP_ENDIF),
+    ] = &instructions[key_count + 2..]
+    else {
+        return Err(ConfigError::UnrecognizedFundingScript.into());
+    };
+
+    let parse_key = |bytes: &script::PushBytes| {
+        PublicKey::from_slice(bytes.as_bytes()).map_err(|_| ConfigError::UnrecognizedFundingScript)
+    };
+
+    let cooperative_keys = keys
+        .iter()
+        .map(|key| match key {
+            Instruction::PushBytes(bytes) => parse_key(bytes),
+            Instruction::Op(_) => Err(ConfigError::UnrecognizedFundingScript),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let threshold = threshold
+        .script_num()
+        .and_then(|n| usize::try_from(n).ok())
+        .filter(|n| (1..=cooperative_keys.len()).contains(n))
+        .ok_or(ConfigError::UnrecognizedFundingScript)?;
+
+    let refund_lock_time = lock_time
+        .script_num()
+        .and_then(|n| u32::try_from(n).ok())
+        .and_then(|n| relative::LockTime::from_consensus(n).ok())
+        .ok_or(ConfigError::UnrecognizedFundingScript)?;
+
+    let template = FundingScriptTemplate {
+        payer: parse_key(payer)?,
+        cooperative_keys,
+        threshold,
+        refund_lock_time,
+    };
+
+    let rebuilt = build_funding_script(
+        &template.payer,
+        &template.cooperative_keys,
+        template.threshold,
+        template.refund_lock_time,
+    );
+
+    if rebuilt.as_bytes() != script.as_bytes() {
+        return Err(ConfigError::UnrecognizedFundingScript.into());
+    }
+
+    Ok(template)
+}
+
 impl ChannelBackend for SegwitBackend {
+    fn kind(&self) -> ChannelKind {
+        ChannelKind::CsvP2wsh
+    }
+
     fn script_pubkey(
         &mut self,
         payer: &PublicKey,
-        payee: &PublicKey,
+        cooperative_keys: &[PublicKey],
+        threshold: usize,
         refund_lock_time: relative::LockTime,
     ) -> Result<ScriptPubKeyBuf, SpillError> {
-        let funding_script: WitnessScriptBuf = script::Builder::new()
-            .push_opcode(OP_IF)
-            .push_int(2)
-            .expect(
-                "Segwit funding_script: internal invariant violated (integer must be valid in scipt)",
-            )
-            .push_key(*payer)
-            .push_key(*payee)
-            .push_int(2)
-            .expect(
-                "Segwit funding_script: internal invariant violated (integer must be valid in scipt)",
-            )
-            .push_opcode(OP_CHECKMULTISIG)
-            .push_opcode(OP_ELSE)
-            .push_relative_lock_time(refund_lock_time)
-            .push_opcode(OP_CSV)
-            .push_opcode(OP_DROP)
-            .push_key(*payer)
-            .push_opcode(OP_CHECKSIG)
-            .push_opcode(OP_ENDIF)
-            .into_script();
+        let funding_script =
+            build_funding_script(payer, cooperative_keys, threshold, refund_lock_time);
+
+        debug_assert!(
+            parse_funding_script(&funding_script).is_ok_and(|template| {
+                template.payer == *payer
+                    && template.cooperative_keys == cooperative_keys
+                    && template.threshold == threshold
+                    && template.refund_lock_time == refund_lock_time
+            }),
+            "script_pubkey: internal invariant violated (funding script must parse back to its parameters)"
+        );
 
         self.funding_script = Some(funding_script.clone());
 
         Ok(funding_script.to_p2wsh().expect("Segwit funding_script: internal invariant violated (funding script must be valid p2wsh)"))
     }
 
-    fn populate_funding_psbt(&self, psbt: &mut bitcoin::Psbt) {
-        psbt.outputs[0].witness_script = Some(self.funding_script.clone().expect("Segwit funding_script: internal invariant violated (funding_script must be built at this point)"));
+    fn populate_funding_psbt(&self, psbt: &mut bitcoin::Psbt, vout: usize) {
+        psbt.outputs[vout].witness_script = Some(self.funding_script.clone().expect("Segwit funding_script: internal invariant violated (funding_script must be built at this point)"));
     }
 
     fn populate_refund_psbt(&self, psbt: &mut bitcoin::Psbt, funding_utxo: TxOut) {
@@ -86,12 +634,7 @@ impl ChannelBackend for SegwitBackend {
         Ok(ScriptPubKeyBuf::new_p2wpkh(payee.wpubkey_hash()?))
     }
 
-    fn verify_payment(
-        &self,
-        psbt: &Psbt,
-        payer: &PublicKey,
-        capacity: Amount,
-    ) -> Result<(), SpillError> {
+    fn verify_payment_structure(&self, psbt: &Psbt) -> Result<(), SpillError> {
         let witness_script = psbt.inputs[0]
             .witness_script
             .clone()
@@ -99,51 +642,124 @@ impl ChannelBackend for SegwitBackend {
 
         let witness_script = WitnessScriptBuf::from_bytes(witness_script.into_bytes());
 
-        if witness_script != self.funding_script.clone().expect("Segwit funding_script: internal invariant violated (funding_script must be built at this point)") {
+        let funding_script = self.funding_script.as_ref().expect("Segwit funding_script: internal invariant violated (funding_script must be built at this point)");
+
+        if witness_script != *funding_script {
+            // A script differing only in its refund lock time usually means the
+            // params were altered after funding, so report that specifically.
+            let expected = parse_funding_script(funding_script).expect(
+                "Segwit funding_script: internal invariant violated (funding_script must parse)",
+            );
+            if let Ok(found) = parse_funding_script(&witness_script)
+                && found.payer == expected.payer
+                && found.cooperative_keys == expected.cooperative_keys
+                && found.threshold == expected.threshold
+            {
+                return Err(PaymentError::LocktimeMismatch {
+                    expected: expected.refund_lock_time,
+                    found: found.refund_lock_time,
+                }
+                .into());
+            }
+
             return Err(PaymentError::WitnessScriptMismatch.into());
         }
 
-        let sig = psbt.inputs[0]
-            .partial_sigs
-            .get(payer)
-            .ok_or(PaymentError::MissingSignature)?;
-
-        if sig.sighash_type != EcdsaSighashType::All
-            && sig.sighash_type != EcdsaSighashType::AllPlusAnyoneCanPay
-        {
-            return Err(PaymentError::InvalidSighash.into());
-        }
+        Ok(())
+    }
 
+    fn signature_hash(
+        &self,
+        psbt: &Psbt,
+        capacity: Amount,
+        sighash_type: EcdsaSighashType,
+    ) -> secp256k1::Message {
         let mut cache = SighashCache::new(&psbt.unsigned_tx);
         let sighash = cache
             .p2wsh_signature_hash(
                 0,
                 self.funding_script.as_ref().expect("Segwit funding_script: internal invariant violated (funding_script must be built at this point)"),
                 capacity,
-                sig.sighash_type,
+                sighash_type,
             )
-            .expect("verify_payment_psbt: internal invariant (sign input 0)");
+            .expect("signature_hash: internal invariant (sign input 0)");
+
+        secp256k1::Message::from_digest(sighash.to_byte_array())
+    }
+
+    fn verify_payment_signature(
+        &self,
+        psbt: &Psbt,
+        public_key: &PublicKey,
+        capacity: Amount,
+    ) -> Result<(), SpillError> {
+        let sig = psbt.inputs[0]
+            .partial_sigs
+            .get(public_key)
+            .ok_or(PaymentError::MissingSignature)?;
 
-        let msg = secp256k1::Message::from_digest(sighash.to_byte_array());
+        if !is_all_sighash(sig.sighash_type) {
+            return Err(PaymentError::InvalidSighash.into());
+        }
 
-        if secp256k1::ecdsa::verify(&sig.signature, msg, &payer.to_inner()).is_err() {
+        let msg = self.signature_hash(psbt, capacity, sig.sighash_type);
+
+        if secp256k1::ecdsa::verify(&sig.signature, msg, &public_key.to_inner()).is_err() {
             return Err(PaymentError::InvalidSignature.into());
         }
 
         Ok(())
     }
 
+    fn payment_witness_size(&self, threshold: usize) -> usize {
+        let funding_script = self.funding_script.as_ref().expect("Segwit funding_script: internal invariant violated (funding_script must be built at this point)");
+
+        let mut witness = Witness::new();
+        witness.push([]);
+        for _ in 0..threshold {
+            witness.push([0; MAX_SIGNATURE_SIZE]);
+        }
+        witness.push([1]);
+        witness.push(funding_script.as_bytes());
+
+        witness.size()
+    }
+
+    fn refund_witness_size(&self) -> usize {
+        let funding_script = self.funding_script.as_ref().expect("Segwit funding_script: internal invariant violated (funding_script must be built at this point)");
+
+        let mut witness = Witness::new();
+        witness.push([0; MAX_SIGNATURE_SIZE]);
+        witness.push([]);
+        witness.push(funding_script.as_bytes());
+
+        witness.size()
+    }
+
+    fn classify_spend(&self, witness: &Witness) -> Option<SpendKind> {
+        let funding_script = self.funding_script.as_ref().expect("Segwit funding_script: internal invariant violated (funding_script must be built at this point)");
+
+        if witness.last()? != funding_script.as_bytes() {
+            return None;
+        }
+
+        match witness.get_back(1)? {
+            [1] => Some(SpendKind::Payment),
+            [] => Some(SpendKind::Refund),
+            _ => None,
+        }
+    }
+
     fn finalize_refund_tx(&self, psbt: &mut Psbt, payer: &PublicKey) -> Result<(), SpillError> {
         let mut witness = Witness::new();
         let input = &mut psbt.inputs[0];
+        check_recorded_sighash(input)?;
 
         let sig_payer = input
             .partial_sigs
             .get(payer)
             .ok_or(FinalizeError::MissingSignature { public_key: *payer })?;
-        let mut sig_payer_bytes = sig_payer.signature.serialize_der().to_vec();
-        sig_payer_bytes.push(sig_payer.sighash_type.to_u32() as u8);
-        witness.push(sig_payer_bytes);
+        witness.push(witness_signature(sig_payer, payer)?);
 
         witness.push(vec![]); // OP_FALSE take OP_ELSE branch
 
@@ -162,29 +778,44 @@ impl ChannelBackend for SegwitBackend {
     fn finalize_payment_tx(
         &self,
         psbt: &mut Psbt,
-        payer: &PublicKey,
-        payee: &PublicKey,
+        cooperative_keys: &[PublicKey],
+        threshold: usize,
     ) -> Result<(), SpillError> {
         let mut witness = Witness::new();
-        witness.push(vec![]);
+        witness.push(vec![]); // OP_CHECKMULTISIG dummy element
 
         let input = &mut psbt.inputs[0];
+        check_recorded_sighash(input)?;
+
+        // OP_CHECKMULTISIG requires signatures in the same order as the keys
+        // appear in the script, so walk the keys in script order.
+        debug_assert!(
+            self.funding_script.as_ref().is_none_or(|script| {
+                parse_funding_script(script)
+                    .is_ok_and(|template| template.cooperative_keys == cooperative_keys)
+            }),
+            "finalize_payment_tx: cooperative keys must be in funding script order"
+        );
+        let mut signed = 0;
+        for key in cooperative_keys {
+            if signed == threshold {
+                break;
+            }
+
+            let Some(sig) = input.partial_sigs.get(key) else {
+                continue;
+            };
+            witness.push(witness_signature(sig, key)?);
+            signed += 1;
+        }
 
-        let sig_payer = input
-            .partial_sigs
-            .get(payer)
-            .ok_or(FinalizeError::MissingSignature { public_key: *payer })?;
-        let mut sig_payer_bytes = sig_payer.signature.serialize_der().to_vec();
-        sig_payer_bytes.push(sig_payer.sighash_type.to_u32() as u8);
-        witness.push(sig_payer_bytes);
-
-        let sig_payee = input
-            .partial_sigs
-            .get(payee)
-            .ok_or(FinalizeError::MissingSignature { public_key: *payee })?;
-        let mut sig_payee_bytes = sig_payee.signature.serialize_der().to_vec();
-        sig_payee_bytes.push(sig_payee.sighash_type.to_u32() as u8);
-        witness.push(sig_payee_bytes);
+        if signed < threshold {
+            let public_key = *cooperative_keys
+                .iter()
+                .find(|k| !input.partial_sigs.contains_key(k))
+                .expect("finalize_payment_tx: internal invariant violated (a signature must be missing)");
+            return Err(FinalizeError::MissingSignature { public_key }.into());
+        }
 
         witness.push(vec![1]); // OP_TRUE take OP_IF branch
 
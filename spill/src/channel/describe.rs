@@ -0,0 +1,85 @@
+use core::fmt;
+
+use bitcoin::{EcdsaSighashType, OutPoint, Psbt, PublicKey, Sequence, TxOut, absolute};
+
+/// Human-readable summary of the fields of a payment PSBT.
+///
+/// Returned by [`describe_payment_psbt`] as a diagnostic aid when a payment
+/// fails verification. The summary is purely descriptive: it is built from
+/// whatever the PSBT contains, without checking it against any channel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaymentPsbtSummary {
+    /// Outpoint spent by the first input, if any.
+    pub outpoint: Option<OutPoint>,
+    /// Sequence of the first input, if any.
+    pub sequence: Option<Sequence>,
+    /// Lock time of the transaction.
+    pub lock_time: absolute::LockTime,
+    /// Outputs of the transaction, in order.
+    pub outputs: Vec<TxOut>,
+    /// Public keys with a partial signature on the first input, together
+    /// with the sighash type of each signature.
+    pub signatures: Vec<(PublicKey, EcdsaSighashType)>,
+}
+
+/// Summarizes the fields of a payment PSBT relevant to channel verification.
+///
+/// Intended for inspecting a PSBT rejected by [`Channel::verify_payment_psbt`]
+/// (e.g. with `dbg!`) without destructuring it manually.
+///
+/// This function does not verify anything, it only extracts the fields.
+///
+/// [`Channel::verify_payment_psbt`]: crate::Channel::verify_payment_psbt
+pub fn describe_payment_psbt(psbt: &Psbt) -> PaymentPsbtSummary {
+    let input = psbt.unsigned_tx.inputs.first();
+
+    let signatures = psbt
+        .inputs
+        .first()
+        .map(|input| {
+            input
+                .partial_sigs
+                .iter()
+                .map(|(public_key, signature)| (*public_key, signature.sighash_type))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    PaymentPsbtSummary {
+        outpoint: input.map(|input| input.previous_output),
+        sequence: input.map(|input| input.sequence),
+        lock_time: psbt.unsigned_tx.lock_time,
+        outputs: psbt.unsigned_tx.outputs.clone(),
+        signatures,
+    }
+}
+
+impl fmt::Display for PaymentPsbtSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.outpoint {
+            Some(outpoint) => writeln!(f, "outpoint: {outpoint}")?,
+            None => writeln!(f, "outpoint: <none>")?,
+        }
+        match self.sequence {
+            Some(sequence) => writeln!(f, "sequence: {sequence}")?,
+            None => writeln!(f, "sequence: <none>")?,
+        }
+        writeln!(f, "lock time: {}", self.lock_time)?;
+
+        writeln!(f, "outputs:")?;
+        for (index, output) in self.outputs.iter().enumerate() {
+            writeln!(
+                f,
+                "  {index}: {} to {:x}",
+                output.amount, output.script_pubkey
+            )?;
+        }
+
+        write!(f, "signatures:")?;
+        for (public_key, sighash_type) in &self.signatures {
+            write!(f, "\n  {public_key} ({sighash_type})")?;
+        }
+
+        Ok(())
+    }
+}
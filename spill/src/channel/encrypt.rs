@@ -0,0 +1,62 @@
+use chacha20poly1305::{
+    AeadCore, ChaCha20Poly1305, KeyInit, Nonce,
+    aead::{Aead, OsRng},
+};
+
+use crate::{BackupError, Channel, ChannelParams, SegwitBackend, SpillError};
+
+/// Size of the random nonce prepended to an encrypted backup.
+const NONCE_SIZE: usize = 12;
+
+impl Channel<SegwitBackend> {
+    /// Serializes the channel backup encrypted with ChaCha20-Poly1305.
+    ///
+    /// Encrypts the output of [`Channel::to_backup`] under the 32-byte `key`
+    /// with a fresh random nonce, which is prepended to the ciphertext. The
+    /// authentication tag ensures a modified backup, or one decrypted with
+    /// the wrong key, is rejected rather than restored as garbage state. Use
+    /// [`ChannelParams::from_encrypted_backup`] to restore the channel.
+    ///
+    /// The key must be kept secret and must not be derived from a channel
+    /// key, since the backup is meant to be stored apart from the wallet.
+    pub fn to_encrypted_backup(&self, key: &[u8; 32]) -> Vec<u8> {
+        let cipher = ChaCha20Poly1305::new(key.into());
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = cipher.encrypt(&nonce, self.to_backup().as_slice()).expect(
+            "to_encrypted_backup: internal invariant violated (backup must fit in a single message)",
+        );
+
+        let mut bytes = Vec::with_capacity(NONCE_SIZE + ciphertext.len());
+        bytes.extend_from_slice(&nonce);
+        bytes.extend_from_slice(&ciphertext);
+        bytes
+    }
+}
+
+impl ChannelParams<SegwitBackend> {
+    /// Restores a channel from a backup produced by [`Channel::to_encrypted_backup`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SpillError::Backup` variant if:
+    /// - `Malformed`: `bytes` is too short to hold a nonce and an
+    ///   authentication tag.
+    /// - `DecryptionFailed`: `key` is wrong or the backup was modified.
+    ///
+    /// Also returns any error returned by [`ChannelParams::from_backup`].
+    pub fn from_encrypted_backup(
+        bytes: &[u8],
+        key: &[u8; 32],
+    ) -> Result<Channel<SegwitBackend>, SpillError> {
+        let (nonce, ciphertext) = bytes
+            .split_first_chunk::<NONCE_SIZE>()
+            .ok_or(BackupError::Malformed)?;
+
+        let cipher = ChaCha20Poly1305::new(key.into());
+        let backup = cipher
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| BackupError::DecryptionFailed)?;
+
+        ChannelParams::from_backup(&backup)
+    }
+}
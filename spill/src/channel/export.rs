@@ -0,0 +1,50 @@
+use bitcoin::{Amount, Psbt};
+
+use crate::{
+    Channel, ChannelParams, PaymentError, SpillError,
+    channel::{backend::ChannelBackend, payment::PaymentInfo},
+};
+
+impl<B: ChannelBackend + Clone> ChannelParams<B> {
+    /// Constructs the funding PSBT and serializes it to base64.
+    ///
+    /// See [`ChannelParams::funding_psbt`] for details on the returned PSBT.
+    pub fn funding_psbt_base64(&self) -> String {
+        self.funding_psbt().to_string()
+    }
+}
+
+impl<B: ChannelBackend + Clone> Channel<B> {
+    /// Constructs the next payment PSBT and serializes it to base64.
+    ///
+    /// See [`Channel::next_payment`] for details on the returned PSBT and errors.
+    pub fn payment_psbt_base64(&self, amount: Amount, fee: Amount) -> Result<String, SpillError> {
+        Ok(self.next_payment(amount, fee)?.to_string())
+    }
+
+    /// Constructs the refund PSBT and serializes it to base64.
+    ///
+    /// See [`Channel::refund_psbt`] for details on the returned PSBT.
+    pub fn refund_psbt_base64(&self) -> String {
+        self.refund_psbt().to_string()
+    }
+
+    /// Decodes a base64 payment PSBT and verifies it against the channel state.
+    ///
+    /// Returns the decoded PSBT together with its [`PaymentInfo`], so it can
+    /// later be passed to [`Channel::apply_payment`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `SpillError::Payment(PaymentError::InvalidEncoding)` if the string
+    /// is not a valid base64 PSBT, or any error returned by
+    /// [`Channel::verify_payment_psbt`].
+    pub fn verify_payment_psbt_base64(
+        &self,
+        psbt: &str,
+    ) -> Result<(Psbt, PaymentInfo), SpillError> {
+        let psbt: Psbt = psbt.parse().map_err(|_| PaymentError::InvalidEncoding)?;
+        let info = self.verify_payment_psbt(&psbt)?;
+        Ok((psbt, info))
+    }
+}
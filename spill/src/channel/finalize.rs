@@ -1,6 +1,6 @@
-use bitcoin::Psbt;
+use bitcoin::{Psbt, Transaction};
 
-use crate::{Channel, SpillError, channel::backend::ChannelBackend};
+use crate::{Channel, CloseError, FinalizeError, SpillError, channel::backend::ChannelBackend};
 
 impl<B: ChannelBackend + Clone> Channel<B> {
     /// Finalizes a refund PSBT for broadcast.
@@ -14,28 +14,130 @@ impl<B: ChannelBackend + Clone> Channel<B> {
     ///
     /// Returns `SpillError::Finalize` if:
     /// - `MissingSignature`: The payer's signature is missing from the PSBT.
+    /// - `InconsistentSighash`: The payer's signature is not ALL or ALL|ANYONECANPAY.
+    /// - `NonCanonicalSignature`: The payer's signature has a high S value.
+    /// - `UndefinedSighashType`: The PSBT input records an undefined sighash type.
     /// - `MissingWitnessScript`: The PSBT input lacks a witness script.
     pub fn finalize_refund_tx(&self, psbt: &mut Psbt) -> Result<(), SpillError> {
         self.params
             .backend
-            .finalize_refund_tx(psbt, &self.params.payer)
+            .finalize_refund_tx(psbt, &self.params.payer)?;
+
+        #[cfg(feature = "tracing")]
+        tracing::info!(channel_id = %self.funding_outpoint, "refund finalized");
+
+        Ok(())
     }
 
     /// Finalizes a payment PSBT for broadcast.
     ///
     /// Takes a mutable payment PSBT containing the payer's and payee's signatures
     /// and sets the proper witness for the payee to claim the last payment.
+    /// For channels with a threshold cooperative branch, signatures from any
+    /// `threshold` cooperative keys are used, preferring keys in script order.
     /// After calling this method, the PSBT is ready to be converted into a valid
     /// transaction for broadcast.
     ///
     /// # Errors
     ///
     /// Returns `SpillError::Finalize` if:
-    /// - `MissingSignature`: The PSBT is missing the payer's or payee's signature.
+    /// - `MissingSignature`: The PSBT is missing the payer's or payee's signature
+    ///   (or, for threshold channels, has fewer signatures than the threshold).
+    /// - `InconsistentSighash`: A signature is not ALL or ALL|ANYONECANPAY.
+    /// - `NonCanonicalSignature`: A signature has a high S value.
+    /// - `UndefinedSighashType`: The PSBT input records an undefined sighash type.
     /// - `MissingWitnessScript`: The PSBT input lacks a witness script.
     pub fn finalize_payment_tx(&self, psbt: &mut Psbt) -> Result<(), SpillError> {
-        self.params
-            .backend
-            .finalize_payment_tx(psbt, &self.params.payer, &self.params.payee)
+        self.params.backend.finalize_payment_tx(
+            psbt,
+            &self.params.cooperative_keys(),
+            self.params.threshold,
+        )?;
+
+        #[cfg(feature = "tracing")]
+        tracing::info!(channel_id = %self.funding_outpoint, "payment finalized");
+
+        Ok(())
+    }
+
+    /// Finalizes an anchor close PSBT for broadcast.
+    ///
+    /// Verifies `psbt` with [`Channel::verify_anchor_close_psbt`] and sets
+    /// the cooperative witness like [`Channel::finalize_payment_tx`]. The
+    /// close pays no fee, so it must be broadcast as a package together with
+    /// a version 3 child spending its anchor output.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`Channel::verify_anchor_close_psbt`] and
+    /// [`Channel::finalize_payment_tx`].
+    pub fn finalize_anchor_close_tx(&self, psbt: &mut Psbt) -> Result<(), SpillError> {
+        self.verify_anchor_close_psbt(psbt)?;
+        self.finalize_payment_tx(psbt)
+    }
+
+    /// Finalizes an arbiter-mediated close PSBT for broadcast.
+    ///
+    /// Takes a close PSBT built with [`Channel::arbiter_close_psbt`] and
+    /// assembles the cooperative witness from the signature of an arbiter
+    /// and the signatures of either the payer or the payee, so that neither
+    /// party can block a dispute resolution.
+    ///
+    /// # Errors
+    ///
+    /// Returns:
+    /// - `SpillError::Close(CloseError::NoArbiter)` if the channel has no cosigners.
+    /// - `SpillError::Finalize` with the errors of [`Channel::finalize_payment_tx`],
+    ///   or `MissingSignature` for the arbiter if no cosigner signed the PSBT.
+    pub fn finalize_arbiter_close_tx(&self, psbt: &mut Psbt) -> Result<(), SpillError> {
+        let arbiter = *self.params.cosigners.first().ok_or(CloseError::NoArbiter)?;
+
+        let input = psbt.inputs.first().ok_or(FinalizeError::MissingSignature {
+            public_key: arbiter,
+        })?;
+        if !self
+            .params
+            .cosigners
+            .iter()
+            .any(|key| input.partial_sigs.contains_key(key))
+        {
+            return Err(FinalizeError::MissingSignature {
+                public_key: arbiter,
+            }
+            .into());
+        }
+
+        self.finalize_payment_tx(psbt)
+    }
+
+    /// Builds the finalized claim transaction for a payment without
+    /// modifying the channel state.
+    ///
+    /// Verifies `psbt` with [`Channel::verify_payment_psbt`], requires the
+    /// payee's signature to be present, then finalizes a copy of the PSBT and
+    /// returns the extracted transaction. The result is the transaction a
+    /// watchtower needs in order to settle the channel on the payee's behalf.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error returned by [`Channel::verify_payment_psbt`], or a
+    /// `SpillError::Finalize` variant if:
+    /// - `MissingSignature`: The payee's signature is missing from the PSBT,
+    ///   or the PSBT has fewer signatures than the threshold.
+    /// - `MissingWitnessScript`: The PSBT input lacks a witness script.
+    pub fn claim_transaction(&self, psbt: &Psbt) -> Result<Transaction, SpillError> {
+        self.verify_payment_psbt(psbt)?;
+
+        if !psbt.inputs[0].partial_sigs.contains_key(&self.params.payee) {
+            return Err(FinalizeError::MissingSignature {
+                public_key: self.params.payee,
+            }
+            .into());
+        }
+
+        let mut psbt = psbt.clone();
+        self.finalize_payment_tx(&mut psbt)?;
+
+        Ok(psbt.extract_tx_unchecked_fee_rate())
     }
 }
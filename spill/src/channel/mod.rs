@@ -1,17 +1,55 @@
+use std::time::{Duration, SystemTime};
+
 use bitcoin::{
-    Amount, OutPoint, PublicKey, ScriptPubKeyBuf, ScriptPubKeyTag, TxOut, primitives::relative,
-    script::ScriptBuf,
+    Address, Amount, Network, OutPoint, Psbt, PublicKey, ScriptPubKeyBuf, ScriptPubKeyTag, TxOut,
+    hashes::{HashEngine, sha256t, sha256t_tag},
+    primitives::relative,
+    psbt::Output,
+    script::{PushBytes, ScriptBuf, ScriptExt, ScriptPubKeyBufExt, ScriptPubKeyExt},
 };
 
-use crate::{ConfigError, SpillError, channel::backend::ChannelBackend};
+use crate::{CloseError, ConfigError, FundingError, SpillError, channel::backend::ChannelBackend};
 
+mod advice;
 pub mod backend;
+mod describe;
+#[cfg(feature = "encrypt")]
+mod encrypt;
+#[cfg(feature = "base64")]
+mod export;
 mod finalize;
 mod payment;
+mod policy;
 mod psbt;
+mod sign;
+mod spend;
+mod splice;
+mod standard;
+mod stream;
 mod verify;
+mod weight;
 
+pub use advice::{CloseContext, CloseReason, CloseRecommendation};
+pub use describe::{PaymentPsbtSummary, describe_payment_psbt};
 pub use payment::PaymentInfo;
+pub use policy::ChannelPolicy;
+pub use psbt::funding_outpoint_of;
+pub use sign::{SigningRequest, SigningResponse};
+pub use standard::check_standardness;
+pub use stream::{PaymentStream, ReceiveStream};
+pub use verify::FundingInfo;
+pub use weight::LifecycleCost;
+
+/// Maximum number of keys allowed in the cooperative multisig branch.
+const MAX_COOPERATIVE_KEYS: usize = 16;
+
+/// Maximum size of the data carried by a standard `OP_RETURN` output.
+const MAX_OP_RETURN_SIZE: usize = 80;
+
+sha256t_tag! {
+    /// Tag of the hash computed by [`ChannelParams::params_id`].
+    struct ParamsIdTag = hash_str("spill/params_id");
+}
 
 /// Immutable channel configuration agreed upon by both peers.
 ///
@@ -34,10 +72,61 @@ pub use payment::PaymentInfo;
 pub struct ChannelParams<B: ChannelBackend + Clone> {
     payer: PublicKey,
     payee: PublicKey,
+    cosigners: Vec<PublicKey>,
+    threshold: usize,
     capacity: Amount,
     script_pubkey: ScriptBuf<ScriptPubKeyTag>,
     refund_lock_time: relative::LockTime,
     backend: B,
+    policy: ChannelPolicy,
+    payee_script: Option<ScriptPubKeyBuf>,
+}
+
+/// Role of a public key within a channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    /// The party funding the channel and sending payments.
+    Payer,
+    /// The party receiving payments.
+    Payee,
+    /// An additional key in the cooperative branch (e.g. an arbiter).
+    Cosigner,
+}
+
+/// Funding script template used by a channel.
+///
+/// Returned by [`ChannelParams::kind`], so that generic code can branch on
+/// the channel type when finalizing transactions or parsing scripts.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChannelKind {
+    /// P2WSH funding script with an m-of-n multisig cooperative branch and
+    /// a refund branch timelocked with `OP_CHECKSEQUENCEVERIFY`.
+    CsvP2wsh,
+}
+
+/// Branch of the funding script executed by a transaction closing the channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpendKind {
+    /// The channel was closed with a payment through the cooperative branch.
+    Payment,
+    /// The payer reclaimed the funds through the timelocked refund branch.
+    Refund,
+}
+
+/// Stable identifier of a funded channel.
+///
+/// The identifier is the channel's funding outpoint, which uniquely
+/// identifies the channel on-chain. It can be used as a map key to
+/// index and sort channels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ChannelId(OutPoint);
+
+impl ChannelId {
+    /// Returns the funding outpoint this identifier wraps.
+    pub fn outpoint(&self) -> OutPoint {
+        self.0
+    }
 }
 
 /// Runtime state of an established Spillman channel.
@@ -59,6 +148,8 @@ pub struct Channel<B: ChannelBackend + Clone> {
     funding_outpoint: OutPoint,
     funding_utxo: TxOut,
     sent: Amount,
+    payer_change: Amount,
+    opened_at: SystemTime,
 }
 
 impl<B: ChannelBackend + Clone> ChannelParams<B> {
@@ -78,16 +169,100 @@ impl<B: ChannelBackend + Clone> ChannelParams<B> {
         payee: PublicKey,
         capacity: Amount,
         refund_lock_time: relative::LockTime,
+        backend: B,
+    ) -> Result<ChannelParams<B>, SpillError> {
+        ChannelParams::new_threshold(
+            payer,
+            payee,
+            Vec::new(),
+            2,
+            capacity,
+            refund_lock_time,
+            backend,
+        )
+    }
+
+    /// Creates a new channel configuration with the capacity given in satoshis.
+    ///
+    /// Convenience wrapper around [`ChannelParams::new`] for callers holding
+    /// amounts as integer satoshis, avoiding unit conversion mistakes.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SpillError::Config(ConfigError::CapacityOutOfRange)` if
+    /// `capacity_sats` exceeds the maximum bitcoin supply, in addition to the
+    /// errors returned by [`ChannelParams::new`].
+    pub fn new_sats(
+        payer: PublicKey,
+        payee: PublicKey,
+        capacity_sats: u64,
+        refund_lock_time: relative::LockTime,
+        backend: B,
+    ) -> Result<ChannelParams<B>, SpillError> {
+        let capacity =
+            Amount::from_sat(capacity_sats).map_err(|_| ConfigError::CapacityOutOfRange)?;
+
+        ChannelParams::new(payer, payee, capacity, refund_lock_time, backend)
+    }
+
+    /// Creates a new channel configuration with an m-of-n cooperative path.
+    ///
+    /// The cooperative branch of the funding script becomes a `threshold`-of-n
+    /// multisig over the payer, the payee and the additional `cosigners`
+    /// (e.g. an arbiter), in that order. The refund path is unchanged and
+    /// remains spendable by the payer alone after `refund_lock_time`.
+    ///
+    /// [`ChannelParams::new`] is equivalent to calling this method with no
+    /// cosigners and a threshold of 2.
+    ///
+    /// # Parameters
+    /// - `payer`: The payer's compressed public key.
+    /// - `payee`: The payee's compressed public key.
+    /// - `cosigners`: Additional compressed public keys in the cooperative branch.
+    /// - `threshold`: Number of signatures required on the cooperative branch.
+    /// - `capacity`: The total channel capacity (must be non-zero).
+    /// - `refund_lock_time`: Lock time used for the refund path (must be non-zero).
+    /// - `backend`: The type of transaction to be used. Implements trait `ChannelBackend`.
+    ///
+    /// # Errors
+    ///
+    /// In addition to the errors returned by [`ChannelParams::new`], returns a
+    /// `SpillError::Config` variant if:
+    /// - `InvalidThreshold`: `threshold` is lower than 2, greater than the number
+    ///   of cooperative keys, or there are more than 16 cooperative keys.
+    /// - `DuplicatePublicKey`: A cosigner key is repeated or equals the payer or payee key.
+    pub fn new_threshold(
+        payer: PublicKey,
+        payee: PublicKey,
+        cosigners: Vec<PublicKey>,
+        threshold: usize,
+        capacity: Amount,
+        refund_lock_time: relative::LockTime,
         mut backend: B,
     ) -> Result<ChannelParams<B>, SpillError> {
         if capacity == Amount::ZERO {
             return Err(ConfigError::InvalidCapacity.into());
         }
 
-        if !(payer.compressed() && payee.compressed()) {
+        // `PublicKey` wraps a parsed secp256k1 point, so every key is on the
+        // curve and never the point at infinity. Requiring compression then
+        // guarantees each key is pushed into the funding script as exactly
+        // 33 bytes.
+        if !(payer.compressed() && payee.compressed() && cosigners.iter().all(|k| k.compressed())) {
             return Err(ConfigError::UncompressedPublicKey.into());
         }
 
+        let key_count = cosigners.len() + 2;
+        if threshold < 2 || threshold > key_count || key_count > MAX_COOPERATIVE_KEYS {
+            return Err(ConfigError::InvalidThreshold.into());
+        }
+
+        for (i, cosigner) in cosigners.iter().enumerate() {
+            if *cosigner == payer || *cosigner == payee || cosigners[..i].contains(cosigner) {
+                return Err(ConfigError::DuplicatePublicKey.into());
+            }
+        }
+
         if refund_lock_time == relative::LockTime::ZERO
             || refund_lock_time == relative::LockTime::from_height(0)
             || refund_lock_time == relative::LockTime::from_512_second_intervals(0)
@@ -95,19 +270,292 @@ impl<B: ChannelBackend + Clone> ChannelParams<B> {
             return Err(ConfigError::InvalidRefundLockTime.into());
         }
 
-        let script_pubkey = backend.script_pubkey(&payer, &payee, refund_lock_time)?;
+        let mut cooperative_keys = vec![payer, payee];
+        cooperative_keys.extend_from_slice(&cosigners);
+
+        let script_pubkey =
+            backend.script_pubkey(&payer, &cooperative_keys, threshold, refund_lock_time)?;
 
         Ok(ChannelParams {
             payer,
             payee,
+            cosigners,
+            threshold,
             capacity,
             script_pubkey,
             refund_lock_time,
             backend,
+            policy: ChannelPolicy::default(),
+            payee_script: None,
         })
     }
 
+    /// Creates a new channel configuration with a time-based refund lock time.
+    ///
+    /// Converts `refund_delay` into a BIP68 relative lock time expressed in
+    /// 512-second intervals, rounding up so the refund never becomes
+    /// available earlier than requested. All other parameters behave as in
+    /// [`ChannelParams::new`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `SpillError::Config(ConfigError::RefundLockTimeOverflow)` if the
+    /// duration exceeds the maximum representable BIP68 time lock
+    /// (`u16::MAX` intervals of 512 seconds), in addition to the errors
+    /// returned by [`ChannelParams::new`].
+    pub fn with_time_refund(
+        payer: PublicKey,
+        payee: PublicKey,
+        capacity: Amount,
+        refund_delay: Duration,
+        backend: B,
+    ) -> Result<ChannelParams<B>, SpillError> {
+        let seconds = refund_delay
+            .as_secs()
+            .saturating_add(u64::from(refund_delay.subsec_nanos() > 0));
+        let seconds = u32::try_from(seconds).map_err(|_| ConfigError::RefundLockTimeOverflow)?;
+        let refund_lock_time = relative::LockTime::from_seconds_ceil(seconds)
+            .map_err(|_| ConfigError::RefundLockTimeOverflow)?;
+
+        ChannelParams::new(payer, payee, capacity, refund_lock_time, backend)
+    }
+
+    /// Returns the `script_pubkey` of the channel funding output.
+    ///
+    /// For a [`ChannelKind::CsvP2wsh`] channel this is the P2WSH output
+    /// committing to the funding witness script.
     pub fn script_pubkey(&self) -> &ScriptPubKeyBuf {
         &self.script_pubkey
     }
+
+    /// Returns the address of the channel funding output on `network`.
+    ///
+    /// The payer can hand this address to any wallet to fund the channel
+    /// with exactly `capacity`. Any [`Network`] is supported, including
+    /// `Signet`, `Regtest` and testnet4 for testing. Signet and testnet
+    /// share the `tb` human-readable part, so their addresses are identical.
+    pub fn funding_address(&self, network: Network) -> Address {
+        Address::from_script(&self.script_pubkey, network).expect(
+            "funding_address: internal invariant violated (funding script must be a witness program)",
+        )
+    }
+
+    /// Returns a stable identifier derived from the channel parameters.
+    ///
+    /// Unlike [`Channel::id`], the identifier is available before the
+    /// channel is funded, which makes it suitable for correlating a channel
+    /// across systems during negotiation or as an idempotency key.
+    ///
+    /// The identifier is a tagged SHA-256 hash over the funding script and
+    /// the capacity. The funding script commits to the payer and payee keys,
+    /// any cosigners and the refund lock time, so two params producing the
+    /// same funding script with the same capacity produce the same id.
+    pub fn params_id(&self) -> [u8; 32] {
+        let mut engine = sha256t::HashEngine::<ParamsIdTag>::default();
+        engine.input(self.script_pubkey.as_bytes());
+        engine.input(&self.capacity.to_sat().to_le_bytes());
+        engine.finalize().to_byte_array()
+    }
+
+    /// Returns the role `key` plays in this channel, or `None` if the key
+    /// is not part of the channel.
+    pub fn role_of(&self, key: &PublicKey) -> Option<Role> {
+        if *key == self.payer {
+            Some(Role::Payer)
+        } else if *key == self.payee {
+            Some(Role::Payee)
+        } else if self.cosigners.contains(key) {
+            Some(Role::Cosigner)
+        } else {
+            None
+        }
+    }
+
+    /// Validates channel parameters proposed by a counterparty against local policy.
+    ///
+    /// Checks that `my_key` is part of the channel, that the capacity is at
+    /// least `min_capacity` and that the refund lock time does not exceed
+    /// `max_lock_time`. On success, returns the role assigned to `my_key`,
+    /// which the caller should compare against the role it expects to play.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SpillError::Config` variant if validation fails:
+    /// - `UnknownPublicKey`: `my_key` is neither the payer, the payee nor a cosigner.
+    /// - `CapacityBelowMinimum`: The capacity is lower than `min_capacity`.
+    /// - `RefundLockTimeTooLong`: The refund lock time exceeds `max_lock_time`
+    ///   or is expressed in a different unit (blocks vs. time).
+    pub fn validate_proposal(
+        &self,
+        my_key: &PublicKey,
+        min_capacity: Amount,
+        max_lock_time: relative::LockTime,
+    ) -> Result<Role, SpillError> {
+        let role = self.role_of(my_key).ok_or(ConfigError::UnknownPublicKey)?;
+
+        if self.capacity < min_capacity {
+            return Err(ConfigError::CapacityBelowMinimum {
+                capacity: self.capacity,
+                minimum: min_capacity,
+            }
+            .into());
+        }
+
+        if !self.refund_lock_time.is_implied_by(max_lock_time) {
+            return Err(ConfigError::RefundLockTimeTooLong.into());
+        }
+
+        Ok(role)
+    }
+
+    /// Sets the local verification policy applied to channel transactions.
+    pub fn with_policy(mut self, policy: ChannelPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Returns the local verification policy applied to channel transactions.
+    pub fn policy(&self) -> &ChannelPolicy {
+        &self.policy
+    }
+
+    /// Sets the script payments pay the payee to.
+    ///
+    /// By default the payee is paid to the P2WPKH script of its channel key.
+    /// This lets the payee receive to any standard segwit script instead
+    /// (P2WPKH, P2WSH or P2TR), while the channel itself is unchanged. Both
+    /// peers must agree on the payee script, since payments are verified
+    /// against it.
+    ///
+    /// The payee script is stored in the channel backup, so a channel
+    /// restored with [`ChannelParams::from_backup`] keeps paying to it.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SpillError::Config(ConfigError::UnsupportedPayeeScript)` if
+    /// `script` is not a P2WPKH, P2WSH or P2TR script.
+    pub fn with_payee_script(mut self, script: ScriptPubKeyBuf) -> Result<Self, SpillError> {
+        if !(script.is_p2wpkh() || script.is_p2wsh() || script.is_p2tr()) {
+            return Err(ConfigError::UnsupportedPayeeScript.into());
+        }
+
+        self.payee_script = Some(script);
+        Ok(self)
+    }
+
+    /// Returns the script payments pay the payee to.
+    pub fn payee_script(&self) -> ScriptPubKeyBuf {
+        match &self.payee_script {
+            Some(script) => script.clone(),
+            None => self
+                .backend
+                .payee_script(&self.payee)
+                .expect("payee_script: internal invariant violated (payee key must be compressed)"),
+        }
+    }
+
+    /// Returns the funding script template used by the channel.
+    pub fn kind(&self) -> ChannelKind {
+        self.backend.kind()
+    }
+
+    /// Returns the number of signatures required on the cooperative branch.
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    /// Returns the keys of the cooperative branch in script order:
+    /// payer, payee, then any additional cosigners.
+    pub fn cooperative_keys(&self) -> Vec<PublicKey> {
+        let mut keys = vec![self.payer, self.payee];
+        keys.extend_from_slice(&self.cosigners);
+        keys
+    }
+}
+
+impl<B: ChannelBackend + Clone> Channel<B> {
+    /// Returns the channel's identifier, derived from its funding outpoint.
+    pub fn id(&self) -> ChannelId {
+        ChannelId(self.funding_outpoint)
+    }
+
+    /// Reconciles two views of the same channel and returns the authoritative
+    /// amount sent.
+    ///
+    /// Intended for high-availability deployments where a primary and a
+    /// backup node may have applied a different number of payments. In a
+    /// unidirectional channel every payment supersedes the previous ones, so
+    /// the higher `sent` value is always the authoritative state: the payee
+    /// can close with the latest payment and the payer cannot revoke it.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SpillError::Funding(FundingError::ChannelMismatch)` if the
+    /// two channels do not share the same funding script, capacity and
+    /// funding outpoint.
+    pub fn reconcile(&self, other: &Channel<B>) -> Result<Amount, SpillError> {
+        if self.funding_outpoint != other.funding_outpoint
+            || self.funding_utxo != other.funding_utxo
+            || self.params.script_pubkey != other.params.script_pubkey
+        {
+            return Err(FundingError::ChannelMismatch.into());
+        }
+
+        Ok(self.sent.max(other.sent))
+    }
+
+    /// Records a verified payment as the latest channel state.
+    fn record_payment(&mut self, payment: &PaymentInfo) {
+        #[cfg(feature = "tracing")]
+        tracing::info!(
+            channel_id = %self.funding_outpoint,
+            old_sent = %self.sent,
+            new_sent = %payment.total,
+            "payment applied"
+        );
+
+        self.sent = payment.total;
+        self.payer_change = payment.payer_change;
+    }
+
+    /// Returns the value of the funding output committed to by every sighash
+    /// over the funding input.
+    ///
+    /// Payment PSBTs carry `funding_utxo` as their `witness_utxo`, so the
+    /// sighash is computed over the same amount signers see. The funding
+    /// output value must always equal the channel capacity.
+    fn funding_amount(&self) -> Amount {
+        debug_assert_eq!(
+            self.funding_utxo.amount, self.params.capacity,
+            "funding_amount: internal invariant violated (funding output value must equal capacity)"
+        );
+        self.funding_utxo.amount
+    }
+}
+
+/// Appends a zero-value `OP_RETURN` output carrying `data` to `psbt`, if any.
+///
+/// Returns `SpillError::Close(CloseError::OpReturnTooLarge)` if `data` exceeds
+/// [`MAX_OP_RETURN_SIZE`] bytes, leaving `psbt` untouched.
+fn push_op_return(psbt: &mut Psbt, data: Option<Vec<u8>>) -> Result<(), SpillError> {
+    let Some(data) = data else {
+        return Ok(());
+    };
+    if data.len() > MAX_OP_RETURN_SIZE {
+        return Err(CloseError::OpReturnTooLarge {
+            size: data.len(),
+            max: MAX_OP_RETURN_SIZE,
+        }
+        .into());
+    }
+
+    let data = <&PushBytes>::try_from(data.as_slice())
+        .expect("push_op_return: internal invariant violated (data must fit in a push)");
+    psbt.unsigned_tx.outputs.push(TxOut {
+        amount: Amount::ZERO,
+        script_pubkey: ScriptBuf::new_op_return(data),
+    });
+    psbt.outputs.push(Output::default());
+
+    Ok(())
 }
@@ -1,10 +1,16 @@
 use bitcoin::{
-    Amount, Psbt, Sequence, Transaction, TxIn, TxOut, Witness, WitnessProgram, absolute,
-    script::{ScriptBuf, ScriptPubKeyBufExt},
+    Amount, EcdsaSighashType, Psbt, Sequence, Transaction, TxIn, TxOut, Witness, WitnessProgram,
+    absolute,
+    amount::Denomination,
+    psbt::{Output, PsbtSighashType},
+    script::{ScriptBuf, ScriptPubKeyBufExt, ScriptPubKeyExt},
     transaction,
 };
 
-use crate::{Channel, PaymentError, SpillError, channel::backend::ChannelBackend};
+use crate::{
+    Channel, CloseError, PaymentError, SpillError,
+    channel::{backend::ChannelBackend, push_op_return},
+};
 
 /// Information about a verified payment.
 ///
@@ -18,6 +24,30 @@ pub struct PaymentInfo {
     pub current: Amount,
     /// Fee paid by the payer for this payment.
     pub fee: Amount,
+    /// Amount returned to the payer's P2WPKH change script.
+    ///
+    /// This is `capacity - total - fee` for payments built by the channel.
+    /// It is zero if the change was dust and was dropped, in which case it
+    /// is included in `fee`. Outputs to other scripts are not change.
+    pub payer_change: Amount,
+}
+
+impl PaymentInfo {
+    /// Formats the payment as a one-line summary with amounts in `denomination`.
+    ///
+    /// Intended for receipts and logs, e.g.
+    /// `Paid 0.0001 BTC (total 0.005 BTC), fee 0.00001 BTC, change 0.00499 BTC`.
+    pub fn display(&self, denomination: Denomination) -> String {
+        let show = |amount: Amount| amount.display_in(denomination).show_denomination();
+
+        format!(
+            "Paid {} (total {}), fee {}, change {}",
+            show(self.current),
+            show(self.total),
+            show(self.fee),
+            show(self.payer_change),
+        )
+    }
 }
 
 impl<B: ChannelBackend + Clone> Channel<B> {
@@ -26,6 +56,8 @@ impl<B: ChannelBackend + Clone> Channel<B> {
     /// The returned PSBT represents a payment from the payer to the payee
     /// for the specified `amount` with an included `fee`. The PSBT can be
     /// signed by the payer and then broadcast, or further inspected before signing.
+    /// `amount` is added to the amount sent so far; use
+    /// [`Channel::next_payment_to_total`] to give the cumulative total instead.
     ///
     /// # Errors
     ///
@@ -36,12 +68,459 @@ impl<B: ChannelBackend + Clone> Channel<B> {
     ///
     /// - The PSBT contains a single input referencing the channel's funding outpoint.
     /// - The input's witness UTXO is set according to the channel's funding transaction.
-    /// - The PSBT has two outputs:
+    /// - The PSBT has one or two outputs:
     ///     1. The payment to the payee (cumulative amount).
-    ///     2. The change back to the payer.
+    ///     2. The change back to the payer, omitted if it would be dust, in
+    ///        which case it is paid as fee.
     /// - The transaction has version 2, sequence `MAX`, and lock time 0.
     pub fn next_payment(&self, amount: Amount, fee: Amount) -> Result<Psbt, SpillError> {
-        let required: Amount = (amount + self.sent + fee)
+        let total = (amount + self.sent)
+            .into_result()
+            .map_err(|_| PaymentError::AmountOverflow)?;
+
+        self.payment_psbt(total, fee)
+    }
+
+    /// Constructs a PSBT for the next payment, recording the sighash type
+    /// the payer should sign with.
+    ///
+    /// Behaves like [`Channel::next_payment`] and additionally sets the
+    /// input's PSBT `sighash_type` field, so that PSBT-aware signers sign
+    /// with `sighash_type`. [`Channel::verify_payment_psbt`] then rejects
+    /// signatures using any other sighash type. PSBTs built with
+    /// [`Channel::next_payment`] leave the field unset, which signers
+    /// interpret as `SIGHASH_ALL`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SpillError::Payment(PaymentError::InvalidSighash)` if
+    /// `sighash_type` is not ALL or ALL|ANYONECANPAY, in addition to the
+    /// errors returned by [`Channel::next_payment`].
+    pub fn next_payment_with_sighash(
+        &self,
+        amount: Amount,
+        fee: Amount,
+        sighash_type: EcdsaSighashType,
+    ) -> Result<Psbt, SpillError> {
+        if sighash_type != EcdsaSighashType::All
+            && sighash_type != EcdsaSighashType::AllPlusAnyoneCanPay
+        {
+            return Err(PaymentError::InvalidSighash.into());
+        }
+
+        let mut psbt = self.next_payment(amount, fee)?;
+        psbt.inputs[0].sighash_type = Some(PsbtSighashType::from(sighash_type));
+
+        Ok(psbt)
+    }
+
+    /// Constructs a PSBT for the next payment that only becomes valid at `lock_time`.
+    ///
+    /// Behaves like [`Channel::next_payment`], but sets the transaction lock
+    /// time to `lock_time` and the input sequence to `ENABLE_LOCKTIME_NO_RBF`
+    /// so that the lock time is enforced. This supports protocols settling a
+    /// payment at a scheduled time, and requires the channel policy to set
+    /// [`ChannelPolicy::allow_timelocked_payments`] on both peers.
+    ///
+    /// A timelocked payment cannot be broadcast before `lock_time`, while
+    /// the payer can broadcast a refund as soon as the refund lock time has
+    /// passed. If `lock_time` is reached after the refund matures, the payer
+    /// can refund the whole capacity first and the payment is worthless, so
+    /// the payee must only accept a lock time that is reached well before
+    /// the refund lock time expires, leaving time to confirm the payment.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SpillError::Payment(PaymentError::NonZeroLockTime)` if
+    /// `lock_time` is not zero and the channel policy does not allow
+    /// timelocked payments, or `SpillError::Payment(PaymentError::LockTimeTooLate)`
+    /// if `lock_time` is later than the policy's `max_payment_lock_time`, in
+    /// addition to the errors returned by [`Channel::next_payment`].
+    ///
+    /// [`ChannelPolicy::allow_timelocked_payments`]: crate::ChannelPolicy::allow_timelocked_payments
+    pub fn next_payment_with_lock_time(
+        &self,
+        amount: Amount,
+        fee: Amount,
+        lock_time: absolute::LockTime,
+    ) -> Result<Psbt, SpillError> {
+        let mut psbt = self.next_payment(amount, fee)?;
+        if lock_time == absolute::LockTime::ZERO {
+            return Ok(psbt);
+        }
+        if !self.params.policy.allow_timelocked_payments {
+            return Err(PaymentError::NonZeroLockTime.into());
+        }
+        if let Some(max) = self.params.policy.max_payment_lock_time
+            && !lock_time.is_implied_by(max)
+        {
+            return Err(PaymentError::LockTimeTooLate { lock_time, max }.into());
+        }
+
+        psbt.unsigned_tx.lock_time = lock_time;
+        psbt.unsigned_tx.inputs[0].sequence = Sequence::ENABLE_LOCKTIME_NO_RBF;
+
+        Ok(psbt)
+    }
+
+    /// Constructs a PSBT for the next payment with amounts given in satoshis.
+    ///
+    /// Convenience wrapper around [`Channel::next_payment`] for callers
+    /// holding amounts as integer satoshis.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SpillError::Payment(PaymentError::AmountOverflow)` if either
+    /// amount exceeds the maximum bitcoin supply, in addition to the errors
+    /// returned by [`Channel::next_payment`].
+    pub fn next_payment_sats(&self, amount_sats: u64, fee_sats: u64) -> Result<Psbt, SpillError> {
+        let amount = Amount::from_sat(amount_sats).map_err(|_| PaymentError::AmountOverflow)?;
+        let fee = Amount::from_sat(fee_sats).map_err(|_| PaymentError::AmountOverflow)?;
+
+        self.next_payment(amount, fee)
+    }
+
+    /// Constructs a PSBT for the next payment given the payee's new cumulative total.
+    ///
+    /// Equivalent to [`Channel::next_payment`] with
+    /// `amount = target_total - sent`, for callers that already track the
+    /// cumulative amount paid to the payee. The payee output of the returned
+    /// PSBT is exactly `target_total`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SpillError::Payment(PaymentError::PaymentNotIncremental)` if
+    /// `target_total` does not exceed the amount sent so far, in addition to
+    /// the errors returned by [`Channel::next_payment`].
+    pub fn next_payment_to_total(
+        &self,
+        target_total: Amount,
+        fee: Amount,
+    ) -> Result<Psbt, SpillError> {
+        if target_total <= self.sent {
+            return Err(PaymentError::PaymentNotIncremental.into());
+        }
+
+        self.payment_psbt(target_total, fee)
+    }
+
+    /// Constructs a PSBT re-paying a previous payment at a higher fee.
+    ///
+    /// Reads the cumulative payee amount from `previous` and rebuilds the
+    /// payment with the same payee total, reducing the payer's change to
+    /// accommodate `new_fee`. This is the channel-native way of bumping the
+    /// fee of a pending close. The returned PSBT is unsigned.
+    ///
+    /// Since the payee amount does not increase, the payee verifies the
+    /// replacement with [`Channel::verify_fee_bump_psbt`] and applies it with
+    /// [`Channel::apply_fee_bump`], not with [`Channel::verify_payment_psbt`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SpillError::Payment` variant if:
+    /// - `MissingInput`: `previous` has no inputs.
+    /// - `FundingOutpointMismatch`: `previous` doesn't spend the funding outpoint.
+    /// - `MissingPayeeOutput`: `previous` has no output to the payee.
+    /// - `OutputsExceedFundingAmount`: The outputs of `previous` exceed the channel capacity.
+    /// - `FeeNotIncreased`: `new_fee` is not higher than the fee of `previous`.
+    /// - `ExceedsCapacity`: The payee total plus `new_fee` exceeds the channel capacity.
+    /// - `AmountOverflow`: Amount operation errored.
+    pub fn bump_payment_fee(&self, previous: &Psbt, new_fee: Amount) -> Result<Psbt, SpillError> {
+        let outpoint = previous
+            .unsigned_tx
+            .inputs
+            .first()
+            .ok_or(PaymentError::MissingInput)?
+            .previous_output;
+
+        if outpoint != self.funding_outpoint {
+            return Err(PaymentError::FundingOutpointMismatch.into());
+        }
+
+        let payee_script = self.params.payee_script();
+
+        let total = previous
+            .unsigned_tx
+            .outputs
+            .iter()
+            .find(|o| o.script_pubkey == payee_script)
+            .ok_or(PaymentError::MissingPayeeOutput)?
+            .amount;
+
+        let outputs = previous
+            .unsigned_tx
+            .outputs
+            .iter()
+            .try_fold(Amount::ZERO, |acc, output| {
+                (acc + output.amount).into_result()
+            })
+            .map_err(|_| PaymentError::AmountOverflow)?;
+        let previous_fee = (self.params.capacity - outputs)
+            .into_result()
+            .map_err(|_| PaymentError::OutputsExceedFundingAmount)?;
+        if new_fee <= previous_fee {
+            return Err(PaymentError::FeeNotIncreased {
+                fee: new_fee,
+                previous_fee,
+            }
+            .into());
+        }
+
+        self.payment_psbt(total, new_fee)
+    }
+
+    /// Applies a payment to the channel state.
+    ///
+    /// This method first verifies the provided PSBT using
+    /// [`Channel::verify_payment_psbt`]. If verification succeeds, the channel's
+    /// `sent` amount is updated to reflect the cumulative total in the PSBT,
+    /// and the payer's change is recorded for subsequent consistency checks.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SpillError::Payment` variant if the PSBT fails verification
+    /// (e.g., missing outputs, invalid signatures, etc.).
+    pub fn apply_payment(&mut self, psbt: &Psbt) -> Result<(), SpillError> {
+        let payment = self.verify_payment_psbt(psbt)?;
+        self.record_payment(&payment);
+        Ok(())
+    }
+
+    /// Applies a fee replacement of the latest payment to the channel state.
+    ///
+    /// Verifies `psbt` with [`Channel::verify_fee_bump_psbt`] and, on
+    /// success, records its lower payer change. The amount sent is unchanged,
+    /// and later payments must decrease the change further.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`Channel::verify_fee_bump_psbt`].
+    pub fn apply_fee_bump(&mut self, psbt: &Psbt) -> Result<(), SpillError> {
+        let payment = self.verify_fee_bump_psbt(psbt)?;
+        self.record_payment(&payment);
+        Ok(())
+    }
+
+    /// Constructs a PSBT closing the channel at the latest payment with an
+    /// additional pay-to-anchor (P2A) output.
+    ///
+    /// The PSBT pays the cumulative amount sent so far to the payee, the
+    /// remaining funds minus `anchor_value` back to the payer and
+    /// `anchor_value` to a keyless P2A output. The transaction itself pays no
+    /// fee: either party can spend the anchor output in a child transaction
+    /// to bump the fee of the close through CPFP.
+    ///
+    /// The close is a version 3 (TRUC) transaction, so that nodes relay it
+    /// together with its fee-paying child as a package despite its zero fee.
+    /// The child must be a version 3 transaction as well. The payee checks
+    /// the close with [`Channel::verify_anchor_close_psbt`] and finalizes it
+    /// with [`Channel::finalize_anchor_close_tx`].
+    ///
+    /// If `op_return_data` is set, a zero-value `OP_RETURN` output carrying
+    /// it is appended, e.g. to tag the close with an invoice reference.
+    ///
+    /// # Errors
+    ///
+    /// Returns:
+    /// - `SpillError::Close(CloseError::NothingSent)` if no payment has been
+    ///   applied to the channel.
+    /// - `SpillError::Payment(PaymentError::ExceedsCapacity)` if the amount
+    ///   sent plus `anchor_value` exceeds the channel capacity.
+    /// - `SpillError::Close(CloseError::OpReturnTooLarge)` if `op_return_data`
+    ///   is larger than 80 bytes.
+    pub fn cooperative_close_with_anchor(
+        &self,
+        anchor_value: Amount,
+        op_return_data: Option<Vec<u8>>,
+    ) -> Result<Psbt, SpillError> {
+        if self.sent == Amount::ZERO {
+            return Err(CloseError::NothingSent.into());
+        }
+
+        let mut psbt = self.payment_psbt(self.sent, anchor_value)?;
+        psbt.unsigned_tx.version = transaction::Version::THREE;
+
+        psbt.unsigned_tx.outputs.push(TxOut {
+            amount: anchor_value,
+            script_pubkey: ScriptBuf::new_p2a(),
+        });
+        psbt.outputs.push(Output::default());
+
+        push_op_return(&mut psbt, op_return_data)?;
+
+        Ok(psbt)
+    }
+
+    /// Constructs a PSBT closing the channel at a split decided by the arbiter.
+    ///
+    /// Intended for resolving a dispute in a channel with an arbiter in a
+    /// 2-of-3 cooperative branch: the arbiter co-signs the close with either
+    /// the payer or the payee, without requiring both parties to agree.
+    /// `split` is the pair `(payee_amount, payer_amount)`; the remaining
+    /// capacity is paid as fee. The amount sent so far is not enforced.
+    /// A zero `payer_amount` omits the payer output, otherwise both amounts
+    /// must be at least the dust limit of their outputs so that the
+    /// transaction is standard.
+    ///
+    /// The returned PSBT is unsigned and is finalized with
+    /// [`Channel::finalize_arbiter_close_tx`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SpillError::Close` variant if:
+    /// - `NoArbiter`: The channel has no cosigners.
+    /// - `ArbiterThresholdTooHigh`: The threshold requires more signatures
+    ///   than the arbiter and one party can provide.
+    /// - `PayerOutputDust`: `payer_amount` is neither zero nor above the
+    ///   dust limit of the payer's output.
+    ///
+    /// Or a `SpillError::Payment` variant if:
+    /// - `PayeeOutputDust`: `payee_amount` is below the dust limit of the
+    ///   payee's output.
+    /// - `ExceedsCapacity`: The split exceeds the channel capacity.
+    pub fn arbiter_close_psbt(&self, split: (Amount, Amount)) -> Result<Psbt, SpillError> {
+        if self.params.cosigners.is_empty() {
+            return Err(CloseError::NoArbiter.into());
+        }
+        if self.params.threshold > 2 {
+            return Err(CloseError::ArbiterThresholdTooHigh {
+                threshold: self.params.threshold,
+            }
+            .into());
+        }
+
+        let (payee_amount, payer_amount) = split;
+        let dust_limit = self.params.payee_script().minimal_non_dust();
+        if payee_amount < dust_limit {
+            return Err(PaymentError::PayeeOutputDust {
+                amount: payee_amount,
+                dust_limit,
+            }
+            .into());
+        }
+        let required = (payee_amount + payer_amount)
+            .into_result()
+            .map_err(|_| PaymentError::AmountOverflow)?;
+        let fee = (self.params.capacity - required)
+            .into_result()
+            .map_err(|_| PaymentError::ExceedsCapacity {
+                available: self.params.capacity,
+                required,
+            })?;
+
+        let payer_script =
+            ScriptBuf::new_witness_program(&WitnessProgram::p2wpkh(self.params.payer.try_into()?));
+        let dust_limit = payer_script.minimal_non_dust();
+        if payer_amount != Amount::ZERO && payer_amount < dust_limit {
+            return Err(CloseError::PayerOutputDust {
+                amount: payer_amount,
+                dust_limit,
+            }
+            .into());
+        }
+
+        // A zero payer amount is dust, so the payer output is omitted.
+        self.payment_psbt(payee_amount, fee)
+    }
+
+    /// Returns whether the channel can no longer accept a useful payment.
+    ///
+    /// A channel is exhausted when a further payment of at least one dust
+    /// unit of the payee's output, plus `min_fee`, no longer fits within the
+    /// remaining capacity. Such a channel should be closed with its latest
+    /// payment.
+    pub fn is_exhausted(&self, min_fee: Amount) -> bool {
+        let payee_script = self.params.payee_script();
+
+        let required = self.sent + payee_script.minimal_non_dust() + min_fee;
+
+        match required.into_result() {
+            Ok(required) => required > self.params.capacity,
+            Err(_) => true,
+        }
+    }
+
+    /// Returns how many more payments of `increment` fit in the remaining capacity.
+    ///
+    /// Intermediate payments are never broadcast, so the fee is not
+    /// cumulative: each payment carries the fee of its own transaction, and
+    /// only the one finally broadcast pays it. The count therefore reserves
+    /// `fee_per_payment` once, assuming every payment uses the same fee.
+    ///
+    /// Returns 0 if `increment` is zero, since such payments are not
+    /// incremental and would be rejected by the payee.
+    pub fn remaining_payment_count(&self, increment: Amount, fee_per_payment: Amount) -> u64 {
+        if increment == Amount::ZERO {
+            return 0;
+        }
+
+        let required = self.sent + fee_per_payment;
+        match (self.params.capacity - required).into_result() {
+            Ok(remaining) => remaining.to_sat() / increment.to_sat(),
+            Err(_) => 0,
+        }
+    }
+
+    /// Returns whether `psbt` has exactly the shape produced by [`Channel::next_payment`].
+    ///
+    /// This is stricter than [`Channel::verify_payment_psbt`], which accepts
+    /// e.g. additional outputs or a different output order, and lets a party
+    /// check that the counterparty did not reshape a payment. In particular,
+    /// a payment whose outputs were sorted by a BIP69 wallet verifies and
+    /// finalizes normally but is not canonical. The canonical form is:
+    /// - Transaction version 2 and lock time 0.
+    /// - A single input spending the funding outpoint with sequence `MAX`
+    ///   and an empty `script_sig` and witness.
+    /// - First the cumulative amount, not below the amount sent so far, to
+    ///   the payee script, then the payer's change to the payer's P2WPKH
+    ///   script. The change output is present exactly when it is not dust.
+    /// - The input PSBT fields set by the channel backend, with no other
+    ///   fields besides partial signatures and a `sighash_type` of ALL or
+    ///   ALL|ANYONECANPAY, as recorded by [`Channel::next_payment_with_sighash`].
+    /// - Empty output PSBT fields and no global PSBT fields.
+    ///
+    /// Signatures are not verified.
+    pub fn is_canonical_payment(&self, psbt: &Psbt) -> bool {
+        let (payment, change) = match psbt.unsigned_tx.outputs.as_slice() {
+            [payment] => (payment, Amount::ZERO),
+            [payment, change] => (payment, change.amount),
+            _ => return false,
+        };
+        if payment.amount < self.sent {
+            return false;
+        }
+        let Some(fee) = (payment.amount + change)
+            .and_then(|outputs| self.params.capacity - outputs)
+            .into_result()
+            .ok()
+        else {
+            return false;
+        };
+        let Ok(mut expected) = self.payment_psbt(payment.amount, fee) else {
+            return false;
+        };
+
+        let Some(input) = psbt.inputs.first() else {
+            return false;
+        };
+        if let Some(sighash_type) = input.sighash_type {
+            match sighash_type.ecdsa_hash_ty() {
+                Ok(EcdsaSighashType::All | EcdsaSighashType::AllPlusAnyoneCanPay) => {}
+                _ => return false,
+            }
+        }
+        expected.inputs[0].sighash_type = input.sighash_type;
+        expected.inputs[0].partial_sigs = input.partial_sigs.clone();
+
+        *psbt == expected
+    }
+
+    /// Builds a payment PSBT paying a cumulative `total` to the payee with the given `fee`.
+    ///
+    /// The payer's change is omitted if it is below the dust limit of the
+    /// payer's output, so the transaction then pays it as fee as well.
+    fn payment_psbt(&self, total: Amount, fee: Amount) -> Result<Psbt, SpillError> {
+        let required: Amount = (total + fee)
             .into_result()
             .map_err(|_| PaymentError::AmountOverflow)?;
         if required > self.params.capacity {
@@ -60,12 +539,8 @@ impl<B: ChannelBackend + Clone> Channel<B> {
         };
 
         let payment = TxOut {
-            amount: (amount + self.sent)
-                .into_result()
-                .map_err(|_| PaymentError::AmountOverflow)?,
-            script_pubkey: ScriptBuf::new_witness_program(&WitnessProgram::p2wpkh(
-                self.params.payee.try_into()?,
-            )),
+            amount: total,
+            script_pubkey: self.params.payee_script(),
         };
 
         let change = TxOut {
@@ -75,11 +550,18 @@ impl<B: ChannelBackend + Clone> Channel<B> {
             script_pubkey: ScriptBuf::new_witness_program(&WitnessProgram::p2wpkh(self.params.payer.try_into()?)),
         };
 
+        let mut outputs = vec![payment];
+        // Dust change would make the transaction non-standard, so it is
+        // dropped and left to the fee instead.
+        if change.amount >= change.script_pubkey.minimal_non_dust() {
+            outputs.push(change);
+        }
+
         let tx = Transaction {
             version: transaction::Version::TWO,
             lock_time: absolute::LockTime::ZERO,
             inputs: vec![input],
-            outputs: vec![payment, change],
+            outputs,
         };
 
         let mut psbt = Psbt::from_unsigned_tx(tx)
@@ -91,20 +573,4 @@ impl<B: ChannelBackend + Clone> Channel<B> {
 
         Ok(psbt)
     }
-
-    /// Applies a payment to the channel state.
-    ///
-    /// This method first verifies the provided PSBT using
-    /// [`Channel::verify_payment_psbt`]. If verification succeeds, the channel's
-    /// `sent` amount is updated to reflect the cumulative total in the PSBT.
-    ///
-    /// # Errors
-    ///
-    /// Returns a `SpillError::Payment` variant if the PSBT fails verification
-    /// (e.g., missing outputs, invalid signatures, etc.).
-    pub fn apply_payment(&mut self, psbt: &Psbt) -> Result<(), SpillError> {
-        let payment = self.verify_payment_psbt(psbt)?;
-        self.sent = payment.total;
-        Ok(())
-    }
 }
@@ -0,0 +1,57 @@
+use bitcoin::{Amount, absolute};
+
+/// Local verification policy applied to channel transactions.
+///
+/// `ChannelPolicy` holds optional, stricter checks that a party may enforce
+/// on top of the rules implied by the [`ChannelParams`](crate::ChannelParams).
+/// Unlike the parameters, the policy is not agreed upon with the
+/// counterparty and does not affect the funding script.
+///
+/// The default policy enables none of the additional checks.
+#[non_exhaustive]
+#[derive(Debug, Clone, Default)]
+pub struct ChannelPolicy {
+    /// Index at which the payee output must appear in payment transactions.
+    ///
+    /// When `None`, the payee output is located by its script.
+    pub expected_payee_index: Option<usize>,
+    /// Maximum number of outputs allowed in payment transactions.
+    pub max_outputs: Option<usize>,
+    /// Requires payment signatures to use exactly `SIGHASH_ALL`.
+    ///
+    /// When `false`, `SIGHASH_ALL|ANYONECANPAY` is also accepted, which lets
+    /// the payee attach additional inputs to bump the fee.
+    pub strict_sighash_all: bool,
+    /// Minimum value of the payee output in payment transactions.
+    ///
+    /// When `None`, the standard dust limit of the payee's output script is used.
+    pub dust_limit: Option<Amount>,
+    /// Accepts payment transactions with a non-zero lock time.
+    ///
+    /// Timelocked payments use sequence `ENABLE_LOCKTIME_NO_RBF` so that
+    /// their lock time is enforced, and only become valid once it is
+    /// reached. See [`Channel::next_payment_with_lock_time`] for how such
+    /// payments interact with the refund path.
+    ///
+    /// [`Channel::next_payment_with_lock_time`]: crate::Channel::next_payment_with_lock_time
+    pub allow_timelocked_payments: bool,
+    /// Latest lock time accepted for timelocked payments.
+    ///
+    /// Only applies when `allow_timelocked_payments` is set. Payments whose
+    /// lock time is later than this, or is not in the same unit, are
+    /// rejected. The payee should set it well before the refund path
+    /// matures, since a payment that only becomes valid afterwards can be
+    /// preempted by the refund. When `None`, any lock time is accepted.
+    pub max_payment_lock_time: Option<absolute::LockTime>,
+    /// Rejects funding transactions with more than one output matching the
+    /// expected funding output.
+    ///
+    /// Such outputs are indistinguishable, so only one of them can back the
+    /// channel and the funds in the others are easily lost track of.
+    pub reject_ambiguous_funding: bool,
+    /// Minimum increase of the cumulative amount required for each payment.
+    ///
+    /// Protects the payee from spending verification work on a stream of
+    /// negligible payments. Zero, the default, accepts any increase.
+    pub min_payment_increment: Amount,
+}
@@ -1,6 +1,37 @@
-use bitcoin::{Psbt, Transaction, TxIn, TxOut, Witness, absolute, script::ScriptBuf, transaction};
+use bitcoin::{
+    Address, Amount, EcdsaSighashType, NumOpResult, OutPoint, Psbt, ScriptPubKeyBuf, Sequence,
+    Transaction, TxIn, TxOut, Witness, absolute,
+    psbt::{self, Output},
+    script::{ScriptBuf, ScriptExt, ScriptPubKeyExt},
+    secp256k1::Message,
+    sighash::SighashCache,
+    transaction,
+};
 
-use crate::{Channel, ChannelParams, channel::backend::ChannelBackend};
+use crate::{
+    Channel, ChannelParams, FundingError, RefundError, SpillError,
+    channel::{backend::ChannelBackend, push_op_return},
+};
+
+/// Upper bound on the number of outputs of a valid transaction.
+///
+/// The smallest output is 9 bytes (an 8-byte amount and an empty script),
+/// so no transaction fitting in a 4M weight unit block can have more.
+const MAX_OUTPUT_COUNT: usize = 4_000_000 / (4 * 9);
+
+/// Extracts the outpoint spent by the first input of a payment PSBT.
+///
+/// Intended for routing an incoming PSBT to the channel it belongs to
+/// before calling [`Channel::verify_payment_psbt`]. Returns `None` if the
+/// PSBT has no inputs.
+///
+/// This function does not verify anything, it only extracts the outpoint.
+pub fn funding_outpoint_of(psbt: &Psbt) -> Option<OutPoint> {
+    psbt.unsigned_tx
+        .inputs
+        .first()
+        .map(|input| input.previous_output)
+}
 
 impl<B: ChannelBackend + Clone> ChannelParams<B> {
     /// Constructs a funding PSBT for the channel.
@@ -14,25 +45,280 @@ impl<B: ChannelBackend + Clone> ChannelParams<B> {
     /// - The PSBT contains a single output paying the channel capacity to the
     ///   channel's funding script.
     /// - The transaction has version 2 and a lock time of 0.
+    ///
+    /// The funding output is always equal to [`ChannelParams::expected_funding_output`],
+    /// which is the same output [`ChannelParams::verify_funding_tx`] checks against.
     pub fn funding_psbt(&self) -> Psbt {
-        let output = TxOut {
-            amount: self.capacity,
-            script_pubkey: self.script_pubkey.clone(),
+        self.funding_psbt_at_index(0)
+            .expect("funding_psbt: internal invariant violated (index 0 must be reachable)")
+    }
+
+    /// Constructs a funding PSBT with the funding output at `index`.
+    ///
+    /// Behaves like [`ChannelParams::funding_psbt`], but places the funding
+    /// output at the requested position, for callers matching an existing
+    /// transaction template. The outputs before `index` are zero-value
+    /// placeholders with an empty script that the caller must replace with
+    /// its own outputs before signing. The funding outpoint is then
+    /// `index` when calling [`ChannelParams::verify_funding_tx`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `SpillError::Funding(FundingError::OutputIndexOutOfRange)` if
+    /// no valid transaction can hold an output at `index`.
+    pub fn funding_psbt_at_index(&self, index: usize) -> Result<Psbt, SpillError> {
+        if index >= MAX_OUTPUT_COUNT {
+            return Err(FundingError::OutputIndexOutOfRange.into());
+        }
+
+        let placeholder = TxOut {
+            amount: Amount::ZERO,
+            script_pubkey: ScriptBuf::new(),
         };
+        let mut outputs = vec![placeholder; index];
+        outputs.push(self.expected_funding_output());
 
         let tx = Transaction {
             version: transaction::Version::TWO,
             lock_time: absolute::LockTime::ZERO,
             inputs: vec![],
-            outputs: vec![output],
+            outputs,
         };
 
         let mut psbt = Psbt::from_unsigned_tx(tx)
             .expect("funding_psbt: internal invariant violated (tx must be unsigned)");
 
-        self.backend.populate_funding_psbt(&mut psbt);
+        self.backend.populate_funding_psbt(&mut psbt, index);
 
-        psbt
+        debug_assert_eq!(
+            psbt.unsigned_tx.outputs[index],
+            self.expected_funding_output(),
+            "funding_psbt: internal invariant violated (funding output must match expected)"
+        );
+
+        Ok(psbt)
+    }
+
+    /// Constructs a complete funding PSBT spending the given UTXOs.
+    ///
+    /// The PSBT spends every UTXO in `utxos`, pays the channel capacity to
+    /// the funding output at index 0 and returns the remainder, minus `fee`,
+    /// to `change_script`. If the change would be below the dust limit of
+    /// `change_script`, no change output is created and the remainder is
+    /// added to the fee instead.
+    ///
+    /// The inputs' witness UTXOs are set so the PSBT can be signed directly.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SpillError::Funding` variant if:
+    /// - `InsufficientFunds`: The UTXOs do not cover the capacity plus `fee`.
+    /// - `AmountOverflow`: Amount operation errored.
+    pub fn fund_from_utxos(
+        &self,
+        utxos: &[(OutPoint, TxOut)],
+        change_script: ScriptPubKeyBuf,
+        fee: Amount,
+    ) -> Result<Psbt, SpillError> {
+        let available = utxos
+            .iter()
+            .map(|(_, utxo)| utxo.amount)
+            .fold(NumOpResult::Valid(Amount::ZERO), |acc, item| acc + item)
+            .into_result()
+            .map_err(|_| FundingError::AmountOverflow)?;
+
+        let required = (self.capacity + fee)
+            .into_result()
+            .map_err(|_| FundingError::AmountOverflow)?;
+
+        let change =
+            (available - required)
+                .into_result()
+                .map_err(|_| FundingError::InsufficientFunds {
+                    available,
+                    required,
+                })?;
+
+        let mut psbt = self.funding_psbt();
+
+        for (outpoint, utxo) in utxos {
+            psbt.unsigned_tx.inputs.push(TxIn {
+                previous_output: *outpoint,
+                script_sig: ScriptBuf::new(),
+                sequence: Sequence::MAX,
+                witness: Witness::new(),
+            });
+            psbt.inputs.push(psbt::Input {
+                witness_utxo: Some(utxo.clone()),
+                ..Default::default()
+            });
+        }
+
+        if change >= change_script.minimal_non_dust() {
+            psbt.unsigned_tx.outputs.push(TxOut {
+                amount: change,
+                script_pubkey: change_script,
+            });
+            psbt.outputs.push(Output::default());
+        }
+
+        Ok(psbt)
+    }
+
+    /// Inserts the channel's funding output into a wallet-built PSBT.
+    ///
+    /// Intended for wallets, such as `bdk_wallet`, that select coins and
+    /// build the funding PSBT themselves. The funding output is inserted as
+    /// the first output and populated like the output of
+    /// [`ChannelParams::funding_psbt`]. The returned PSBT can then be signed
+    /// by the wallet.
+    ///
+    /// `wallet_psbt` must not be signed yet, since adding an output changes
+    /// the transaction being signed. Every input must carry its witness or
+    /// non-witness UTXO so that the input value can be checked.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SpillError::Funding` variant if:
+    /// - `MissingInputUtxo`: An input lacks both its witness and non-witness UTXO.
+    /// - `InsufficientFunds`: The inputs do not cover the capacity plus the
+    ///   wallet's own outputs.
+    /// - `AmountOverflow`: Amount operation errored.
+    pub fn merge_into_funding_psbt(&self, mut wallet_psbt: Psbt) -> Result<Psbt, SpillError> {
+        let available = input_value(&wallet_psbt)?;
+
+        let required = wallet_psbt
+            .unsigned_tx
+            .outputs
+            .iter()
+            .map(|o| o.amount)
+            .fold(NumOpResult::Valid(self.capacity), |acc, item| acc + item)
+            .into_result()
+            .map_err(|_| FundingError::AmountOverflow)?;
+
+        if available < required {
+            return Err(FundingError::InsufficientFunds {
+                available,
+                required,
+            }
+            .into());
+        }
+
+        wallet_psbt
+            .unsigned_tx
+            .outputs
+            .insert(0, self.expected_funding_output());
+        wallet_psbt.outputs.insert(0, Output::default());
+
+        self.backend.populate_funding_psbt(&mut wallet_psbt, 0);
+
+        Ok(wallet_psbt)
+    }
+
+    /// Computes the fee paid by a funding PSBT and checks it against `max_fee`.
+    ///
+    /// The fee is the value of the PSBT inputs, taken from their witness or
+    /// non-witness UTXOs, minus the value of all outputs. This is a
+    /// pre-broadcast check protecting the payer from accidentally overpaying
+    /// fees after editing the funding PSBT. Returns the fee on success.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SpillError::Funding` variant if:
+    /// - `MissingInputUtxo`: An input has no witness or non-witness UTXO.
+    /// - `InsufficientFunds`: The outputs exceed the value of the inputs.
+    /// - `FeeTooHigh`: The fee exceeds `max_fee`.
+    /// - `AmountOverflow`: Amount operation errored.
+    pub fn verify_funding_fee(&self, psbt: &Psbt, max_fee: Amount) -> Result<Amount, SpillError> {
+        let available = input_value(psbt)?;
+        let required = psbt
+            .unsigned_tx
+            .outputs
+            .iter()
+            .map(|o| o.amount)
+            .fold(NumOpResult::Valid(Amount::ZERO), |acc, item| acc + item)
+            .into_result()
+            .map_err(|_| FundingError::AmountOverflow)?;
+
+        let fee =
+            (available - required)
+                .into_result()
+                .map_err(|_| FundingError::InsufficientFunds {
+                    available,
+                    required,
+                })?;
+
+        if fee > max_fee {
+            return Err(FundingError::FeeTooHigh { fee, max_fee }.into());
+        }
+
+        Ok(fee)
+    }
+
+    /// Computes the sighash the payer signs for an input of the funding PSBT.
+    ///
+    /// `utxo` is the output spent by the input at `index`. The sighash commits
+    /// to `SIGHASH_ALL`, and the resulting signature goes in the input's
+    /// `partial_sigs` like any segwit v0 signature. The supported input
+    /// types are:
+    /// - P2WPKH.
+    /// - P2SH-P2WPKH, whose P2WPKH redeem script must be set in the PSBT
+    ///   input's `redeem_script`.
+    ///
+    /// Funding inputs of other script types (e.g. P2PKH or P2TR) must be
+    /// signed by the payer's wallet.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SpillError::Funding` variant if:
+    /// - `InputIndexOutOfRange`: The PSBT has no input at `index`.
+    /// - `UnsupportedInputScript`: `utxo` is neither a P2WPKH output nor a
+    ///   P2SH output matching a P2WPKH redeem script of the input.
+    pub fn funding_input_sighash(
+        &self,
+        psbt: &Psbt,
+        index: usize,
+        utxo: &TxOut,
+    ) -> Result<Message, SpillError> {
+        let input = psbt
+            .inputs
+            .get(index)
+            .ok_or(FundingError::InputIndexOutOfRange { index })?;
+
+        let mut cache = SighashCache::new(&psbt.unsigned_tx);
+        let sighash = if utxo.script_pubkey.is_p2wpkh() {
+            cache.p2wpkh_signature_hash(
+                index,
+                &utxo.script_pubkey,
+                utxo.amount,
+                EcdsaSighashType::All,
+            )
+        } else if utxo.script_pubkey.is_p2sh() {
+            let redeem_script = input
+                .redeem_script
+                .as_ref()
+                .filter(|script| script.is_p2wpkh())
+                .filter(|script| script.to_p2sh().ok().as_ref() == Some(&utxo.script_pubkey))
+                .ok_or(FundingError::UnsupportedInputScript { index })?;
+            cache.p2wpkh_signature_hash(index, redeem_script, utxo.amount, EcdsaSighashType::All)
+        } else {
+            return Err(FundingError::UnsupportedInputScript { index }.into());
+        }
+        .expect("funding_input_sighash: internal invariant violated (input must be p2wpkh)");
+
+        Ok(Message::from_digest(sighash.to_byte_array()))
+    }
+
+    /// Returns the canonical funding output for the channel.
+    ///
+    /// The output pays exactly the channel capacity to the channel's funding
+    /// script. It is the single source of truth used both to construct the
+    /// funding PSBT and to verify a received funding transaction.
+    pub fn expected_funding_output(&self) -> TxOut {
+        TxOut {
+            amount: self.capacity,
+            script_pubkey: self.script_pubkey.clone(),
+        }
     }
 }
 
@@ -73,4 +359,133 @@ impl<B: ChannelBackend + Clone> Channel<B> {
 
         psbt
     }
+
+    /// Constructs a refund PSBT paying the channel funds to `refund_address`.
+    ///
+    /// The returned PSBT is the same as [`Channel::refund_psbt`] with a single
+    /// output paying `capacity - fee` to `refund_address`, ready to be signed
+    /// by the payer.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SpillError::Refund` variant if:
+    /// - `ExceedsCapacity`: `fee` exceeds the channel capacity.
+    /// - `DustOutput`: The resulting refund output would be dust.
+    pub fn refund_psbt_to(
+        &self,
+        refund_address: &Address,
+        fee: Amount,
+    ) -> Result<Psbt, SpillError> {
+        let capacity = self.funding_utxo.amount;
+        let amount = (capacity - fee)
+            .into_result()
+            .map_err(|_| RefundError::ExceedsCapacity {
+                available: capacity,
+                required: fee,
+            })?;
+
+        self.refund_psbt_to_many(&[(*refund_address, amount)], fee, None)
+    }
+
+    /// Constructs a refund PSBT splitting the channel funds across several outputs.
+    ///
+    /// Generalizes [`Channel::refund_psbt_to`] for a payer reclaiming the funds
+    /// to multiple destinations (e.g. a cold and a hot wallet). The returned
+    /// PSBT is the same as [`Channel::refund_psbt`] with one output per entry
+    /// of `outputs`, in order, ready to be signed by the payer. The output
+    /// amounts plus `fee` must add up to exactly the channel capacity.
+    ///
+    /// If `op_return_data` is set, a zero-value `OP_RETURN` output carrying
+    /// it is appended after the refund outputs.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SpillError::Refund` variant if:
+    /// - `NoOutputs`: `outputs` is empty.
+    /// - `ExceedsCapacity`: The output amounts plus `fee` exceed the channel capacity.
+    /// - `UnallocatedFunds`: The output amounts plus `fee` are below the channel capacity.
+    /// - `DustOutput`: A refund output would be dust.
+    ///
+    /// Returns `SpillError::Close(CloseError::OpReturnTooLarge)` if
+    /// `op_return_data` is larger than 80 bytes.
+    pub fn refund_psbt_to_many(
+        &self,
+        outputs: &[(Address, Amount)],
+        fee: Amount,
+        op_return_data: Option<Vec<u8>>,
+    ) -> Result<Psbt, SpillError> {
+        if outputs.is_empty() {
+            return Err(RefundError::NoOutputs.into());
+        }
+
+        let capacity = self.funding_utxo.amount;
+        let required = outputs
+            .iter()
+            .map(|(_, amount)| *amount)
+            .chain(core::iter::once(fee))
+            .try_fold(Amount::ZERO, |acc, amount| (acc + amount).into_result())
+            .map_err(|_| RefundError::ExceedsCapacity {
+                available: capacity,
+                required: Amount::MAX,
+            })?;
+        if required > capacity {
+            return Err(RefundError::ExceedsCapacity {
+                available: capacity,
+                required,
+            }
+            .into());
+        }
+        if required < capacity {
+            return Err(RefundError::UnallocatedFunds {
+                available: capacity,
+                required,
+            }
+            .into());
+        }
+
+        let mut psbt = self.refund_psbt();
+        for (address, amount) in outputs {
+            let script_pubkey = address.script_pubkey();
+            let dust_limit = script_pubkey.minimal_non_dust();
+            if *amount < dust_limit {
+                return Err(RefundError::DustOutput {
+                    amount: *amount,
+                    dust_limit,
+                }
+                .into());
+            }
+
+            psbt.unsigned_tx.outputs.push(TxOut {
+                amount: *amount,
+                script_pubkey,
+            });
+            psbt.outputs.push(Output::default());
+        }
+
+        push_op_return(&mut psbt, op_return_data)?;
+
+        Ok(psbt)
+    }
+}
+
+/// Sums the values of the outputs spent by the inputs of `psbt`.
+fn input_value(psbt: &Psbt) -> Result<Amount, SpillError> {
+    let mut available = NumOpResult::Valid(Amount::ZERO);
+    for (index, (txin, input)) in psbt.unsigned_tx.inputs.iter().zip(&psbt.inputs).enumerate() {
+        let amount = match (&input.witness_utxo, &input.non_witness_utxo) {
+            (Some(utxo), _) => utxo.amount,
+            (None, Some(tx)) => {
+                tx.outputs
+                    .get(txin.previous_output.vout as usize)
+                    .ok_or(FundingError::MissingInputUtxo { index })?
+                    .amount
+            }
+            (None, None) => return Err(FundingError::MissingInputUtxo { index }.into()),
+        };
+        available += amount;
+    }
+
+    Ok(available
+        .into_result()
+        .map_err(|_| FundingError::AmountOverflow)?)
 }
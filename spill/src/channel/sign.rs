@@ -0,0 +1,225 @@
+use bitcoin::{EcdsaSighashType, Psbt, PublicKey, ecdsa::Signature};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Channel, ConfigError, PaymentError, Signer, SpillError, channel::backend::ChannelBackend,
+};
+
+/// Request for a signature on a payment, sent from one peer to another.
+///
+/// Built with [`Channel::payment_signing_request`]. The request is
+/// self-describing, so a remote signer can inspect the payment and sign
+/// `sighash` without access to the channel state. Byte fields use the
+/// consensus or BIP174 encodings, and the struct can be serialized with any
+/// `serde` format.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SigningRequest {
+    /// The serialized payment PSBT.
+    pub psbt: Vec<u8>,
+    /// The funding witness script spent by the payment.
+    pub witness_script: Vec<u8>,
+    /// The sighash of the funding input to sign.
+    pub sighash: [u8; 32],
+    /// The sighash type the signature must commit to.
+    pub sighash_type: u32,
+}
+
+/// Signature returned in response to a [`SigningRequest`].
+///
+/// Applied to the payment with [`Channel::apply_signing_response`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SigningResponse {
+    /// The serialized compressed public key of the signer.
+    pub public_key: Vec<u8>,
+    /// The DER-encoded signature followed by its sighash type byte.
+    pub signature: Vec<u8>,
+}
+
+impl SigningResponse {
+    /// Creates a response carrying `signature` by `public_key`.
+    pub fn new(public_key: PublicKey, signature: Signature) -> Self {
+        SigningResponse {
+            public_key: public_key.to_bytes(),
+            signature: signature.to_vec(),
+        }
+    }
+}
+
+impl<B: ChannelBackend + Clone> Channel<B> {
+    /// Signs a payment PSBT as the payer and inserts the signature.
+    ///
+    /// Computes the sighash of the funding input and asks `signer` to sign
+    /// it, then stores the result in the input's `partial_sigs`. The sighash
+    /// type is the one recorded in the PSBT input, or `SIGHASH_ALL` if none
+    /// is recorded (see [`Channel::next_payment_with_sighash`]). The payment
+    /// itself is not verified; call [`Channel::verify_payment_psbt`] on the
+    /// signed PSBT.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SpillError::Payment` variant if:
+    /// - `MissingInput`: The PSBT has no inputs.
+    /// - `FundingOutpointMismatch`: The PSBT doesn't reference the funding outpoint.
+    /// - `SignerKeyMismatch`: `signer` does not sign for the payer key.
+    /// - `InvalidSighash`: The recorded sighash type is not ALL or ALL|ANYONECANPAY.
+    pub fn sign_payment_as_payer(
+        &self,
+        psbt: &mut Psbt,
+        signer: &dyn Signer,
+    ) -> Result<(), SpillError> {
+        self.sign_funding_input(psbt, signer, self.params.payer)
+    }
+
+    /// Signs a refund PSBT as the payer and inserts the signature.
+    ///
+    /// Behaves like [`Channel::sign_payment_as_payer`] for a PSBT built with
+    /// [`Channel::refund_psbt`] or one of its variants. The signed refund is
+    /// then finalized with [`Channel::finalize_refund_tx`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`Channel::sign_payment_as_payer`].
+    pub fn sign_refund(&self, psbt: &mut Psbt, signer: &dyn Signer) -> Result<(), SpillError> {
+        self.sign_funding_input(psbt, signer, self.params.payer)
+    }
+
+    /// Describes the signature a peer must provide for a payment PSBT.
+    ///
+    /// Formalizes the signature exchange between the payer and the payee
+    /// (or a cosigner): the request carries the payment, the funding witness
+    /// script and the sighash to sign, using the sighash type recorded in
+    /// the PSBT input or `SIGHASH_ALL`. The payment itself is not verified.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SpillError::Payment` variant if:
+    /// - `MissingInput`: The PSBT has no inputs.
+    /// - `FundingOutpointMismatch`: The PSBT doesn't reference the funding outpoint.
+    /// - `MissingWitnessScript`: The input lacks a witness script.
+    /// - `InvalidSighash`: The recorded sighash type is not ALL or ALL|ANYONECANPAY.
+    pub fn payment_signing_request(&self, psbt: &Psbt) -> Result<SigningRequest, SpillError> {
+        let sighash_type = self.funding_input_sighash_type(psbt)?;
+        let witness_script = psbt
+            .inputs
+            .first()
+            .ok_or(PaymentError::MissingInput)?
+            .witness_script
+            .as_ref()
+            .ok_or(PaymentError::MissingWitnessScript)?;
+
+        let msg = self
+            .params
+            .backend
+            .signature_hash(psbt, self.funding_amount(), sighash_type);
+
+        Ok(SigningRequest {
+            psbt: psbt.serialize(),
+            witness_script: witness_script.to_vec(),
+            sighash: *msg.as_ref(),
+            sighash_type: sighash_type.to_u32(),
+        })
+    }
+
+    /// Inserts the signature of a [`SigningResponse`] into a payment PSBT.
+    ///
+    /// The signature must be by a key of the cooperative branch, use the
+    /// sighash type recorded in the PSBT input (or `SIGHASH_ALL`) and be
+    /// valid for the payment's sighash. `psbt` is left unchanged if any
+    /// check fails.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SpillError::Config(ConfigError::UnknownPublicKey)` if the
+    /// response key is not in the cooperative branch, and otherwise a
+    /// `SpillError::Payment` variant if:
+    /// - `MissingInput`: The PSBT has no inputs.
+    /// - `FundingOutpointMismatch`: The PSBT doesn't reference the funding outpoint.
+    /// - `InvalidSighash`: The signature doesn't use the expected sighash type.
+    /// - `InvalidSignature`: The response is malformed or the signature is invalid.
+    pub fn apply_signing_response(
+        &self,
+        psbt: &mut Psbt,
+        response: &SigningResponse,
+    ) -> Result<(), SpillError> {
+        let sighash_type = self.funding_input_sighash_type(psbt)?;
+
+        let public_key = PublicKey::from_slice(&response.public_key)
+            .map_err(|_| PaymentError::InvalidSignature)?;
+        if !self.params.cooperative_keys().contains(&public_key) {
+            return Err(ConfigError::UnknownPublicKey.into());
+        }
+
+        let signature = Signature::from_slice(&response.signature)
+            .map_err(|_| PaymentError::InvalidSignature)?;
+        if signature.sighash_type != sighash_type {
+            return Err(PaymentError::InvalidSighash.into());
+        }
+
+        let previous = psbt.inputs[0].partial_sigs.insert(public_key, signature);
+        let verified =
+            self.params
+                .backend
+                .verify_payment_signature(psbt, &public_key, self.funding_amount());
+        if verified.is_err() {
+            match previous {
+                Some(previous) => psbt.inputs[0].partial_sigs.insert(public_key, previous),
+                None => psbt.inputs[0].partial_sigs.remove(&public_key),
+            };
+        }
+
+        verified
+    }
+
+    /// Returns the sighash type to sign the funding input of `psbt` with.
+    fn funding_input_sighash_type(&self, psbt: &Psbt) -> Result<EcdsaSighashType, SpillError> {
+        let input = psbt
+            .unsigned_tx
+            .inputs
+            .first()
+            .ok_or(PaymentError::MissingInput)?;
+        if input.previous_output != self.funding_outpoint {
+            return Err(PaymentError::FundingOutpointMismatch.into());
+        }
+
+        let sighash_type = psbt
+            .inputs
+            .first()
+            .ok_or(PaymentError::MissingInput)?
+            .ecdsa_hash_ty()
+            .map_err(|_| PaymentError::InvalidSighash)?;
+        if sighash_type != EcdsaSighashType::All
+            && sighash_type != EcdsaSighashType::AllPlusAnyoneCanPay
+        {
+            return Err(PaymentError::InvalidSighash.into());
+        }
+
+        Ok(sighash_type)
+    }
+
+    /// Signs the funding input of `psbt` with `signer`, which must sign for `expected`.
+    fn sign_funding_input(
+        &self,
+        psbt: &mut Psbt,
+        signer: &dyn Signer,
+        expected: PublicKey,
+    ) -> Result<(), SpillError> {
+        let sighash_type = self.funding_input_sighash_type(psbt)?;
+
+        let public_key = signer.public_key();
+        if public_key != expected {
+            return Err(PaymentError::SignerKeyMismatch { public_key }.into());
+        }
+
+        let msg = self
+            .params
+            .backend
+            .signature_hash(psbt, self.funding_amount(), sighash_type);
+        let signature = Signature {
+            signature: signer.sign_ecdsa(msg),
+            sighash_type,
+        };
+        psbt.inputs[0].partial_sigs.insert(expected, signature);
+
+        Ok(())
+    }
+}
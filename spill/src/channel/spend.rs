@@ -0,0 +1,186 @@
+use core::cmp::Ordering;
+
+use bitcoin::{
+    Amount, NumOpResult, Psbt, ScriptPubKeyBuf, Transaction, WitnessProgram,
+    script::{ScriptBuf, ScriptPubKeyBufExt},
+};
+
+use crate::{
+    Channel, CloseError, PaymentError, PaymentInfo, SpendKind, SpillError,
+    channel::backend::ChannelBackend,
+};
+
+impl<B: ChannelBackend + Clone> Channel<B> {
+    /// Returns whether `tx` spends the channel's funding outpoint.
+    ///
+    /// This is a building block for detecting that the funding output was
+    /// double-spent: a payee watching the mempool or the chain can check
+    /// every transaction it sees and refuse payments if a transaction other
+    /// than a channel close spends the funding output. It does not fetch any
+    /// chain data itself.
+    pub fn funding_outpoint_spent_by(&self, tx: &Transaction) -> bool {
+        tx.inputs
+            .iter()
+            .any(|input| input.previous_output == self.funding_outpoint)
+    }
+
+    /// Returns whether broadcasting payment `a` invalidates payment `b`.
+    ///
+    /// Any two valid payments conflict, since they spend the same funding
+    /// outpoint and only one of them can confirm. This returns `false` only
+    /// if one of the PSBTs doesn't spend the funding outpoint at all, which
+    /// means it isn't a payment of this channel.
+    ///
+    /// Use [`Channel::compare_payment_totals`] to pick the payment to
+    /// broadcast among conflicting ones.
+    pub fn payments_conflict(&self, a: &Psbt, b: &Psbt) -> bool {
+        self.funding_outpoint_spent_by(&a.unsigned_tx)
+            && self.funding_outpoint_spent_by(&b.unsigned_tx)
+    }
+
+    /// Compares the amounts two conflicting payments pay to the payee.
+    ///
+    /// Returns `Some(Ordering::Greater)` if `a` pays the payee more than `b`,
+    /// or `None` if the payments don't conflict (see
+    /// [`Channel::payments_conflict`]). The totals are read from the outputs
+    /// paying the payee script without verifying the payments, so both
+    /// should be verified with [`Channel::verify_payment_psbt`] before
+    /// relying on the result.
+    pub fn compare_payment_totals(&self, a: &Psbt, b: &Psbt) -> Option<Ordering> {
+        if !self.payments_conflict(a, b) {
+            return None;
+        }
+
+        let payee_script = self.params.payee_script();
+        let payee_total = |psbt: &Psbt| {
+            psbt.unsigned_tx
+                .outputs
+                .iter()
+                .filter(|o| o.script_pubkey == payee_script)
+                .map(|o| o.amount)
+                .fold(NumOpResult::Valid(Amount::ZERO), |acc, item| acc + item)
+                .into_result()
+                .unwrap_or(Amount::MAX)
+        };
+
+        Some(payee_total(a).cmp(&payee_total(b)))
+    }
+
+    /// Classifies an on-chain transaction spending the channel's funding output.
+    ///
+    /// Inspects the witness of the input spending the funding outpoint and
+    /// reports whether the channel was closed with a payment (cooperative
+    /// branch) or a refund (timelocked branch).
+    ///
+    /// Returns `None` if `tx` does not spend the funding outpoint, or if its
+    /// witness does not match either branch of the funding script.
+    pub fn classify_spend(&self, tx: &Transaction) -> Option<SpendKind> {
+        let input = tx
+            .inputs
+            .iter()
+            .find(|input| input.previous_output == self.funding_outpoint)?;
+
+        self.params.backend.classify_spend(&input.witness)
+    }
+
+    /// Reconstructs the [`PaymentInfo`] of an on-chain payment transaction.
+    ///
+    /// Intended for accounting systems deriving payment details from block
+    /// data without retaining the payment PSBTs. `tx` must be a finalized
+    /// payment spending the funding outpoint through the cooperative branch.
+    /// The amounts are computed like in [`Channel::verify_payment_psbt`],
+    /// with `current` relative to the amount sent so far, so it is zero for
+    /// the close of the latest applied payment. Signatures are not verified.
+    ///
+    /// # Errors
+    ///
+    /// Returns:
+    /// - `SpillError::Close(CloseError::NotChannelSpend)` if `tx` doesn't
+    ///   spend the funding outpoint with a recognized witness.
+    /// - `SpillError::Close(CloseError::NotPaymentClose)` if `tx` is a refund.
+    /// - `SpillError::Payment(PaymentError::MissingPayeeOutput)` if no output
+    ///   pays the payee.
+    /// - `SpillError::Payment(PaymentError::PaymentNotIncremental)` if `tx`
+    ///   pays the payee less than the amount sent so far.
+    /// - `SpillError::Payment(PaymentError::OutputsExceedFundingAmount)` if
+    ///   the outputs exceed the channel capacity.
+    pub fn payment_info_from_tx(&self, tx: &Transaction) -> Result<PaymentInfo, SpillError> {
+        match self.classify_spend(tx) {
+            Some(SpendKind::Payment) => {}
+            Some(SpendKind::Refund) => return Err(CloseError::NotPaymentClose.into()),
+            None => return Err(CloseError::NotChannelSpend.into()),
+        }
+
+        let payee_script = self.params.payee_script();
+        let total = tx
+            .outputs
+            .iter()
+            .find(|o| o.script_pubkey == payee_script)
+            .ok_or(PaymentError::MissingPayeeOutput)?
+            .amount;
+        let current = (total - self.sent)
+            .into_result()
+            .map_err(|_| PaymentError::PaymentNotIncremental)?;
+
+        let total_output = tx
+            .outputs
+            .iter()
+            .map(|o| o.amount)
+            .fold(NumOpResult::Valid(Amount::ZERO), |acc, item| acc + item)
+            .into_result()
+            .map_err(|_| PaymentError::AmountOverflow)?;
+        let fee = (self.params.capacity - total_output)
+            .into_result()
+            .map_err(|_| PaymentError::OutputsExceedFundingAmount)?;
+
+        Ok(PaymentInfo {
+            total,
+            current,
+            fee,
+            payer_change: (total_output - total).into_result().expect(
+                "payment_info_from_tx: internal invariant violated (Amount calculation must be valid)",
+            ),
+        })
+    }
+
+    /// Returns the final `(payee_amount, payer_amount)` split realized by
+    /// an on-chain transaction closing the channel.
+    ///
+    /// For a payment close, the amounts are read from the payee output and
+    /// the payer's change output. For a refund close, the payee receives
+    /// nothing and every output of the transaction is credited to the payer.
+    /// In both cases the on-chain fee is excluded.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SpillError::Close(CloseError::NotChannelSpend)` if `tx` does
+    /// not spend this channel's funding output, or
+    /// `SpillError::Payment(PaymentError::AmountOverflow)` if the outputs
+    /// credited to a party sum above the maximum bitcoin supply, which no
+    /// valid transaction does.
+    pub fn settled_amounts(&self, tx: &Transaction) -> Result<(Amount, Amount), SpillError> {
+        let kind = self.classify_spend(tx).ok_or(CloseError::NotChannelSpend)?;
+
+        let sum_to = |script: Option<&ScriptPubKeyBuf>| {
+            tx.outputs
+                .iter()
+                .filter(|o| script.is_none_or(|s| o.script_pubkey == *s))
+                .map(|o| o.amount)
+                .fold(NumOpResult::Valid(Amount::ZERO), |acc, item| acc + item)
+                .into_result()
+                .map_err(|_| PaymentError::AmountOverflow)
+        };
+
+        match kind {
+            SpendKind::Payment => {
+                let payee_script = self.params.payee_script();
+                let payer_script = ScriptBuf::new_witness_program(&WitnessProgram::p2wpkh(
+                    self.params.payer.try_into()?,
+                ));
+
+                Ok((sum_to(Some(&payee_script))?, sum_to(Some(&payer_script))?))
+            }
+            SpendKind::Refund => Ok((Amount::ZERO, sum_to(None)?)),
+        }
+    }
+}
@@ -0,0 +1,359 @@
+use std::time::SystemTime;
+
+use bitcoin::{
+    Address, Amount, NumOpResult, OutPoint, Psbt, Sequence, Transaction, TxIn, TxOut, Witness,
+    absolute,
+    script::{ScriptBuf, ScriptPubKeyExt},
+    transaction,
+};
+
+use crate::{
+    Channel, FundingError, FundingInfo, RefundError, SpillError, channel::backend::ChannelBackend,
+};
+
+impl<B: ChannelBackend + Clone> Channel<B> {
+    /// Constructs a PSBT splicing additional funds into the channel.
+    ///
+    /// The returned PSBT spends the current funding output together with
+    /// `new_inputs` into a single new funding output of `capacity + additional`
+    /// locked to the same funding script. Any value of `new_inputs` above
+    /// `additional` is left as the transaction fee.
+    ///
+    /// The funding input is spent through the cooperative branch and must be
+    /// signed by the payer and the payee; the new inputs must be signed by
+    /// their owners. Splicing invalidates any payment signed against the
+    /// current funding output, so the payee should obtain a payment for the
+    /// amount already sent spending the new funding output before signing.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SpillError::Funding` variant if:
+    /// - `InsufficientFunds`: `new_inputs` do not cover `additional`.
+    /// - `AmountOverflow`: Amount operation errored.
+    pub fn splice_in_psbt(
+        &self,
+        additional: Amount,
+        new_inputs: &[(OutPoint, TxOut)],
+    ) -> Result<Psbt, SpillError> {
+        let available = new_inputs
+            .iter()
+            .map(|(_, utxo)| utxo.amount)
+            .fold(NumOpResult::Valid(Amount::ZERO), |acc, item| acc + item)
+            .into_result()
+            .map_err(|_| FundingError::AmountOverflow)?;
+
+        if available < additional {
+            return Err(FundingError::InsufficientFunds {
+                available,
+                required: additional,
+            }
+            .into());
+        }
+
+        let capacity = (self.params.capacity + additional)
+            .into_result()
+            .map_err(|_| FundingError::AmountOverflow)?;
+
+        let inputs = std::iter::once(self.funding_outpoint)
+            .chain(new_inputs.iter().map(|(outpoint, _)| *outpoint))
+            .map(|previous_output| TxIn {
+                previous_output,
+                script_sig: ScriptBuf::new(),
+                sequence: Sequence::MAX,
+                witness: Witness::new(),
+            })
+            .collect();
+
+        let tx = Transaction {
+            version: transaction::Version::TWO,
+            lock_time: absolute::LockTime::ZERO,
+            inputs,
+            outputs: vec![TxOut {
+                amount: capacity,
+                script_pubkey: self.params.script_pubkey.clone(),
+            }],
+        };
+
+        let mut psbt = Psbt::from_unsigned_tx(tx)
+            .expect("splice_in_psbt: internal invariant violated (tx must be unsigned)");
+
+        self.params
+            .backend
+            .populate_payment_psbt(&mut psbt, self.funding_utxo.clone());
+        self.params.backend.populate_funding_psbt(&mut psbt, 0);
+
+        for (input, (_, utxo)) in psbt.inputs[1..].iter_mut().zip(new_inputs) {
+            input.witness_utxo = Some(utxo.clone());
+        }
+
+        Ok(psbt)
+    }
+
+    /// Verifies a splice transaction against the channel state.
+    ///
+    /// Ensures that `tx` spends the current funding output and that its
+    /// output at `vout` pays more than the current capacity to the channel's
+    /// funding script. On success, returns a summary of the new funding output.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SpillError::Funding` variant if verification fails:
+    /// - `SpliceMissingFundingInput`: `tx` does not spend the funding outpoint.
+    /// - `OutputNotFound`: No output exists at `vout`.
+    /// - `ScriptMismatch`: The output script does not match the channel's funding script.
+    /// - `NoCapacityIncrease`: The output value does not exceed the current capacity.
+    pub fn verify_splice(&self, tx: &Transaction, vout: u32) -> Result<FundingInfo, SpillError> {
+        if !tx
+            .inputs
+            .iter()
+            .any(|input| input.previous_output == self.funding_outpoint)
+        {
+            return Err(FundingError::SpliceMissingFundingInput.into());
+        }
+
+        let output = tx
+            .outputs
+            .get(vout as usize)
+            .ok_or(FundingError::OutputNotFound)?;
+
+        if output.script_pubkey != self.params.script_pubkey {
+            return Err(FundingError::ScriptMismatch.into());
+        }
+
+        if output.amount <= self.params.capacity {
+            return Err(FundingError::NoCapacityIncrease {
+                found: output.amount,
+                capacity: self.params.capacity,
+            }
+            .into());
+        }
+
+        Ok(FundingInfo {
+            outpoint: OutPoint {
+                txid: tx.compute_txid(),
+                vout,
+            },
+            capacity: output.amount,
+            funding_spk: output.script_pubkey.clone(),
+        })
+    }
+
+    /// Applies a splice transaction to the channel state.
+    ///
+    /// Verifies `tx` using [`Channel::verify_splice`] and, on success, moves
+    /// the channel to the new funding output. The amount already sent is
+    /// preserved and the added capacity is credited to the payer's change.
+    ///
+    /// The refund lock time restarts with the new funding output, so the
+    /// channel counts as opened now, see [`Channel::opened_at`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`Channel::verify_splice`].
+    pub fn apply_splice(&mut self, tx: &Transaction, vout: u32) -> Result<(), SpillError> {
+        let info = self.verify_splice(tx, vout)?;
+
+        let added = (info.capacity - self.params.capacity)
+            .into_result()
+            .expect("apply_splice: internal invariant violated (Amount calculation must be valid)");
+
+        self.payer_change = (self.payer_change + added)
+            .into_result()
+            .map_err(|_| FundingError::AmountOverflow)?;
+        self.params.capacity = info.capacity;
+        self.funding_outpoint = info.outpoint;
+        self.funding_utxo = TxOut {
+            amount: info.capacity,
+            script_pubkey: info.funding_spk,
+        };
+        self.opened_at = SystemTime::now();
+
+        Ok(())
+    }
+
+    /// Constructs a PSBT reducing the channel capacity.
+    ///
+    /// The returned PSBT spends the current funding output into a new funding
+    /// output of `new_capacity`, locked to the same funding script, and pays
+    /// the removed capacity minus `fee` back to the payer at `refund_address`.
+    ///
+    /// The funding input is spent through the cooperative branch and must be
+    /// signed by the payer and the payee. Like splicing in, reducing the
+    /// capacity invalidates any payment signed against the current funding
+    /// output, so the payee should obtain a payment for the amount already
+    /// sent spending the new funding output before signing.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SpillError::Funding` variant if:
+    /// - `ValueTooHigh`: `new_capacity` is not below the current capacity.
+    /// - `InsufficientFunds`: The removed capacity exceeds the payer's change
+    ///   of the latest payment, or does not cover `fee`.
+    ///
+    /// Returns `SpillError::Refund(RefundError::DustOutput)` if the payer's
+    /// refund output would be dust.
+    pub fn reduce_capacity_psbt(
+        &self,
+        new_capacity: Amount,
+        refund_address: &Address,
+        fee: Amount,
+    ) -> Result<Psbt, SpillError> {
+        let removed = self.removed_capacity(new_capacity)?;
+        let amount =
+            (removed - fee)
+                .into_result()
+                .map_err(|_| FundingError::InsufficientFunds {
+                    available: removed,
+                    required: fee,
+                })?;
+
+        let script_pubkey = refund_address.script_pubkey();
+        let dust_limit = script_pubkey.minimal_non_dust();
+        if amount < dust_limit {
+            return Err(RefundError::DustOutput { amount, dust_limit }.into());
+        }
+
+        let tx = Transaction {
+            version: transaction::Version::TWO,
+            lock_time: absolute::LockTime::ZERO,
+            inputs: vec![TxIn {
+                previous_output: self.funding_outpoint,
+                script_sig: ScriptBuf::new(),
+                sequence: Sequence::MAX,
+                witness: Witness::new(),
+            }],
+            outputs: vec![
+                TxOut {
+                    amount: new_capacity,
+                    script_pubkey: self.params.script_pubkey.clone(),
+                },
+                TxOut {
+                    amount,
+                    script_pubkey,
+                },
+            ],
+        };
+
+        let mut psbt = Psbt::from_unsigned_tx(tx)
+            .expect("reduce_capacity_psbt: internal invariant violated (tx must be unsigned)");
+
+        self.params
+            .backend
+            .populate_payment_psbt(&mut psbt, self.funding_utxo.clone());
+        self.params.backend.populate_funding_psbt(&mut psbt, 0);
+
+        Ok(psbt)
+    }
+
+    /// Verifies a capacity reduction transaction against the channel state.
+    ///
+    /// Ensures that `tx` spends the current funding output and that its
+    /// output at `vout` pays less than the current capacity to the channel's
+    /// funding script, while still covering the amount sent and the fee of
+    /// the latest payment. On success, returns a summary of the new funding
+    /// output.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SpillError::Funding` variant if verification fails:
+    /// - `SpliceMissingFundingInput`: `tx` does not spend the funding outpoint.
+    /// - `OutputNotFound`: No output exists at `vout`.
+    /// - `ScriptMismatch`: The output script does not match the channel's funding script.
+    /// - `ValueTooHigh`: The output value is not below the current capacity.
+    /// - `InsufficientFunds`: The removed capacity exceeds the payer's change
+    ///   of the latest payment.
+    pub fn verify_capacity_reduction(
+        &self,
+        tx: &Transaction,
+        vout: u32,
+    ) -> Result<FundingInfo, SpillError> {
+        if !tx
+            .inputs
+            .iter()
+            .any(|input| input.previous_output == self.funding_outpoint)
+        {
+            return Err(FundingError::SpliceMissingFundingInput.into());
+        }
+
+        let output = tx
+            .outputs
+            .get(vout as usize)
+            .ok_or(FundingError::OutputNotFound)?;
+
+        if output.script_pubkey != self.params.script_pubkey {
+            return Err(FundingError::ScriptMismatch.into());
+        }
+
+        self.removed_capacity(output.amount)?;
+
+        Ok(FundingInfo {
+            outpoint: OutPoint {
+                txid: tx.compute_txid(),
+                vout,
+            },
+            capacity: output.amount,
+            funding_spk: output.script_pubkey.clone(),
+        })
+    }
+
+    /// Applies a capacity reduction transaction to the channel state.
+    ///
+    /// Verifies `tx` using [`Channel::verify_capacity_reduction`] and, on
+    /// success, moves the channel to the new funding output. The amount
+    /// already sent is preserved and the removed capacity is debited from
+    /// the payer's change.
+    ///
+    /// The refund lock time restarts with the new funding output, so the
+    /// channel counts as opened now, see [`Channel::opened_at`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`Channel::verify_capacity_reduction`].
+    pub fn apply_capacity_reduction(
+        &mut self,
+        tx: &Transaction,
+        vout: u32,
+    ) -> Result<(), SpillError> {
+        let info = self.verify_capacity_reduction(tx, vout)?;
+
+        let removed = (self.params.capacity - info.capacity).into_result().expect(
+            "apply_capacity_reduction: internal invariant violated (Amount calculation must be valid)",
+        );
+
+        self.payer_change = (self.payer_change - removed).into_result().expect(
+            "apply_capacity_reduction: internal invariant violated (Amount calculation must be valid)",
+        );
+        self.params.capacity = info.capacity;
+        self.funding_outpoint = info.outpoint;
+        self.funding_utxo = TxOut {
+            amount: info.capacity,
+            script_pubkey: info.funding_spk,
+        };
+        self.opened_at = SystemTime::now();
+
+        Ok(())
+    }
+
+    /// Returns the capacity removed by reducing it to `new_capacity`, which
+    /// must be below the current capacity and keep the latest payment valid.
+    fn removed_capacity(&self, new_capacity: Amount) -> Result<Amount, SpillError> {
+        let removed = (self.params.capacity - new_capacity)
+            .into_result()
+            .ok()
+            .filter(|removed| *removed > Amount::ZERO)
+            .ok_or(FundingError::ValueTooHigh {
+                found: new_capacity,
+                expected: self.params.capacity,
+            })?;
+
+        if removed > self.payer_change {
+            return Err(FundingError::InsufficientFunds {
+                available: self.payer_change,
+                required: removed,
+            }
+            .into());
+        }
+
+        Ok(removed)
+    }
+}
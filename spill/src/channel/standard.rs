@@ -0,0 +1,103 @@
+use bitcoin::{
+    Amount, FeeRate, Transaction, Weight,
+    policy::MAX_STANDARD_TX_WEIGHT,
+    script::{ScriptExt, ScriptPubKeyExt},
+    transaction::TransactionExt,
+};
+
+use crate::{SpillError, StandardnessError, channel::MAX_OP_RETURN_SIZE};
+
+/// Checks that a finalized channel transaction passes the main mempool policy rules.
+///
+/// This is a best-effort safety net to run before broadcasting a payment,
+/// refund or close built by this crate, not a full standardness check. It
+/// verifies that:
+/// - The transaction version is standard.
+/// - The weight is within the standard limit.
+/// - Every output uses a standard script type (P2PKH, P2SH, segwit v0, P2TR,
+///   P2A or an `OP_RETURN` of at most 80 bytes of data).
+/// - No output other than an `OP_RETURN` is below its dust limit.
+/// - The fee, computed as `input_value` minus the outputs, pays at least
+///   `min_relay_feerate`.
+///
+/// `input_value` is the total value spent by `tx`, the channel capacity for
+/// transactions spending the funding output. The weight is taken from `tx`
+/// as is, so it must carry its final witness. Zero-fee closes built with
+/// [`Channel::cooperative_close_with_anchor`] rely on package relay and are
+/// rejected by the fee check.
+///
+/// # Errors
+///
+/// Returns a `SpillError::Standardness` variant if:
+/// - `NonStandardVersion`: The transaction version is not standard.
+/// - `TooLarge`: The transaction weight exceeds the standard limit.
+/// - `NonStandardOutput`: An output script is not standard.
+/// - `DustOutput`: An output is below its dust limit.
+/// - `OutputsExceedInput`: The outputs exceed `input_value`.
+/// - `FeeTooLow`: The fee is below the minimum relay fee.
+///
+/// [`Channel::cooperative_close_with_anchor`]: crate::Channel::cooperative_close_with_anchor
+pub fn check_standardness(
+    tx: &Transaction,
+    min_relay_feerate: FeeRate,
+    input_value: Amount,
+) -> Result<(), SpillError> {
+    if !tx.version.is_standard() {
+        return Err(StandardnessError::NonStandardVersion.into());
+    }
+
+    let weight = tx.weight();
+    let max_weight = Weight::from_wu(MAX_STANDARD_TX_WEIGHT as u64);
+    if weight > max_weight {
+        return Err(StandardnessError::TooLarge { weight, max_weight }.into());
+    }
+
+    for (index, output) in tx.outputs.iter().enumerate() {
+        let script = &output.script_pubkey;
+        if script.is_op_return() {
+            // OP_RETURN, a push opcode and up to two length bytes.
+            if script.len() > MAX_OP_RETURN_SIZE + 3 {
+                return Err(StandardnessError::NonStandardOutput { index }.into());
+            }
+            continue;
+        }
+
+        let standard = script.is_p2pkh()
+            || script.is_p2sh()
+            || script.is_p2wpkh()
+            || script.is_p2wsh()
+            || script.is_p2tr()
+            || script.is_p2a();
+        if !standard {
+            return Err(StandardnessError::NonStandardOutput { index }.into());
+        }
+
+        let dust_limit = script.minimal_non_dust();
+        if output.amount < dust_limit {
+            return Err(StandardnessError::DustOutput {
+                index,
+                amount: output.amount,
+                dust_limit,
+            }
+            .into());
+        }
+    }
+
+    let outputs = tx
+        .outputs
+        .iter()
+        .try_fold(Amount::ZERO, |acc, output| {
+            (acc + output.amount).into_result()
+        })
+        .map_err(|_| StandardnessError::OutputsExceedInput)?;
+    let fee = (input_value - outputs)
+        .into_result()
+        .map_err(|_| StandardnessError::OutputsExceedInput)?;
+
+    let min_fee = min_relay_feerate.to_fee(weight);
+    if fee < min_fee {
+        return Err(StandardnessError::FeeTooLow { fee, min_fee }.into());
+    }
+
+    Ok(())
+}
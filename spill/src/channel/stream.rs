@@ -0,0 +1,135 @@
+use bitcoin::{Amount, FeeRate, Psbt};
+
+use crate::{
+    Channel, PaymentError, PaymentInfo, Signer, SpillError, channel::backend::ChannelBackend,
+};
+
+/// Streams successive payments over a channel at a fixed feerate.
+///
+/// `PaymentStream` is a higher-level API over [`Channel::next_payment_to_total`]
+/// for pay-per-use services that repeatedly pay a few more sats. It computes
+/// the fee of every payment from the configured feerate and the estimated
+/// weight of the payment transaction.
+///
+/// Each payment is signed with the payer's [`Signer`] and applied to the
+/// channel before it is returned, so the channel state stays authoritative:
+/// the total paid is always the channel's amount sent, and the PSBT returned
+/// by the latest [`PaymentStream::pay`] is the one to hand to the payee.
+pub struct PaymentStream<'a, B: ChannelBackend + Clone> {
+    channel: &'a mut Channel<B>,
+    signer: &'a dyn Signer,
+    fee: Amount,
+}
+
+impl<'a, B: ChannelBackend + Clone> PaymentStream<'a, B> {
+    /// Creates a stream paying at `feerate` over `channel`, signing its
+    /// payments with the payer's `signer`.
+    pub fn new(channel: &'a mut Channel<B>, signer: &'a dyn Signer, feerate: FeeRate) -> Self {
+        let fee = channel.params.estimate_lifecycle_cost(feerate, 0).close_fee;
+
+        PaymentStream {
+            channel,
+            signer,
+            fee,
+        }
+    }
+
+    /// Constructs, signs and applies the next payment, paying `increment`
+    /// more to the payee.
+    ///
+    /// On success, the amount sent over the channel increases by
+    /// `increment` and the signed PSBT is returned. On failure, neither the
+    /// stream nor the channel is changed.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SpillError::Payment` variant if:
+    /// - `PaymentNotIncremental`: `increment` is zero.
+    /// - `ExceedsCapacity`: The channel is exhausted: the new total plus the
+    ///   fee exceeds the channel capacity.
+    /// - `SignerKeyMismatch`: The stream's signer does not sign for the payer key.
+    /// - `AmountOverflow`: Amount operation errored.
+    ///
+    /// Or the errors of [`Channel::apply_payment`] for the signed payment.
+    pub fn pay(&mut self, increment: Amount) -> Result<Psbt, SpillError> {
+        let total = (self.channel.sent + increment)
+            .into_result()
+            .map_err(|_| PaymentError::AmountOverflow)?;
+
+        let mut psbt = self.channel.next_payment_to_total(total, self.fee)?;
+        self.channel.sign_payment_as_payer(&mut psbt, self.signer)?;
+        self.channel.apply_payment(&psbt)?;
+
+        Ok(psbt)
+    }
+
+    /// Returns the cumulative amount paid to the payee, including the
+    /// amount sent before the stream was created.
+    pub fn total_paid(&self) -> Amount {
+        self.channel.sent
+    }
+
+    /// Returns the fee paid by every payment of the stream.
+    pub fn fee(&self) -> Amount {
+        self.fee
+    }
+}
+
+/// Receives successive payments over a channel, keeping the latest one.
+///
+/// `ReceiveStream` is the payee-side counterpart of [`PaymentStream`]. Each
+/// incoming payment PSBT is verified with [`Channel::verify_payment_psbt`]
+/// and applied to the channel. Since every payment supersedes the previous
+/// ones, the stream only keeps the highest-value PSBT received, which the
+/// payee signs and broadcasts to close the channel.
+///
+/// Payments arriving out of order are rejected once a higher payment has
+/// been applied, and leave the stream unchanged.
+pub struct ReceiveStream<'a, B: ChannelBackend + Clone> {
+    channel: &'a mut Channel<B>,
+    best: Option<Psbt>,
+}
+
+impl<'a, B: ChannelBackend + Clone> ReceiveStream<'a, B> {
+    /// Creates a stream receiving payments over `channel`.
+    ///
+    /// The stream holds no payment until the first call to
+    /// [`ReceiveStream::receive`] succeeds, even if payments were applied to
+    /// `channel` before.
+    pub fn new(channel: &'a mut Channel<B>) -> Self {
+        ReceiveStream {
+            channel,
+            best: None,
+        }
+    }
+
+    /// Verifies and applies an incoming payment.
+    ///
+    /// On success, `psbt` becomes the best payment of the stream and the
+    /// claimable amount increases to its total. On failure, neither the
+    /// stream nor the channel is changed.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`Channel::verify_payment_psbt`]. A stale or
+    /// replayed payment, whose total is not above the claimable amount,
+    /// returns `SpillError::Payment(PaymentError::PaymentNotIncremental)`.
+    pub fn receive(&mut self, psbt: &Psbt) -> Result<PaymentInfo, SpillError> {
+        let payment = self.channel.verify_payment_psbt(psbt)?;
+        self.channel.record_payment(&payment);
+        self.best = Some(psbt.clone());
+
+        Ok(payment)
+    }
+
+    /// Returns the highest-value payment received by the stream, if any.
+    pub fn best_payment(&self) -> Option<&Psbt> {
+        self.best.as_ref()
+    }
+
+    /// Returns the cumulative amount the payee can claim by closing the
+    /// channel, including payments applied before the stream was created.
+    pub fn claimable(&self) -> Amount {
+        self.channel.sent
+    }
+}
@@ -1,23 +1,51 @@
 use crate::{
-    Channel, ChannelParams, FundingError, PaymentError, SpillError,
-    channel::{backend::ChannelBackend, payment::PaymentInfo},
+    Channel, ChannelParams, CloseError, FundingError, PaymentError, RefundError, SpillError,
+    channel::{MAX_OP_RETURN_SIZE, backend::ChannelBackend, payment::PaymentInfo},
 };
-use bitcoin::{Amount, NumOpResult, OutPoint, Psbt, Sequence, Transaction, absolute::LockTime};
+use std::time::SystemTime;
+
+use bitcoin::{
+    Amount, EcdsaSighashType, NumOpResult, OutPoint, Psbt, ScriptPubKeyBuf, Sequence, Transaction,
+    TxOut, WitnessProgram,
+    absolute::LockTime,
+    primitives::relative,
+    script::{ScriptBuf, ScriptPubKeyBufExt, ScriptPubKeyExt},
+    transaction,
+};
+
+/// Information about a verified funding transaction.
+///
+/// `FundingInfo` summarizes the funding output that was checked by
+/// [`ChannelParams::verify_funding_tx`], allowing callers to inspect
+/// what was verified without accessing the channel's internal state.
+#[derive(Debug, Clone)]
+pub struct FundingInfo {
+    /// The outpoint of the channel's funding output.
+    pub outpoint: OutPoint,
+    /// The value locked in the funding output.
+    pub capacity: Amount,
+    /// The `script_pubkey` of the funding output.
+    pub funding_spk: ScriptPubKeyBuf,
+}
 
 impl<B: ChannelBackend + Clone> ChannelParams<B> {
     /// Verifies a funding transaction against the channel parameters.
     ///
     /// Ensures that the provided transaction and outpoint match the channel's
     /// expected funding transaction. If verification succeeds, returns a new
-    /// [`Channel`] initialized with the funding outpoint and UTXO.
+    /// [`Channel`] initialized with the funding outpoint and UTXO. A summary of
+    /// the verified output is available through [`Channel::funding_info`].
     ///
     /// # Errors
     ///
     /// Returns a `SpillError::Funding` variant if verification fails:
     /// - `TxidMismatch`: Transaction ID does not match the funding outpoint.
     /// - `OutputNotFound`: No output exists at the specified index.
-    /// - `ValueMismatch`: Output value does not match the channel capacity.
+    /// - `ValueTooLow`: Output value is below the channel capacity.
+    /// - `ValueTooHigh`: Output value is above the channel capacity.
     /// - `ScriptMismatch`: Output script does not match the channel's funding script.
+    /// - `AmbiguousFundingOutput`: Several outputs match the expected funding
+    ///   output and the channel policy sets `reject_ambiguous_funding`.
     pub fn verify_funding_tx(
         &self,
         tx: &Transaction,
@@ -32,24 +60,236 @@ impl<B: ChannelBackend + Clone> ChannelParams<B> {
             .get(outpoint.vout as usize)
             .ok_or(FundingError::OutputNotFound)?;
 
-        if output.amount != self.capacity {
-            return Err(FundingError::ValueMismatch.into());
-        }
+        self.verify_funding_output(output)?;
 
-        if output.script_pubkey != self.script_pubkey {
-            return Err(FundingError::ScriptMismatch.into());
+        if self.policy.reject_ambiguous_funding {
+            let expected = self.expected_funding_output();
+            let count = tx.outputs.iter().filter(|o| **o == expected).count();
+            if count > 1 {
+                return Err(FundingError::AmbiguousFundingOutput { count }.into());
+            }
         }
 
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            channel_id = %outpoint,
+            capacity = %output.amount,
+            "funding verified"
+        );
+
         Ok(Channel {
             params: self.clone(),
             funding_outpoint: outpoint,
             funding_utxo: output.clone(),
             sent: Amount::ZERO,
+            payer_change: output.amount,
+            opened_at: SystemTime::now(),
+        })
+    }
+
+    /// Verifies several candidate funding transactions, such as RBF replacements.
+    ///
+    /// Runs [`ChannelParams::verify_funding_tx`] on each `(tx, outpoint)`
+    /// candidate and returns the results in the same order, so the payee can
+    /// track which candidates would open the channel while the funding is
+    /// fee-bumped before confirmation.
+    ///
+    /// This is a verification helper, not a chain oracle: replacements
+    /// conflict with each other, so at most one candidate can confirm. Only
+    /// the channel of the candidate that actually confirmed should be
+    /// trusted and used for payments.
+    pub fn verify_funding_candidates(
+        &self,
+        candidates: &[(Transaction, OutPoint)],
+    ) -> Vec<Result<Channel<B>, SpillError>> {
+        candidates
+            .iter()
+            .map(|(tx, outpoint)| self.verify_funding_tx(tx, *outpoint))
+            .collect()
+    }
+
+    /// Verifies a funding transaction together with the outputs its inputs spend.
+    ///
+    /// Behaves like [`ChannelParams::verify_funding_tx`], but also checks
+    /// that the funding transaction's inputs are fully accounted for:
+    /// `prevouts` must hold the output spent by each input, in input order,
+    /// and their value must cover all outputs of `tx`. This catches a
+    /// malformed funding transaction, however many UTXOs it combines, before
+    /// the channel opens.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SpillError::Funding` variant if verification fails:
+    /// - `PrevoutCountMismatch`: `prevouts` doesn't have one entry per input.
+    /// - `InsufficientFunds`: The inputs do not cover the outputs of `tx`.
+    /// - `AmountOverflow`: Amount operation errored.
+    ///
+    /// Also returns any error returned by [`ChannelParams::verify_funding_tx`].
+    pub fn verify_funding_tx_with_prevouts(
+        &self,
+        tx: &Transaction,
+        outpoint: OutPoint,
+        prevouts: &[TxOut],
+    ) -> Result<Channel<B>, SpillError> {
+        let channel = self.verify_funding_tx(tx, outpoint)?;
+
+        if prevouts.len() != tx.inputs.len() {
+            return Err(FundingError::PrevoutCountMismatch {
+                inputs: tx.inputs.len(),
+                prevouts: prevouts.len(),
+            }
+            .into());
+        }
+
+        let available = prevouts
+            .iter()
+            .map(|prevout| prevout.amount)
+            .fold(NumOpResult::Valid(Amount::ZERO), |acc, item| acc + item)
+            .into_result()
+            .map_err(|_| FundingError::AmountOverflow)?;
+        let required = tx
+            .outputs
+            .iter()
+            .map(|output| output.amount)
+            .fold(NumOpResult::Valid(Amount::ZERO), |acc, item| acc + item)
+            .into_result()
+            .map_err(|_| FundingError::AmountOverflow)?;
+
+        if available < required {
+            return Err(FundingError::InsufficientFunds {
+                available,
+                required,
+            }
+            .into());
+        }
+
+        Ok(channel)
+    }
+
+    /// Resumes a channel from persisted state without a funding transaction.
+    ///
+    /// Intended for restoring a [`Channel`] whose funding transaction was
+    /// verified with [`ChannelParams::verify_funding_tx`] in an earlier
+    /// session. Since the stored `funding_utxo` is used for sighash
+    /// computation, it is checked against the channel parameters like the
+    /// funding output of [`ChannelParams::verify_funding_tx`], so that a
+    /// corrupted or tampered state cannot produce a diverging channel.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SpillError::Funding` variant if:
+    /// - `ValueTooLow`: `funding_utxo` value is below the channel capacity.
+    /// - `ValueTooHigh`: `funding_utxo` value is above the channel capacity.
+    /// - `ScriptMismatch`: `funding_utxo` script does not match the channel's funding script.
+    ///
+    /// Returns `SpillError::Payment(PaymentError::OutputsExceedFundingAmount)`
+    /// if `sent` plus `payer_change` exceeds the channel capacity.
+    ///
+    /// The resumed channel is considered opened now; restore the original
+    /// opening time with [`Channel::with_opened_at`].
+    pub fn resume_channel(
+        &self,
+        funding_outpoint: OutPoint,
+        funding_utxo: TxOut,
+        sent: Amount,
+        payer_change: Amount,
+    ) -> Result<Channel<B>, SpillError> {
+        self.verify_funding_output(&funding_utxo)?;
+
+        if (sent + payer_change)
+            .into_result()
+            .map_or(true, |total| total > self.capacity)
+        {
+            return Err(PaymentError::OutputsExceedFundingAmount.into());
+        }
+
+        Ok(Channel {
+            params: self.clone(),
+            funding_outpoint,
+            funding_utxo,
+            sent,
+            payer_change,
+            opened_at: SystemTime::now(),
         })
     }
+
+    /// Checks that `output` is the channel funding output.
+    fn verify_funding_output(&self, output: &TxOut) -> Result<(), SpillError> {
+        let expected = self.expected_funding_output();
+
+        if output.amount < expected.amount {
+            return Err(FundingError::ValueTooLow {
+                found: output.amount,
+                expected: expected.amount,
+            }
+            .into());
+        }
+
+        if output.amount > expected.amount {
+            return Err(FundingError::ValueTooHigh {
+                found: output.amount,
+                expected: expected.amount,
+            }
+            .into());
+        }
+
+        if output.script_pubkey != expected.script_pubkey {
+            return Err(FundingError::ScriptMismatch.into());
+        }
+
+        Ok(())
+    }
 }
 
 impl<B: ChannelBackend + Clone> Channel<B> {
+    /// Returns a summary of the verified funding output backing this channel.
+    pub fn funding_info(&self) -> FundingInfo {
+        FundingInfo {
+            outpoint: self.funding_outpoint,
+            capacity: self.funding_utxo.amount,
+            funding_spk: self.funding_utxo.script_pubkey.clone(),
+        }
+    }
+
+    /// Checks that the funding transaction is sufficiently confirmed.
+    ///
+    /// Computes the number of confirmations of the funding transaction from
+    /// the height of the block that includes it and the current chain tip,
+    /// and returns it if it is at least `min_confirmations`.
+    ///
+    /// This method does not validate block inclusion or that the block is in
+    /// the best chain; the caller is responsible for obtaining both heights
+    /// from a trusted chain source.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SpillError::Funding` variant if:
+    /// - `InvalidConfirmationHeight`: `funding_height` is above `tip_height`,
+    ///   or the confirmation count does not fit in a `u32`.
+    /// - `InsufficientConfirmations`: The funding transaction has fewer than
+    ///   `min_confirmations` confirmations.
+    pub fn confirm_funding(
+        &self,
+        funding_height: u32,
+        tip_height: u32,
+        min_confirmations: u32,
+    ) -> Result<u32, SpillError> {
+        let confirmations = tip_height
+            .checked_sub(funding_height)
+            .and_then(|depth| depth.checked_add(1))
+            .ok_or(FundingError::InvalidConfirmationHeight)?;
+
+        if confirmations < min_confirmations {
+            return Err(FundingError::InsufficientConfirmations {
+                confirmations,
+                required: min_confirmations,
+            }
+            .into());
+        }
+
+        Ok(confirmations)
+    }
+
     /// Verifies a payment PSBT against the channel state.
     ///
     /// Ensures that the provided PSBT correctly represents a payment from the
@@ -57,6 +297,13 @@ impl<B: ChannelBackend + Clone> Channel<B> {
     /// succeeds, returns a [`PaymentInfo`] containing the cumulative and
     /// incremental amounts and the fee.
     ///
+    /// The payee's signature is optional while the payment is handed from
+    /// payer to payee, but if present it is verified as well.
+    ///
+    /// Unless the channel policy sets `expected_payee_index`, the payee
+    /// output is located by its script, so payments whose outputs were
+    /// reordered, e.g. sorted as per BIP69, are accepted.
+    ///
     /// # Errors
     ///
     /// Returns a `SpillError::Payment` variant if verification fails:
@@ -67,18 +314,473 @@ impl<B: ChannelBackend + Clone> Channel<B> {
     /// - `WitnessUtxoMismatch`: The witness UTXO does not match the channel funding UTXO.
     /// - `MissingWitnessScript`: The input lacks a witness script.
     /// - `WitnessScriptMismatch`: The witness script does not match the channel funding script.
-    /// - `InvalidSequence`: The input sequence is not MAX.
-    /// - `NonZeroLockTime`: The transaction lock time is not zero.
+    /// - `LocktimeMismatch`: The witness script only differs from the channel funding script
+    ///   in its refund lock time.
+    /// - `InvalidSequence`: The input sequence is not MAX, or `ENABLE_LOCKTIME_NO_RBF`
+    ///   if the channel policy sets `allow_timelocked_payments`.
+    /// - `NonZeroLockTime`: The transaction lock time is not zero, unless the channel
+    ///   policy sets `allow_timelocked_payments` and the sequence enables it.
+    /// - `LockTimeTooLate`: The transaction lock time is later than the
+    ///   `max_payment_lock_time` of the channel policy.
+    /// - `InvalidVersion`: The transaction version is not 2.
+    /// - `TooManyOutputs`: The PSBT has more outputs than allowed by the channel policy.
     /// - `MissingPayeeOutput`: No output exists for the payee.
+    /// - `PayeeOutputWrongIndex`: The payee output is not at the index required by
+    ///   the channel policy.
+    /// - `PayeeOutputDust`: The payee output is below the dust limit.
     /// - `PaymentNotIncremental`: The payment does not increase the cumulative amount.
+    /// - `IncrementTooSmall`: The payment increases the cumulative amount by less than
+    ///   the `min_payment_increment` of the channel policy.
     /// - `OutputsExceedFundingAmount`: The total outputs exceed the channel capacity.
+    /// - `InconsistentChange`: The payer's change does not decrease relative to the
+    ///   last applied payment.
     /// - `MissingSignature`: No signature from the payer is present.
-    /// - `InvalidSighash`: The signature sighash type is unsupported (must be ALL or ALL|ANYONECANPAY).
-    /// - `InvalidSignature`: The payer's signature is invalid.
+    /// - `InvalidSighash`: The signature sighash type is unsupported (must be ALL or ALL|ANYONECANPAY,
+    ///   or exactly ALL if the channel policy sets `strict_sighash_all`), or differs from the
+    ///   sighash type recorded in the PSBT input.
+    /// - `InvalidSignature`: The payer's signature is invalid, or the payee's
+    ///   signature is present and invalid.
     /// - `AmountOverflow`: Amount operation errored.
     /// - `ScriptPubKeyMismatch`: The input's script_pubkey does not match the channel funding
     ///   script_pubkey.
     pub fn verify_payment_psbt(&self, psbt: &Psbt) -> Result<PaymentInfo, SpillError> {
+        self.verify_payment_psbt_against(psbt, self.sent, self.payer_change)
+    }
+
+    /// Verifies a payment PSBT against an externally supplied previous state.
+    ///
+    /// Behaves like [`Channel::verify_payment_psbt`], but checks that the
+    /// payment increases `previous_sent` and decreases `previous_change`
+    /// instead of the amounts recorded in the channel. This is intended for
+    /// stateless servers that load the cumulative amount sent and the
+    /// payer's change of the latest payment from storage on each request
+    /// instead of keeping the channel in memory. Before the first payment,
+    /// `previous_change` is the channel capacity. The returned `current`
+    /// amount is relative to `previous_sent`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`Channel::verify_payment_psbt`].
+    pub fn verify_payment_psbt_against(
+        &self,
+        psbt: &Psbt,
+        previous_sent: Amount,
+        previous_change: Amount,
+    ) -> Result<PaymentInfo, SpillError> {
+        let payment = self.verify_payment(psbt, previous_sent, previous_change, false, true)?;
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            channel_id = %self.funding_outpoint,
+            total = %payment.total,
+            current = %payment.current,
+            fee = %payment.fee,
+            "payment verified"
+        );
+
+        Ok(payment)
+    }
+
+    /// Verifies a fee replacement of the latest applied payment.
+    ///
+    /// Intended for PSBTs built with [`Channel::bump_payment_fee`]. Runs the
+    /// same checks as [`Channel::verify_payment_psbt`], except that the
+    /// payment must pay exactly the amount already sent instead of
+    /// increasing it, and must pay a strictly higher fee, taken from the
+    /// payer's change. The returned `current` amount is zero.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`Channel::verify_payment_psbt`], except
+    /// `PaymentNotIncremental`, `IncrementTooSmall` and `InconsistentChange`,
+    /// and a `SpillError::Payment` variant if:
+    /// - `ReplacementAmountMismatch`: The payee amount differs from the amount sent.
+    /// - `FeeNotIncreased`: The fee is not higher than the fee of the latest payment.
+    pub fn verify_fee_bump_psbt(&self, psbt: &Psbt) -> Result<PaymentInfo, SpillError> {
+        self.verify_payment(psbt, self.sent, self.payer_change, true, true)
+    }
+
+    /// Verifies an anchor close PSBT built with [`Channel::cooperative_close_with_anchor`].
+    ///
+    /// The close pays exactly the amount already sent, so it is checked
+    /// against the channel state rather than as a new payment: `psbt` must
+    /// be the close rebuilt from the channel state with the value of its P2A
+    /// output, optionally followed by a zero-value `OP_RETURN` output, and
+    /// must carry a valid payer signature. The payee's signature is checked
+    /// if present. The returned `current` amount and fee are zero.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SpillError::Close` variant if:
+    /// - `NothingSent`: No payment has been applied to the channel.
+    /// - `AnchorCloseMismatch`: The PSBT differs from the expected close.
+    ///
+    /// Returns a `SpillError::Payment` variant if:
+    /// - `MissingSignature`: No signature from the payer is present.
+    /// - `InvalidSighash`: A signature sighash type is unsupported.
+    /// - `InvalidSignature`: The payer's signature is invalid, or the payee's
+    ///   signature is present and invalid.
+    pub fn verify_anchor_close_psbt(&self, psbt: &Psbt) -> Result<PaymentInfo, SpillError> {
+        let outputs = &psbt.unsigned_tx.outputs;
+        let anchor_value = outputs
+            .iter()
+            .find(|output| output.script_pubkey.is_p2a())
+            .ok_or(CloseError::AnchorCloseMismatch)?
+            .amount;
+
+        let mut expected = self
+            .cooperative_close_with_anchor(anchor_value, None)
+            .map_err(|error| match error {
+                SpillError::Payment(PaymentError::ExceedsCapacity { .. }) => {
+                    CloseError::AnchorCloseMismatch.into()
+                }
+                error => error,
+            })?;
+
+        if outputs.len() == expected.unsigned_tx.outputs.len() + 1
+            && let Some(op_return) = outputs.last()
+            && op_return.amount == Amount::ZERO
+            && op_return.script_pubkey.is_op_return()
+            // OP_RETURN, a push opcode and up to two length bytes.
+            && op_return.script_pubkey.len() <= MAX_OP_RETURN_SIZE + 3
+        {
+            expected.unsigned_tx.outputs.push(op_return.clone());
+        }
+
+        if psbt.inputs.len() != 1
+            || psbt.unsigned_tx != expected.unsigned_tx
+            || psbt.inputs[0].witness_utxo != expected.inputs[0].witness_utxo
+            || psbt.inputs[0].witness_script != expected.inputs[0].witness_script
+        {
+            return Err(CloseError::AnchorCloseMismatch.into());
+        }
+
+        self.verify_payment_signatures(psbt)?;
+
+        let payer_script =
+            ScriptBuf::new_witness_program(&WitnessProgram::p2wpkh(self.params.payer.try_into()?));
+        let payer_change = outputs
+            .iter()
+            .find(|output| output.script_pubkey == payer_script)
+            .map_or(Amount::ZERO, |output| output.amount);
+
+        Ok(PaymentInfo {
+            total: self.sent,
+            current: Amount::ZERO,
+            fee: Amount::ZERO,
+            payer_change,
+        })
+    }
+
+    /// Verifies that a sequence of payment PSBTs forms a valid chain.
+    ///
+    /// Each PSBT is verified with [`Channel::verify_payment_psbt`] as if all
+    /// the previous PSBTs had been applied, so the cumulative totals must be
+    /// strictly increasing in order. Returns the [`PaymentInfo`] of each step,
+    /// with `current` relative to the previous payment of the chain.
+    ///
+    /// The channel state is not modified; the payments still have to be
+    /// applied with [`Channel::apply_payment`].
+    ///
+    /// Each payment costs about as much as a call to
+    /// [`Channel::verify_payment_psbt`], which is dominated by ECDSA signature
+    /// verification. Computing the BIP143 sighash is a small fraction of it,
+    /// and no sighash work can be shared between payments since each one
+    /// commits to different output values. The `verify` benchmark compares
+    /// both approaches.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SpillError::Payment` variant if:
+    /// - `ChainOutOfOrder`: A payment does not increase the cumulative amount
+    ///   of the previous one.
+    /// - `ChainInvalidPayment`: A payment failed verification for any other
+    ///   reason, wrapping the error returned by [`Channel::verify_payment_psbt`].
+    pub fn verify_payment_chain(&self, psbts: &[Psbt]) -> Result<Vec<PaymentInfo>, SpillError> {
+        let mut channel = Channel {
+            params: self.params.clone(),
+            funding_outpoint: self.funding_outpoint,
+            funding_utxo: self.funding_utxo.clone(),
+            sent: self.sent,
+            payer_change: self.payer_change,
+            opened_at: self.opened_at,
+        };
+
+        let mut payments = Vec::with_capacity(psbts.len());
+        for (index, psbt) in psbts.iter().enumerate() {
+            let payment = channel
+                .verify_payment_psbt(psbt)
+                .map_err(|error| match error {
+                    SpillError::Payment(PaymentError::PaymentNotIncremental) => {
+                        PaymentError::ChainOutOfOrder { index }
+                    }
+                    error => PaymentError::ChainInvalidPayment {
+                        index,
+                        error: Box::new(error),
+                    },
+                })?;
+
+            channel.sent = payment.total;
+            channel.payer_change = payment.payer_change;
+            payments.push(payment);
+        }
+
+        Ok(payments)
+    }
+
+    /// Verifies the structure of a payment PSBT without checking signatures.
+    ///
+    /// Runs the same structural checks as [`Channel::verify_payment_psbt`]
+    /// (outpoint, scripts, sequence, lock time, version and amounts) but
+    /// skips the secp256k1 signature verification. This is a fast path for
+    /// a payer replaying payments they produced themselves.
+    ///
+    /// **This method does NOT validate signatures.** It must never be used
+    /// by a payee deciding whether to accept funds.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`Channel::verify_payment_psbt`], except
+    /// for `MissingSignature`, `InvalidSighash` and `InvalidSignature`.
+    pub fn verify_payment_psbt_structural(&self, psbt: &Psbt) -> Result<PaymentInfo, SpillError> {
+        self.verify_payment(psbt, self.sent, self.payer_change, false, false)
+    }
+
+    /// Verifies a payment PSBT built by the payer, before the payer signs it.
+    ///
+    /// Lets the payer catch mistakes in their own construction before
+    /// committing a signature. Runs the structural checks of
+    /// [`Channel::verify_payment_psbt_structural`] and returns the
+    /// [`PaymentInfo`] the payee will see once the PSBT is signed.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`Channel::verify_payment_psbt_structural`].
+    pub fn verify_own_payment(&self, psbt: &Psbt) -> Result<PaymentInfo, SpillError> {
+        self.verify_payment_psbt_structural(psbt)
+    }
+
+    /// Verifies the payee's signature on a payment PSBT.
+    ///
+    /// Mirrors the payer signature check performed by
+    /// [`Channel::verify_payment_psbt`], allowing the payer to confirm the
+    /// payee's countersignature before finalizing. Only the signature is
+    /// checked; the payment itself should be verified separately.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SpillError::Payment` variant if:
+    /// - `MissingInput`: The PSBT has no inputs.
+    /// - `FundingOutpointMismatch`: The PSBT doesn't reference the funding outpoint.
+    /// - `MissingSignature`: No signature from the payee is present.
+    /// - `InvalidSighash`: The signature sighash type is unsupported.
+    /// - `InvalidSignature`: The payee's signature is invalid.
+    pub fn verify_payee_signature(&self, psbt: &Psbt) -> Result<(), SpillError> {
+        let outpoint = psbt
+            .unsigned_tx
+            .inputs
+            .first()
+            .ok_or(PaymentError::MissingInput)?
+            .previous_output;
+
+        if outpoint != self.funding_outpoint {
+            return Err(PaymentError::FundingOutpointMismatch.into());
+        }
+
+        self.params.backend.verify_payment_signature(
+            psbt,
+            &self.params.payee,
+            self.funding_amount(),
+        )
+    }
+
+    /// Verifies the payer's signature on a refund PSBT.
+    ///
+    /// Computes the sighash of the refund input and checks the payer's
+    /// signature against it, regardless of whether the refund lock time has
+    /// matured. This lets the payer confirm the refund is correctly signed
+    /// long before it can be broadcast. Only the signature is checked; the
+    /// outputs and sequence of the refund are not.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SpillError::Payment` variant if:
+    /// - `MissingInput`: The PSBT has no inputs.
+    /// - `FundingOutpointMismatch`: The PSBT doesn't reference the funding outpoint.
+    /// - `MissingSignature`: No signature from the payer is present.
+    /// - `InvalidSighash`: The signature sighash type is unsupported.
+    /// - `InvalidSignature`: The payer's signature is invalid.
+    pub fn verify_refund_signature(&self, psbt: &Psbt) -> Result<(), SpillError> {
+        let outpoint = psbt
+            .unsigned_tx
+            .inputs
+            .first()
+            .ok_or(PaymentError::MissingInput)?
+            .previous_output;
+
+        if outpoint != self.funding_outpoint {
+            return Err(PaymentError::FundingOutpointMismatch.into());
+        }
+
+        // Both branches share the same witness script, so the refund sighash
+        // is computed exactly like the payment one.
+        self.params.backend.verify_payment_signature(
+            psbt,
+            &self.params.payer,
+            self.funding_amount(),
+        )
+    }
+
+    /// Checks that a refund transaction can be broadcast at `current_height`.
+    ///
+    /// The refund branch of the funding script enforces the refund lock time
+    /// with `OP_CSV`, so a refund broadcast too early, or whose input doesn't
+    /// encode the lock time, is rejected by the network. This checks that the
+    /// input spending the funding outpoint has the refund lock time as its
+    /// sequence, that the transaction version enables it, and that the lock
+    /// time has matured for a transaction included in the block after
+    /// `current_height`. Signatures are not checked; see
+    /// [`Channel::verify_refund_signature`].
+    ///
+    /// Both heights must come from a trusted chain source.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SpillError::Refund` variant if:
+    /// - `MissingFundingInput`: `tx` doesn't spend the funding outpoint.
+    /// - `InvalidSequence`: The input sequence is not the refund lock time.
+    /// - `InvalidVersion`: The transaction version is not 2.
+    /// - `TimeBasedLockTime`: The refund lock time is time-based, so its
+    ///   maturity cannot be derived from block heights.
+    /// - `LockTimeNotMatured`: The refund lock time has not matured yet.
+    ///
+    /// Returns `SpillError::Funding(FundingError::InvalidConfirmationHeight)`
+    /// if `funding_confirmation_height` is above `current_height`.
+    pub fn verify_refund_broadcastable(
+        &self,
+        tx: &Transaction,
+        funding_confirmation_height: u32,
+        current_height: u32,
+    ) -> Result<(), SpillError> {
+        let input = tx
+            .inputs
+            .iter()
+            .find(|input| input.previous_output == self.funding_outpoint)
+            .ok_or(RefundError::MissingFundingInput)?;
+
+        let expected = self.params.refund_lock_time.to_sequence();
+        if input.sequence != expected {
+            return Err(RefundError::InvalidSequence {
+                found: input.sequence,
+                expected,
+            }
+            .into());
+        }
+
+        if tx.version != transaction::Version::TWO {
+            return Err(RefundError::InvalidVersion.into());
+        }
+
+        let relative::LockTime::Blocks(blocks) = self.params.refund_lock_time else {
+            return Err(RefundError::TimeBasedLockTime.into());
+        };
+
+        if funding_confirmation_height > current_height {
+            return Err(FundingError::InvalidConfirmationHeight.into());
+        }
+
+        // BIP68: the refund can be included once the block height is at
+        // least the funding height plus the lock time.
+        let matures_at = funding_confirmation_height.saturating_add(u32::from(blocks.to_height()));
+        let next_height = current_height.saturating_add(1);
+        if next_height < matures_at {
+            return Err(RefundError::LockTimeNotMatured {
+                remaining_blocks: matures_at - next_height,
+            }
+            .into());
+        }
+
+        Ok(())
+    }
+
+    /// Verifies a payment PSBT, running the signature checks even if a
+    /// structural check fails.
+    ///
+    /// [`Channel::verify_payment_psbt`] returns as soon as a check fails, so
+    /// its running time reveals roughly which check rejected the PSBT. This
+    /// variant always performs the secp256k1 signature verification, which
+    /// dominates the cost of verification, and only then reports the first
+    /// error found. It is intended for servers verifying payments from
+    /// untrusted peers, at the cost of spending the full verification time
+    /// on PSBTs that are malformed. The individual checks are not constant
+    /// time; only the early exit is removed.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`Channel::verify_payment_psbt`]. If both a
+    /// structural and a signature check fail, the structural error is returned.
+    pub fn verify_payment_psbt_ct(&self, psbt: &Psbt) -> Result<PaymentInfo, SpillError> {
+        let structural = self.verify_payment(psbt, self.sent, self.payer_change, false, false);
+        let signatures = if psbt.inputs.is_empty() {
+            Ok(())
+        } else {
+            self.verify_payment_signatures(psbt)
+        };
+
+        let info = structural?;
+        signatures?;
+        Ok(info)
+    }
+
+    fn verify_payment_signatures(&self, psbt: &Psbt) -> Result<(), SpillError> {
+        self.params.backend.verify_payment_signature(
+            psbt,
+            &self.params.payer,
+            self.funding_amount(),
+        )?;
+
+        if psbt.inputs[0].partial_sigs.contains_key(&self.params.payee) {
+            self.params.backend.verify_payment_signature(
+                psbt,
+                &self.params.payee,
+                self.funding_amount(),
+            )?;
+        }
+
+        if let Some(recorded) = psbt.inputs[0].sighash_type
+            && psbt.inputs[0]
+                .partial_sigs
+                .values()
+                .any(|sig| sig.sighash_type.to_u32() != recorded.to_u32())
+        {
+            return Err(PaymentError::InvalidSighash.into());
+        }
+
+        if self.params.policy.strict_sighash_all
+            && psbt.inputs[0]
+                .partial_sigs
+                .values()
+                .any(|sig| sig.sighash_type != EcdsaSighashType::All)
+        {
+            return Err(PaymentError::InvalidSighash.into());
+        }
+
+        Ok(())
+    }
+
+    /// Verifies a payment against the previous `previous_sent` and `previous_change`.
+    ///
+    /// If `fee_bump` is set, the payment must instead pay exactly
+    /// `previous_sent` to the payee at a strictly higher fee than the
+    /// previous payment.
+    fn verify_payment(
+        &self,
+        psbt: &Psbt,
+        previous_sent: Amount,
+        previous_change: Amount,
+        fee_bump: bool,
+        verify_signature: bool,
+    ) -> Result<PaymentInfo, SpillError> {
         if psbt.inputs.len() > 1 {
             return Err(PaymentError::MultipleInputs.into());
         }
@@ -100,6 +802,13 @@ impl<B: ChannelBackend + Clone> Channel<B> {
             .ok_or(PaymentError::MissingWitnessUtxo)?;
 
         if witness_utxo != &self.funding_utxo {
+            // A counterparty with an altered refund lock time spends a different
+            // funding script; report that instead of an opaque mismatch.
+            if let Err(err @ SpillError::Payment(PaymentError::LocktimeMismatch { .. })) =
+                self.params.backend.verify_payment_structure(psbt)
+            {
+                return Err(err);
+            }
             return Err(PaymentError::WitnessUtxoMismatch.into());
         }
 
@@ -108,31 +817,97 @@ impl<B: ChannelBackend + Clone> Channel<B> {
         }
 
         let sequence = psbt.unsigned_tx.inputs[0].sequence;
+        let timelocked = self.params.policy.allow_timelocked_payments
+            && sequence == Sequence::ENABLE_LOCKTIME_NO_RBF;
 
-        if sequence != Sequence::MAX {
+        if sequence != Sequence::MAX && !timelocked {
             return Err(PaymentError::InvalidSequence.into());
         }
 
         let lock_time = psbt.unsigned_tx.lock_time;
 
-        if lock_time != LockTime::ZERO {
+        if lock_time != LockTime::ZERO && !timelocked {
             return Err(PaymentError::NonZeroLockTime.into());
         }
 
-        let payee_script = self.params.backend.payee_script(&self.params.payee)?;
+        if lock_time != LockTime::ZERO
+            && let Some(max) = self.params.policy.max_payment_lock_time
+            && !lock_time.is_implied_by(max)
+        {
+            return Err(PaymentError::LockTimeTooLate { lock_time, max }.into());
+        }
 
-        let new_payment_amount = psbt
-            .unsigned_tx
-            .outputs
-            .iter()
-            .find(|o| o.script_pubkey == payee_script)
-            .ok_or(PaymentError::MissingPayeeOutput)?
-            .amount;
+        if psbt.unsigned_tx.version != transaction::Version::TWO {
+            return Err(PaymentError::InvalidVersion.into());
+        }
+
+        let payee_script = self.params.payee_script();
+
+        let outputs = &psbt.unsigned_tx.outputs;
+        let policy = &self.params.policy;
+
+        if let Some(max) = policy.max_outputs
+            && outputs.len() > max
+        {
+            return Err(PaymentError::TooManyOutputs {
+                count: outputs.len(),
+                max,
+            }
+            .into());
+        }
+
+        let payee_output = match policy.expected_payee_index {
+            Some(index) => match outputs.get(index) {
+                Some(output) if output.script_pubkey == payee_script => Some(output),
+                _ if outputs.iter().any(|o| o.script_pubkey == payee_script) => {
+                    return Err(PaymentError::PayeeOutputWrongIndex { expected: index }.into());
+                }
+                _ => None,
+            },
+            None => outputs.iter().find(|o| o.script_pubkey == payee_script),
+        };
+
+        let new_payment_amount = payee_output.ok_or(PaymentError::MissingPayeeOutput)?.amount;
+
+        let dust_limit = policy
+            .dust_limit
+            .unwrap_or_else(|| payee_script.minimal_non_dust());
 
-        if new_payment_amount <= self.sent {
-            return Err(PaymentError::PaymentNotIncremental.into());
+        if new_payment_amount < dust_limit {
+            return Err(PaymentError::PayeeOutputDust {
+                amount: new_payment_amount,
+                dust_limit,
+            }
+            .into());
         }
 
+        let increment = if fee_bump {
+            if new_payment_amount != previous_sent {
+                return Err(PaymentError::ReplacementAmountMismatch {
+                    expected: previous_sent,
+                    found: new_payment_amount,
+                }
+                .into());
+            }
+            Amount::ZERO
+        } else {
+            if new_payment_amount <= previous_sent {
+                return Err(PaymentError::PaymentNotIncremental.into());
+            }
+
+            let increment = (new_payment_amount - previous_sent).into_result().expect(
+                "verify_payment_psbt: internal invariant violated (Amount calculation must be valid)",
+            );
+            if increment < policy.min_payment_increment {
+                return Err(PaymentError::IncrementTooSmall {
+                    increment,
+                    minimum: policy.min_payment_increment,
+                }
+                .into());
+            }
+            increment
+        };
+
         let total_output: Amount = psbt
             .unsigned_tx
             .outputs
@@ -146,18 +921,45 @@ impl<B: ChannelBackend + Clone> Channel<B> {
             return Err(PaymentError::OutputsExceedFundingAmount.into());
         }
 
-        self.params
-            .backend
-            .verify_payment(psbt, &self.params.payer, self.params.capacity)?;
+        // Only outputs to the payer's change script count as change, so
+        // value moved to third-party outputs cannot pass as change.
+        let payer_script =
+            ScriptBuf::new_witness_program(&WitnessProgram::p2wpkh(self.params.payer.try_into()?));
+        let payer_change = outputs
+            .iter()
+            .filter(|o| o.script_pubkey == payer_script)
+            .map(|o| o.amount)
+            .fold(NumOpResult::Valid(Amount::ZERO), |acc, item| acc + item)
+            .into_result()
+            .map_err(|_| PaymentError::AmountOverflow)?;
+
+        let fee = (self.params.capacity - total_output).into_result().expect(
+            "verify_payment_psbt: internal invariant violated (Amount calculation must be valid)",
+        );
+
+        if fee_bump {
+            // The lower change must go to the fee, not to additional outputs.
+            let previous_fee = (self.params.capacity - previous_sent - previous_change)
+                .into_result()
+                .map_err(|_| PaymentError::AmountOverflow)?;
+            if payer_change >= previous_change || fee <= previous_fee {
+                return Err(PaymentError::FeeNotIncreased { fee, previous_fee }.into());
+            }
+        } else if payer_change >= previous_change {
+            return Err(PaymentError::InconsistentChange.into());
+        }
+
+        self.params.backend.verify_payment_structure(psbt)?;
+
+        if verify_signature {
+            self.verify_payment_signatures(psbt)?;
+        }
 
         Ok(PaymentInfo {
             total: new_payment_amount,
-            current: (new_payment_amount - self.sent)
-                .into_result()
-                .expect("verify_payment_psbt: internal invariant violated (Amount calculation must be valid)"),
-            fee: (self.params.capacity - total_output)
-                .into_result()
-                .expect("verify_payment_psbt: internal invariant violated (Amount calculation must be valid)"),
+            current: increment,
+            fee,
+            payer_change,
         })
     }
 }
@@ -0,0 +1,170 @@
+use bitcoin::{
+    Amount, FeeRate, Transaction, Weight,
+    transaction::{InputWeightPrediction, TransactionExt, predict_weight},
+};
+
+use crate::{Channel, ChannelParams, PaymentError, SpillError, channel::backend::ChannelBackend};
+
+/// Size of a P2WPKH `script_pubkey`.
+const P2WPKH_SCRIPT_SIZE: usize = 22;
+
+/// Estimated on-chain cost of opening and closing a channel.
+///
+/// Returned by [`ChannelParams::estimate_lifecycle_cost`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LifecycleCost {
+    /// Fee of the funding transaction.
+    pub funding_fee: Amount,
+    /// Worst-case fee of the payment transaction closing the channel.
+    pub close_fee: Amount,
+}
+
+impl LifecycleCost {
+    /// Returns the combined fee of opening and closing the channel.
+    pub fn total(&self) -> Amount {
+        (self.funding_fee + self.close_fee)
+            .into_result()
+            .unwrap_or(Amount::MAX)
+    }
+}
+
+impl<B: ChannelBackend + Clone> ChannelParams<B> {
+    /// Returns the serialized size, in bytes, of a finalized payment witness.
+    ///
+    /// The size covers the full witness stack (signatures, branch selector
+    /// and witness script), assuming maximum-size DER signatures, so it is
+    /// an upper bound suitable for fee and package-fee calculations.
+    ///
+    /// The estimate stays worst-case even if the local signer grinds for
+    /// low-R signatures, see [`PrivateKeySigner::with_low_r`], since the
+    /// other signatures come from signers this crate does not control. Each
+    /// low-R signature is at least 2 bytes shorter than assumed.
+    ///
+    /// [`PrivateKeySigner::with_low_r`]: crate::PrivateKeySigner::with_low_r
+    pub fn payment_witness_size(&self) -> usize {
+        self.backend.payment_witness_size(self.threshold)
+    }
+
+    /// Returns the serialized size, in bytes, of a finalized refund witness.
+    ///
+    /// Like [`ChannelParams::payment_witness_size`], this assumes a
+    /// maximum-size DER signature, even for low-R signers, and is therefore
+    /// an upper bound.
+    pub fn refund_witness_size(&self) -> usize {
+        self.backend.refund_witness_size()
+    }
+
+    /// Estimates the on-chain fees of opening and closing the channel at `feerate`.
+    ///
+    /// The estimate assumes:
+    /// - The funding transaction spends `funding_inputs` P2WPKH inputs and
+    ///   has a single P2WPKH change output next to the funding output.
+    /// - The channel is closed with a payment transaction paying the payee
+    ///   script and a P2WPKH payer output, with maximum-size signatures.
+    ///
+    /// Fees saturate at [`Amount::MAX`] for absurdly high fee rates.
+    pub fn estimate_lifecycle_cost(
+        &self,
+        feerate: FeeRate,
+        funding_inputs: usize,
+    ) -> LifecycleCost {
+        let funding_weight = predict_weight(
+            vec![InputWeightPrediction::P2WPKH_MAX; funding_inputs],
+            [self.script_pubkey.len(), P2WPKH_SCRIPT_SIZE],
+        );
+
+        LifecycleCost {
+            funding_fee: feerate.to_fee(funding_weight),
+            close_fee: feerate.to_fee(self.close_weight()),
+        }
+    }
+
+    /// Returns the worst-case weight of a finalized payment transaction
+    /// paying the payee script and a P2WPKH payer output.
+    fn close_weight(&self) -> Weight {
+        // The segwit marker and flag add 2 weight units once the input has a witness.
+        predict_weight(
+            [InputWeightPrediction::new(0, [0usize; 0])],
+            [self.payee_script().len(), P2WPKH_SCRIPT_SIZE],
+        ) + Weight::from_wu(2 + self.payment_witness_size() as u64)
+    }
+}
+
+impl<B: ChannelBackend + Clone> Channel<B> {
+    /// Returns the fee the payment expected to close the channel should carry at `feerate`.
+    ///
+    /// In a unidirectional channel only the last payment is ever broadcast:
+    /// each payment replaces the previous one, so the fees of intermediate
+    /// payments are virtual and never paid. The payer can therefore use a
+    /// minimal fee for intermediate payments and only give the payment
+    /// expected to close the channel this fee, computed from the worst-case
+    /// weight of the finalized payment transaction.
+    ///
+    /// The fee of a payment is deducted from the payer's change, so a later
+    /// payment can raise it as long as the capacity allows.
+    pub fn optimal_close_fee(&self, feerate: FeeRate) -> Amount {
+        feerate.to_fee(self.params.close_weight())
+    }
+
+    /// Checks that a payment or refund transaction pays at least the minimum relay fee.
+    ///
+    /// The fee is computed from the channel capacity minus the outputs of
+    /// `tx`, and compared against `min_relay_feerate` applied to the weight
+    /// of the finalized transaction. If `tx` is not finalized yet, its
+    /// witness is assumed to have the maximum size of the branch selected by
+    /// the input sequence, which only enables a relative lock time on the
+    /// refund branch (see [`ChannelParams::payment_witness_size`]), so
+    /// the check can be run before signing.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SpillError::Payment` variant if:
+    /// - `MissingInput`: `tx` has no inputs.
+    /// - `MultipleInputs`: `tx` has more than one input, whose value is unknown.
+    /// - `FundingOutpointMismatch`: `tx` doesn't spend the funding outpoint.
+    /// - `OutputsExceedFundingAmount`: The outputs exceed the channel capacity.
+    /// - `FeeTooLow`: The fee is below the minimum relay fee.
+    pub fn check_relay_feasible(
+        &self,
+        tx: &Transaction,
+        min_relay_feerate: FeeRate,
+    ) -> Result<(), SpillError> {
+        let input = match tx.inputs.as_slice() {
+            [] => return Err(PaymentError::MissingInput.into()),
+            [input] => input,
+            _ => return Err(PaymentError::MultipleInputs.into()),
+        };
+        if input.previous_output != self.funding_outpoint {
+            return Err(PaymentError::FundingOutpointMismatch.into());
+        }
+
+        let outputs = tx
+            .outputs
+            .iter()
+            .try_fold(Amount::ZERO, |acc, output| {
+                (acc + output.amount).into_result()
+            })
+            .map_err(|_| PaymentError::OutputsExceedFundingAmount)?;
+        let fee = (self.params.capacity - outputs)
+            .into_result()
+            .map_err(|_| PaymentError::OutputsExceedFundingAmount)?;
+
+        let mut weight = tx.weight();
+        if input.witness.is_empty() {
+            let witness_size = if !input.sequence.is_relative_lock_time() {
+                self.params.payment_witness_size()
+            } else {
+                self.params.refund_witness_size()
+            };
+            // The segwit marker and flag add 2 weight units once the input has a witness.
+            weight += Weight::from_wu(2 + witness_size as u64);
+        }
+
+        let min_fee = min_relay_feerate.to_fee(weight);
+        if fee < min_fee {
+            return Err(PaymentError::FeeTooLow { fee, min_fee }.into());
+        }
+
+        Ok(())
+    }
+}
@@ -1,4 +1,7 @@
-use bitcoin::{Amount, PublicKey, key::UncompressedPublicKeyError};
+use bitcoin::{
+    Amount, PublicKey, Sequence, Weight, absolute, key::UncompressedPublicKeyError,
+    primitives::relative,
+};
 use core::fmt;
 use std::error::Error;
 
@@ -11,10 +14,28 @@ use std::error::Error;
 pub enum ConfigError {
     /// The channel capacity is invalid (zero).
     InvalidCapacity,
+    /// The channel capacity exceeds the maximum bitcoin supply.
+    CapacityOutOfRange,
     /// A provided public key is not in compressed form.
     UncompressedPublicKey,
     /// The refund lock time is invalid (zero).
     InvalidRefundLockTime,
+    /// The requested refund delay exceeds the maximum BIP68 relative lock time.
+    RefundLockTimeOverflow,
+    /// The cooperative multisig threshold or key count is invalid.
+    InvalidThreshold,
+    /// A public key appears more than once in the cooperative branch.
+    DuplicatePublicKey,
+    /// The given public key is not part of the channel.
+    UnknownPublicKey,
+    /// The channel capacity is below the locally accepted minimum.
+    CapacityBelowMinimum { capacity: Amount, minimum: Amount },
+    /// The refund lock time exceeds the locally accepted maximum.
+    RefundLockTimeTooLong,
+    /// The funding script does not match the channel's script template.
+    UnrecognizedFundingScript,
+    /// The payee script is not a P2WPKH, P2WSH or P2TR script.
+    UnsupportedPayeeScript,
 }
 
 /// Errors that can occur when constructing or verifying the funding transaction.
@@ -28,10 +49,41 @@ pub enum FundingError {
     TxidMismatch,
     /// The expected output was not found in the funding transaction.
     OutputNotFound,
-    /// The value of the funding output does not match the channel capacity.
-    ValueMismatch,
+    /// The value of the funding output is below the channel capacity.
+    ValueTooLow { found: Amount, expected: Amount },
+    /// The value of the funding output is above the channel capacity.
+    ValueTooHigh { found: Amount, expected: Amount },
+    /// The value of the splice output does not increase the channel capacity.
+    NoCapacityIncrease { found: Amount, capacity: Amount },
     /// The script of the funding output does not match the expected funding script.
     ScriptMismatch,
+    /// The funding block height is above the chain tip, or the heights are
+    /// too far apart to count the confirmations.
+    InvalidConfirmationHeight,
+    /// The funding transaction does not have enough confirmations.
+    InsufficientConfirmations { confirmations: u32, required: u32 },
+    /// A funding PSBT input is missing its witness or non-witness UTXO.
+    MissingInputUtxo { index: usize },
+    /// The funding inputs do not cover the funding output and other outputs.
+    InsufficientFunds { available: Amount, required: Amount },
+    /// Amount overflowed
+    AmountOverflow,
+    /// The splice transaction does not spend the current funding output.
+    SpliceMissingFundingInput,
+    /// The requested funding output index cannot exist in a valid transaction.
+    OutputIndexOutOfRange,
+    /// Two channel states do not share the same parameters and funding outpoint.
+    ChannelMismatch,
+    /// The funding input at `index` spends an output of an unsupported script type.
+    UnsupportedInputScript { index: usize },
+    /// The funding PSBT has no input at `index`.
+    InputIndexOutOfRange { index: usize },
+    /// The funding transaction fee exceeds the accepted maximum.
+    FeeTooHigh { fee: Amount, max_fee: Amount },
+    /// The number of previous outputs does not match the number of funding inputs.
+    PrevoutCountMismatch { inputs: usize, prevouts: usize },
+    /// More than one output of the funding transaction matches the expected funding output.
+    AmbiguousFundingOutput { count: usize },
 }
 
 /// Errors that can occur when constructing or verifying a payment.
@@ -57,18 +109,41 @@ pub enum PaymentError {
     MissingWitnessScript,
     /// The witness script does not match the expected funding script.
     WitnessScriptMismatch,
+    /// The witness script matches the funding script except for its refund lock time.
+    ///
+    /// The refund lock time is committed in the funding script and cannot be
+    /// changed after the channel is funded.
+    LocktimeMismatch {
+        expected: relative::LockTime,
+        found: relative::LockTime,
+    },
     /// The script_pubkey does not match the expected funding script_pubkey.
     ScriptPubKeyMismatch,
     /// The input sequence number is invalid (expected MAX).
     InvalidSequence,
     /// The lock time is non-zero, unexpected for payment transactions.
     NonZeroLockTime,
+    /// The payment lock time is later than the channel policy allows.
+    LockTimeTooLate {
+        lock_time: absolute::LockTime,
+        max: absolute::LockTime,
+    },
+    /// The transaction version is not 2.
+    InvalidVersion,
     /// The payee output is missing from the PSBT outputs.
     MissingPayeeOutput,
+    /// The payee output is not at the index required by the channel policy.
+    PayeeOutputWrongIndex { expected: usize },
+    /// The payment has more outputs than allowed by the channel policy.
+    TooManyOutputs { count: usize, max: usize },
+    /// The payee output is below the dust limit.
+    PayeeOutputDust { amount: Amount, dust_limit: Amount },
     /// The total output decreases (negative payment).
     PaymentNotIncremental,
     /// The sum of outputs exceeds the funding transaction value.
     OutputsExceedFundingAmount,
+    /// The payer's change does not strictly decrease relative to the previous payment.
+    InconsistentChange,
     /// The payment PSBT is missing the payer's signature.
     MissingSignature,
     /// The PSBT uses an unsupported sighash type (expected ALL or ALL|ANYONECANPAY).
@@ -77,6 +152,53 @@ pub enum PaymentError {
     InvalidSignature,
     /// Amount overflowed
     AmountOverflow,
+    /// The payment PSBT could not be decoded.
+    InvalidEncoding,
+    /// The transaction fee is below the minimum relay fee.
+    FeeTooLow { fee: Amount, min_fee: Amount },
+    /// The payment at `index` of a payment chain does not increase the
+    /// cumulative amount of the previous payment.
+    ChainOutOfOrder { index: usize },
+    /// The payment at `index` of a payment chain failed verification.
+    ChainInvalidPayment {
+        index: usize,
+        error: Box<SpillError>,
+    },
+    /// The signer's public key is not the key expected to sign.
+    SignerKeyMismatch { public_key: PublicKey },
+    /// The payment increases the cumulative amount by less than the policy minimum.
+    IncrementTooSmall { increment: Amount, minimum: Amount },
+    /// The fee replacement changes the payee amount of the payment it replaces.
+    ReplacementAmountMismatch { expected: Amount, found: Amount },
+    /// The fee replacement does not pay a strictly higher fee than the payment it replaces.
+    FeeNotIncreased { fee: Amount, previous_fee: Amount },
+}
+
+/// Errors that can occur when constructing or verifying the refund transaction.
+///
+/// These errors indicate that a refund transaction cannot be built or is
+/// invalid under the channel parameters.
+#[non_exhaustive]
+#[derive(Debug)]
+pub enum RefundError {
+    /// The refund outputs plus fee exceed the channel capacity.
+    ExceedsCapacity { available: Amount, required: Amount },
+    /// A refund output is below the dust limit.
+    DustOutput { amount: Amount, dust_limit: Amount },
+    /// The refund has no outputs.
+    NoOutputs,
+    /// The refund outputs plus fee do not spend the whole channel capacity.
+    UnallocatedFunds { available: Amount, required: Amount },
+    /// The refund transaction does not spend the funding outpoint.
+    MissingFundingInput,
+    /// The refund input sequence does not encode the refund lock time.
+    InvalidSequence { found: Sequence, expected: Sequence },
+    /// The refund transaction version is not 2, so its sequence is not enforced as a lock time.
+    InvalidVersion,
+    /// The refund lock time is time-based and cannot be checked against block heights.
+    TimeBasedLockTime,
+    /// The refund lock time has not matured yet.
+    LockTimeNotMatured { remaining_blocks: u32 },
 }
 
 /// Errors that can occur when finalizing channel transactions.
@@ -90,6 +212,82 @@ pub enum FinalizeError {
     MissingSignature { public_key: PublicKey },
     /// The witness script required to finalize the transaction is missing.
     MissingWitnessScript,
+    /// A signature uses a sighash type outside the ALL family accepted by verification.
+    InconsistentSighash { public_key: PublicKey },
+    /// The sighash type recorded in the PSBT input is not a defined ECDSA
+    /// sighash type, so it cannot be appended to a signature.
+    UndefinedSighashType { sighash_type: u32 },
+    /// A signature has a high S value, which makes the witness non-standard.
+    NonCanonicalSignature { public_key: PublicKey },
+}
+
+/// Errors that can occur when closing the channel or inspecting an on-chain
+/// transaction closing the channel.
+///
+/// These errors indicate that a close cannot be built for this channel, or
+/// that a transaction cannot be interpreted as a close of this channel.
+#[non_exhaustive]
+#[derive(Debug)]
+pub enum CloseError {
+    /// The transaction does not spend the channel's funding output.
+    NotChannelSpend,
+    /// The channel has no arbiter in its cooperative branch.
+    NoArbiter,
+    /// The `OP_RETURN` data exceeds the standard size limit.
+    OpReturnTooLarge { size: usize, max: usize },
+    /// The transaction spends the channel's funding output through the refund branch.
+    NotPaymentClose,
+    /// No payment has been applied, so there is nothing to pay the payee.
+    NothingSent,
+    /// The PSBT is not the anchor close expected from the channel state.
+    AnchorCloseMismatch,
+    /// The threshold of the cooperative branch cannot be met by the arbiter
+    /// and one party.
+    ArbiterThresholdTooHigh { threshold: usize },
+    /// The payer output of an arbiter close is below its dust limit.
+    PayerOutputDust { amount: Amount, dust_limit: Amount },
+}
+
+/// Errors returned by the pre-broadcast standardness check.
+///
+/// These errors indicate that a transaction would likely be rejected by the
+/// mempool policy of most nodes, even if it is valid by consensus rules.
+#[non_exhaustive]
+#[derive(Debug)]
+pub enum StandardnessError {
+    /// The transaction version is not standard.
+    NonStandardVersion,
+    /// The transaction weight exceeds the standard limit.
+    TooLarge { weight: Weight, max_weight: Weight },
+    /// The output at `index` has a non-standard script.
+    NonStandardOutput { index: usize },
+    /// The output at `index` is below its dust limit.
+    DustOutput {
+        index: usize,
+        amount: Amount,
+        dust_limit: Amount,
+    },
+    /// The transaction outputs exceed the value of its inputs.
+    OutputsExceedInput,
+    /// The transaction fee is below the minimum relay fee.
+    FeeTooLow { fee: Amount, min_fee: Amount },
+}
+
+/// Errors that can occur when restoring a channel from a backup.
+#[non_exhaustive]
+#[derive(Debug)]
+pub enum BackupError {
+    /// The backup uses an unknown format version.
+    UnsupportedVersion { version: u8 },
+    /// The backup could not be decoded.
+    Malformed,
+    /// The backup string is not a valid bech32m backup or has an invalid checksum.
+    InvalidEncoding,
+    /// The backup is too large to be encoded as a bech32m string.
+    TooLarge { size: usize },
+    /// The encrypted backup failed authentication: the key is wrong or the
+    /// backup was modified.
+    DecryptionFailed,
 }
 
 /// Top-level error type for this crate.
@@ -106,8 +304,16 @@ pub enum SpillError {
     Funding(FundingError),
     /// Errors related to payment construction or verification.
     Payment(PaymentError),
+    /// Errors related to refund construction or verification.
+    Refund(RefundError),
     /// Errors that can occur when finalizing transactions.
     Finalize(FinalizeError),
+    /// Errors related to closing the channel or inspecting on-chain closes.
+    Close(CloseError),
+    /// Errors related to restoring a channel from a backup.
+    Backup(BackupError),
+    /// Errors related to the pre-broadcast standardness check.
+    Standardness(StandardnessError),
 }
 
 impl From<UncompressedPublicKeyError> for SpillError {
@@ -134,30 +340,98 @@ impl From<PaymentError> for SpillError {
     }
 }
 
+impl From<RefundError> for SpillError {
+    fn from(value: RefundError) -> Self {
+        Self::Refund(value)
+    }
+}
+
 impl From<FinalizeError> for SpillError {
     fn from(value: FinalizeError) -> Self {
         Self::Finalize(value)
     }
 }
 
+impl From<CloseError> for SpillError {
+    fn from(value: CloseError) -> Self {
+        Self::Close(value)
+    }
+}
+
+impl From<BackupError> for SpillError {
+    fn from(value: BackupError) -> Self {
+        Self::Backup(value)
+    }
+}
+
+impl From<StandardnessError> for SpillError {
+    fn from(value: StandardnessError) -> Self {
+        Self::Standardness(value)
+    }
+}
+
 impl fmt::Display for SpillError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             SpillError::Config(config_error) => match config_error {
                 ConfigError::InvalidCapacity => write!(f, "channel capacity must be non-zero."),
+                ConfigError::CapacityOutOfRange => {
+                    write!(f, "channel capacity exceeds the maximum bitcoin supply")
+                }
                 ConfigError::UncompressedPublicKey => write!(f, "public key must be compressed"),
                 ConfigError::InvalidRefundLockTime => {
                     write!(f, "invalid refund lock time (must be greater than 0)")
                 }
+                ConfigError::RefundLockTimeOverflow => {
+                    write!(f, "refund lock time exceeds the maximum relative lock time")
+                }
+                ConfigError::InvalidThreshold => write!(
+                    f,
+                    "invalid multisig threshold (must be between 2 and the number of keys, at most 16 keys)"
+                ),
+                ConfigError::DuplicatePublicKey => {
+                    write!(f, "public keys in the cooperative branch must be distinct")
+                }
+                ConfigError::UnknownPublicKey => {
+                    write!(f, "public key is not part of the channel")
+                }
+                ConfigError::CapacityBelowMinimum { capacity, minimum } => write!(
+                    f,
+                    "channel capacity is below minimum (capacity: {}, minimum: {})",
+                    capacity, minimum
+                ),
+                ConfigError::RefundLockTimeTooLong => {
+                    write!(f, "refund lock time exceeds accepted maximum")
+                }
+                ConfigError::UnrecognizedFundingScript => {
+                    write!(
+                        f,
+                        "funding script does not match the channel script template"
+                    )
+                }
+                ConfigError::UnsupportedPayeeScript => {
+                    write!(f, "payee script must be P2WPKH, P2WSH or P2TR")
+                }
             },
             SpillError::Funding(funding_error) => match funding_error {
                 FundingError::TxidMismatch => {
                     write!(f, "funding transaction does not match expected id")
                 }
                 FundingError::OutputNotFound => write!(f, "funding transaction output not found"),
-                FundingError::ValueMismatch => write!(
+                FundingError::ValueTooLow { found, expected } => write!(
+                    f,
+                    "funding transaction output value is too low (found: {}, expected: {})",
+                    found, expected
+                ),
+                FundingError::ValueTooHigh { found, expected } => write!(
+                    f,
+                    "funding transaction output value is too high (found: {}, expected: {})",
+                    found, expected
+                ),
+                FundingError::NoCapacityIncrease { found, capacity } => write!(
                     f,
-                    "funding transaction output value does not match expected"
+                    "splice output does not increase the channel capacity (found: {}, capacity: {})",
+                    found, capacity
                 ),
                 FundingError::ScriptMismatch => {
                     write!(
@@ -165,6 +439,59 @@ impl fmt::Display for SpillError {
                         "funding transaction output script does not match expected"
                     )
                 }
+                FundingError::InvalidConfirmationHeight => {
+                    write!(f, "funding block height is invalid for the chain tip")
+                }
+                FundingError::InsufficientConfirmations {
+                    confirmations,
+                    required,
+                } => write!(
+                    f,
+                    "funding transaction is not sufficiently confirmed (confirmations: {}, required: {})",
+                    confirmations, required
+                ),
+                FundingError::MissingInputUtxo { index } => {
+                    write!(f, "funding PSBT input {} is missing its UTXO", index)
+                }
+                FundingError::InsufficientFunds {
+                    available,
+                    required,
+                } => write!(
+                    f,
+                    "funding inputs are insufficient (available: {}, required: {})",
+                    available, required
+                ),
+                FundingError::AmountOverflow => write!(f, "funding amount overflow"),
+                FundingError::SpliceMissingFundingInput => {
+                    write!(f, "splice transaction does not spend the funding output")
+                }
+                FundingError::OutputIndexOutOfRange => {
+                    write!(f, "funding output index is out of range")
+                }
+                FundingError::ChannelMismatch => {
+                    write!(f, "channel states describe different channels")
+                }
+                FundingError::UnsupportedInputScript { index } => {
+                    write!(f, "funding input {} has an unsupported script type", index)
+                }
+                FundingError::InputIndexOutOfRange { index } => {
+                    write!(f, "funding PSBT has no input {}", index)
+                }
+                FundingError::FeeTooHigh { fee, max_fee } => write!(
+                    f,
+                    "funding fee exceeds the maximum (fee: {}, maximum: {})",
+                    fee, max_fee
+                ),
+                FundingError::PrevoutCountMismatch { inputs, prevouts } => write!(
+                    f,
+                    "funding transaction has {} inputs but {} previous outputs were given",
+                    inputs, prevouts
+                ),
+                FundingError::AmbiguousFundingOutput { count } => write!(
+                    f,
+                    "funding transaction has {} outputs matching the funding output",
+                    count
+                ),
             },
             SpillError::Payment(payment_error) => match payment_error {
                 PaymentError::ExceedsCapacity {
@@ -201,15 +528,43 @@ impl fmt::Display for SpillError {
                         "payment transaction witness script does not match expected"
                     )
                 }
+                PaymentError::LocktimeMismatch { expected, found } => write!(
+                    f,
+                    "payment witness script refund lock time does not match the channel (expected: {}, found: {})",
+                    expected, found
+                ),
                 PaymentError::InvalidSequence => {
                     write!(f, "payment transaction sequence is not MAX")
                 }
                 PaymentError::NonZeroLockTime => {
                     write!(f, "payment transaction uses non-final lock time")
                 }
+                PaymentError::LockTimeTooLate { lock_time, max } => write!(
+                    f,
+                    "payment lock time is later than allowed (lock time: {}, max: {})",
+                    lock_time, max
+                ),
+                PaymentError::InvalidVersion => {
+                    write!(f, "payment transaction version is not 2")
+                }
                 PaymentError::MissingPayeeOutput => {
                     write!(f, "payment transaction missing output to payee")
                 }
+                PaymentError::PayeeOutputWrongIndex { expected } => write!(
+                    f,
+                    "payment transaction output to payee is not at index {}",
+                    expected
+                ),
+                PaymentError::TooManyOutputs { count, max } => write!(
+                    f,
+                    "payment transaction has too many outputs (count: {}, max: {})",
+                    count, max
+                ),
+                PaymentError::PayeeOutputDust { amount, dust_limit } => write!(
+                    f,
+                    "payment output to payee is dust (amount: {}, dust limit: {})",
+                    amount, dust_limit
+                ),
                 PaymentError::PaymentNotIncremental => {
                     write!(
                         f,
@@ -219,6 +574,9 @@ impl fmt::Display for SpillError {
                 PaymentError::OutputsExceedFundingAmount => {
                     write!(f, "payment transaction outputs exceed funding amount")
                 }
+                PaymentError::InconsistentChange => {
+                    write!(f, "payer change must be lower than in the previous payment")
+                }
                 PaymentError::MissingSignature => {
                     write!(f, "payment transaction missing payer's signature")
                 }
@@ -229,16 +587,181 @@ impl fmt::Display for SpillError {
                     write!(f, "payment transaction signature is invalid")
                 }
                 PaymentError::AmountOverflow => write!(f, "Amount operation error"),
+                PaymentError::InvalidEncoding => write!(f, "payment PSBT could not be decoded"),
+                PaymentError::FeeTooLow { fee, min_fee } => write!(
+                    f,
+                    "fee is below the minimum relay fee (fee: {}, minimum: {})",
+                    fee, min_fee
+                ),
+                PaymentError::ChainOutOfOrder { index } => {
+                    write!(f, "payment {} of the chain is out of order", index)
+                }
+                PaymentError::ChainInvalidPayment { index, error } => {
+                    write!(f, "payment {} of the chain is invalid: {}", index, error)
+                }
                 PaymentError::ScriptPubKeyMismatch => write!(
                     f,
                     "payment transaction input script_pubkey does not match expected"
                 ),
+                PaymentError::SignerKeyMismatch { public_key } => write!(
+                    f,
+                    "signer key {} is not the key expected to sign",
+                    public_key
+                ),
+                PaymentError::IncrementTooSmall { increment, minimum } => write!(
+                    f,
+                    "payment increment is below the minimum (increment: {}, minimum: {})",
+                    increment, minimum
+                ),
+                PaymentError::ReplacementAmountMismatch { expected, found } => write!(
+                    f,
+                    "fee replacement changes the payee amount (expected: {}, found: {})",
+                    expected, found
+                ),
+                PaymentError::FeeNotIncreased { fee, previous_fee } => write!(
+                    f,
+                    "fee replacement does not increase the fee (fee: {}, previous fee: {})",
+                    fee, previous_fee
+                ),
+            },
+            SpillError::Refund(refund_error) => match refund_error {
+                RefundError::ExceedsCapacity {
+                    available,
+                    required,
+                } => write!(
+                    f,
+                    "refund exceeds channel capacity (available: {}, required: {})",
+                    available, required
+                ),
+                RefundError::DustOutput { amount, dust_limit } => write!(
+                    f,
+                    "refund output is dust (amount: {}, dust limit: {})",
+                    amount, dust_limit
+                ),
+                RefundError::NoOutputs => write!(f, "refund has no outputs"),
+                RefundError::UnallocatedFunds {
+                    available,
+                    required,
+                } => write!(
+                    f,
+                    "refund does not spend the whole channel capacity (available: {}, allocated: {})",
+                    available, required
+                ),
+                RefundError::MissingFundingInput => {
+                    write!(f, "refund does not spend the funding outpoint")
+                }
+                RefundError::InvalidSequence { found, expected } => write!(
+                    f,
+                    "refund input sequence does not match the refund lock time (found: {}, expected: {})",
+                    found, expected
+                ),
+                RefundError::InvalidVersion => write!(f, "refund transaction version must be 2"),
+                RefundError::TimeBasedLockTime => write!(
+                    f,
+                    "refund lock time is time-based and cannot be checked against block heights"
+                ),
+                RefundError::LockTimeNotMatured { remaining_blocks } => {
+                    write!(f, "refund lock time matures in {} blocks", remaining_blocks)
+                }
             },
             SpillError::Finalize(finalize_error) => match finalize_error {
                 FinalizeError::MissingSignature { public_key } => {
                     write!(f, "PSBT is missing signature for public key {}", public_key)
                 }
                 FinalizeError::MissingWitnessScript => write!(f, "PSBT is missing witness script"),
+                FinalizeError::InconsistentSighash { public_key } => write!(
+                    f,
+                    "signature for public key {} uses an unsupported sighash type",
+                    public_key
+                ),
+                FinalizeError::UndefinedSighashType { sighash_type } => write!(
+                    f,
+                    "PSBT input records an undefined sighash type {:#x}",
+                    sighash_type
+                ),
+                FinalizeError::NonCanonicalSignature { public_key } => {
+                    write!(f, "signature for public key {} is not low-S", public_key)
+                }
+            },
+            SpillError::Close(close_error) => match close_error {
+                CloseError::NotChannelSpend => {
+                    write!(f, "transaction does not spend the channel funding output")
+                }
+                CloseError::NoArbiter => {
+                    write!(f, "channel has no arbiter in its cooperative branch")
+                }
+                CloseError::OpReturnTooLarge { size, max } => write!(
+                    f,
+                    "OP_RETURN data is too large (size: {}, maximum: {})",
+                    size, max
+                ),
+                CloseError::NotPaymentClose => {
+                    write!(
+                        f,
+                        "transaction closes the channel with a refund, not a payment"
+                    )
+                }
+                CloseError::NothingSent => {
+                    write!(f, "no payment has been applied to the channel")
+                }
+                CloseError::AnchorCloseMismatch => {
+                    write!(f, "anchor close does not match the channel state")
+                }
+                CloseError::ArbiterThresholdTooHigh { threshold } => write!(
+                    f,
+                    "threshold {} cannot be met by the arbiter and one party",
+                    threshold
+                ),
+                CloseError::PayerOutputDust { amount, dust_limit } => write!(
+                    f,
+                    "arbiter close output to payer is dust (amount: {}, dust limit: {})",
+                    amount, dust_limit
+                ),
+            },
+            SpillError::Backup(backup_error) => match backup_error {
+                BackupError::UnsupportedVersion { version } => {
+                    write!(f, "unsupported channel backup version {}", version)
+                }
+                BackupError::Malformed => write!(f, "malformed channel backup"),
+                BackupError::InvalidEncoding => write!(f, "invalid bech32m channel backup"),
+                BackupError::TooLarge { size } => write!(
+                    f,
+                    "channel backup is too large for bech32m encoding (size: {})",
+                    size
+                ),
+                BackupError::DecryptionFailed => {
+                    write!(f, "failed to decrypt channel backup")
+                }
+            },
+            SpillError::Standardness(standardness_error) => match standardness_error {
+                StandardnessError::NonStandardVersion => {
+                    write!(f, "transaction version is not standard")
+                }
+                StandardnessError::TooLarge { weight, max_weight } => write!(
+                    f,
+                    "transaction is too large (weight: {}, maximum: {})",
+                    weight, max_weight
+                ),
+                StandardnessError::NonStandardOutput { index } => {
+                    write!(f, "output {} has a non-standard script", index)
+                }
+                StandardnessError::DustOutput {
+                    index,
+                    amount,
+                    dust_limit,
+                } => write!(
+                    f,
+                    "output {} is dust (amount: {}, dust limit: {})",
+                    index, amount, dust_limit
+                ),
+                StandardnessError::OutputsExceedInput => {
+                    write!(f, "transaction outputs exceed the input value")
+                }
+                StandardnessError::FeeTooLow { fee, min_fee } => write!(
+                    f,
+                    "transaction fee is below the minimum relay fee (fee: {}, minimum: {})",
+                    fee, min_fee
+                ),
             },
         }
     }
@@ -39,8 +39,20 @@
 
 mod channel;
 mod error;
+mod signer;
+#[cfg(feature = "test-vectors")]
+pub mod test_vectors;
 
-pub use channel::PaymentInfo;
 pub use channel::backend::SegwitBackend;
-pub use channel::{Channel, ChannelParams};
-pub use error::{ConfigError, FinalizeError, FundingError, PaymentError, SpillError};
+pub use channel::{Channel, ChannelId, ChannelKind, ChannelParams, ChannelPolicy, Role, SpendKind};
+pub use channel::{CloseContext, CloseReason, CloseRecommendation};
+pub use channel::{FundingInfo, LifecycleCost, PaymentInfo, PaymentStream, ReceiveStream};
+pub use channel::{
+    PaymentPsbtSummary, check_standardness, describe_payment_psbt, funding_outpoint_of,
+};
+pub use channel::{SigningRequest, SigningResponse};
+pub use error::{
+    BackupError, CloseError, ConfigError, FinalizeError, FundingError, PaymentError, RefundError,
+    SpillError, StandardnessError,
+};
+pub use signer::{PrivateKeySigner, Signer};
@@ -0,0 +1,68 @@
+//! Signing abstraction used to produce channel signatures.
+
+use bitcoin::{
+    PrivateKey, PublicKey,
+    secp256k1::{Message, ecdsa},
+};
+
+/// A source of ECDSA signatures for a single public key.
+///
+/// Implementors may hold the private key in memory, delegate to a hardware
+/// device or forward requests to a remote signer. The crate only ever asks
+/// a `Signer` to sign sighash digests it has computed itself.
+pub trait Signer {
+    /// Returns the public key whose signatures this signer produces.
+    fn public_key(&self) -> PublicKey;
+
+    /// Signs a sighash digest.
+    fn sign_ecdsa(&self, msg: Message) -> ecdsa::Signature;
+}
+
+/// A [`Signer`] backed by an in-memory private key.
+#[derive(Debug, Clone)]
+pub struct PrivateKeySigner {
+    private_key: PrivateKey,
+    low_r: bool,
+}
+
+impl PrivateKeySigner {
+    /// Creates a signer for `private_key`.
+    ///
+    /// Low-R grinding is disabled by default.
+    pub fn new(private_key: PrivateKey) -> Self {
+        PrivateKeySigner {
+            private_key,
+            low_r: false,
+        }
+    }
+
+    /// Enables or disables low-R signature grinding.
+    ///
+    /// When enabled, signing retries with fresh nonce data until the
+    /// signature's R value fits in 32 bytes, so every DER-encoded signature
+    /// is at most 70 bytes long. This makes the actual witness sizes more
+    /// predictable, but the crate's fee estimates, e.g.
+    /// [`ChannelParams::payment_witness_size`], stay worst-case since they
+    /// cannot assume how the other parties sign. Grinding needs two signing
+    /// attempts on average, roughly doubling the CPU cost of each signature.
+    ///
+    /// [`ChannelParams::payment_witness_size`]: crate::ChannelParams::payment_witness_size
+    pub fn with_low_r(mut self, low_r: bool) -> Self {
+        self.low_r = low_r;
+        self
+    }
+}
+
+impl Signer for PrivateKeySigner {
+    fn public_key(&self) -> PublicKey {
+        self.private_key.public_key()
+    }
+
+    fn sign_ecdsa(&self, msg: Message) -> ecdsa::Signature {
+        if self.low_r {
+            ecdsa::sign_low_r(msg, self.private_key.as_inner())
+        } else {
+            ecdsa::sign(msg, self.private_key.as_inner())
+        }
+    }
+}
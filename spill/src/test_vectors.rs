@@ -0,0 +1,272 @@
+//! Deterministic channel test vectors.
+//!
+//! This module exposes a complete, reproducible channel lifecycle built from
+//! fixed private keys: channel parameters, the funding transaction, a payment
+//! and a refund, together with the hex encoding of each finalized
+//! transaction. Integrators can use these vectors as a golden reference when
+//! testing their own signing and broadcast code.
+//!
+//! ECDSA signatures are produced with RFC 6979 deterministic nonces, so every
+//! value in this module is stable across runs.
+//!
+//! **The keys in this module are public and must never hold real funds.**
+
+use bitcoin::{
+    Address, Amount, CompressedPublicKey, EcdsaSighashType, Network, OutPoint, PrivateKey, Psbt,
+    Sequence, Transaction, TxIn, TxOut, Txid, Witness,
+    ecdsa::Signature,
+    primitives::relative,
+    psbt::Output,
+    script::ScriptBuf,
+    secp256k1::{self, Message, SecretKey},
+    sighash::SighashCache,
+};
+
+use crate::{Channel, ChannelParams, SegwitBackend};
+
+/// Secret key of the payer.
+pub const PAYER_SECRET: [u8; 32] = [0x01; 32];
+/// Secret key of the payee.
+pub const PAYEE_SECRET: [u8; 32] = [0x02; 32];
+
+/// Channel capacity used by the vectors.
+pub const CAPACITY: Amount = Amount::from_sat_u32(40_000);
+/// Refund lock time used by the vectors.
+pub const REFUND_LOCK_TIME: relative::LockTime = relative::LockTime::from_height(10);
+/// Value of the payer's UTXO spent by the funding transaction.
+pub const PAYER_UTXO_AMOUNT: Amount = Amount::from_sat_u32(50_000);
+/// Amount sent in the payment vector.
+pub const PAYMENT_AMOUNT: Amount = Amount::from_sat_u32(10_000);
+/// Fee used by every transaction in the vectors.
+pub const FEE: Amount = Amount::from_sat_u32(1_000);
+
+/// Expected hex of the finalized funding transaction.
+pub const FUNDING_TX_HEX: &str = concat!(
+    "02000000000101111111111111111111111111111111111111111111111111111111111111111100",
+    "00000000ffffffff02409c0000000000002200203e6d8ca0709ba6fb7457443157d214a2cff2f822",
+    "25f08ddd2ed4bf3671b0ae2e282300000000000016001479b000887626b294a914501a4cd226b58b",
+    "23598302473044022029bc2ba131f087ed773e43c3c1862f9a8ee71b8011d9945fe976fdef01013d",
+    "1902202d54acfba54acca5de32375cc56ecd217bf0bc845852fcb6d8153ce2bf2b83b80121031b84",
+    "c5567b126440995d3ed5aaba0565d71e1834604819ff9c17f5e9d5dd078f00000000",
+);
+/// Expected hex of the finalized payment transaction.
+pub const PAYMENT_TX_HEX: &str = concat!(
+    "0200000000010131b7e4fd349e0d5aa6f74dd0ac8e14b18a5911e03e5e98eb2be463f80f63c76d00",
+    "00000000ffffffff021027000000000000160014ebc0ee0b2ab9e8277a600c251475e22a3241a1c1",
+    "487100000000000016001479b000887626b294a914501a4cd226b58b2359830500473044022023ef",
+    "9059b0d67a934910fda85c1e0ca798f3afec3cd7de41ebbb96eecd77849802204b970e30e4cd38c3",
+    "4dc14eb02482ade9a2fba40af8d66fa2d8dd59133d9e50dd01483045022100ed25c463017e66f227",
+    "96e9a90dbe161dc887c920322d3929a406825401bca6c3022021712b9998eeaa916ee99b18933707",
+    "79cfe889bc513c067e7f97396b1a80ecef01010170635221031b84c5567b126440995d3ed5aaba05",
+    "65d71e1834604819ff9c17f5e9d5dd078f21024d4b6cd1361032ca9bd2aeb9d900aa4d45d9ead80a",
+    "c9423374c451a7254d076652ae675ab27521031b84c5567b126440995d3ed5aaba0565d71e183460",
+    "4819ff9c17f5e9d5dd078fac6800000000",
+);
+/// Expected hex of the finalized refund transaction.
+pub const REFUND_TX_HEX: &str = concat!(
+    "0200000000010131b7e4fd349e0d5aa6f74dd0ac8e14b18a5911e03e5e98eb2be463f80f63c76d00",
+    "000000000a00000001589800000000000016001479b000887626b294a914501a4cd226b58b235983",
+    "03483045022100e3d1802433d73b69f9d9dd91c468a9912ba4a8bb44988b0cf1c5296aae0c6e7102",
+    "201235801fa01b0b760b77d3e07bae5eb8a1262cf6bd6efef12b146184d8b80f9f01007063522103",
+    "1b84c5567b126440995d3ed5aaba0565d71e1834604819ff9c17f5e9d5dd078f21024d4b6cd13610",
+    "32ca9bd2aeb9d900aa4d45d9ead80ac9423374c451a7254d076652ae675ab27521031b84c5567b12",
+    "6440995d3ed5aaba0565d71e1834604819ff9c17f5e9d5dd078fac6800000000",
+);
+
+/// Returns the payer's private key.
+pub fn payer_key() -> PrivateKey {
+    private_key(PAYER_SECRET)
+}
+
+/// Returns the payee's private key.
+pub fn payee_key() -> PrivateKey {
+    private_key(PAYEE_SECRET)
+}
+
+/// Returns the payer's P2WPKH address on regtest, used for funding change and refunds.
+pub fn payer_address() -> Address {
+    let pubkey = CompressedPublicKey::try_from(payer_key().public_key())
+        .expect("test_vectors: internal invariant violated (key must be compressed)");
+    Address::p2wpkh(pubkey, Network::Regtest)
+}
+
+/// Returns the channel parameters of the vectors.
+pub fn channel_params() -> ChannelParams<SegwitBackend> {
+    ChannelParams::new(
+        payer_key().public_key(),
+        payee_key().public_key(),
+        CAPACITY,
+        REFUND_LOCK_TIME,
+        SegwitBackend::new(),
+    )
+    .expect("test_vectors: internal invariant violated (params must be valid)")
+}
+
+impl ChannelParams<SegwitBackend> {
+    /// Returns deterministic channel parameters for tests.
+    ///
+    /// Same as [`channel_params`]: the payer and payee keys are
+    /// [`payer_key`] and [`payee_key`], the capacity is [`CAPACITY`] and the
+    /// refund lock time is [`REFUND_LOCK_TIME`].
+    ///
+    /// **The keys are public and must never hold real funds.**
+    pub fn new_for_test() -> Self {
+        channel_params()
+    }
+}
+
+/// Returns the payer's UTXO spent by the funding transaction and its outpoint.
+pub fn payer_utxo() -> (OutPoint, TxOut) {
+    let outpoint = OutPoint {
+        txid: Txid::from_byte_array([0x11; 32]),
+        vout: 0,
+    };
+    let utxo = TxOut {
+        amount: PAYER_UTXO_AMOUNT,
+        script_pubkey: payer_address().script_pubkey(),
+    };
+
+    (outpoint, utxo)
+}
+
+/// Returns the signed funding PSBT, spending [`payer_utxo`] with change back to the payer.
+pub fn funding_psbt() -> Psbt {
+    let (outpoint, utxo) = payer_utxo();
+    let mut psbt = channel_params().funding_psbt();
+
+    psbt.unsigned_tx.inputs.push(TxIn {
+        previous_output: outpoint,
+        script_sig: ScriptBuf::new(),
+        sequence: Sequence::MAX,
+        witness: Witness::new(),
+    });
+    psbt.inputs.push(Default::default());
+    psbt.inputs[0].witness_utxo = Some(utxo.clone());
+
+    let change = (PAYER_UTXO_AMOUNT - CAPACITY - FEE)
+        .into_result()
+        .expect("test_vectors: internal invariant violated (Amount calculation must be valid)");
+    psbt.unsigned_tx.outputs.push(TxOut {
+        amount: change,
+        script_pubkey: payer_address().script_pubkey(),
+    });
+    psbt.outputs.push(Output::default());
+
+    let sighash = channel_params()
+        .funding_input_sighash(&psbt, 0, &utxo)
+        .expect("test_vectors: internal invariant violated (input must be p2wpkh)");
+    let signature = sign(&payer_key(), sighash);
+    psbt.inputs[0]
+        .partial_sigs
+        .insert(payer_key().public_key(), signature);
+
+    psbt
+}
+
+/// Returns the finalized funding transaction.
+pub fn funding_tx() -> Transaction {
+    let mut psbt = funding_psbt();
+    let input = &mut psbt.inputs[0];
+    let (pubkey, signature) = input
+        .partial_sigs
+        .pop_first()
+        .expect("test_vectors: internal invariant violated (funding must be signed)");
+
+    let mut witness = Witness::new();
+    witness.push(signature.to_vec());
+    witness.push(pubkey.to_bytes());
+    input.final_script_witness = Some(witness);
+
+    psbt.extract_tx_unchecked_fee_rate()
+}
+
+/// Returns the channel opened by [`funding_tx`].
+pub fn channel() -> Channel<SegwitBackend> {
+    let tx = funding_tx();
+    let outpoint = OutPoint {
+        txid: tx.compute_txid(),
+        vout: 0,
+    };
+
+    channel_params()
+        .verify_funding_tx(&tx, outpoint)
+        .expect("test_vectors: internal invariant violated (funding must be valid)")
+}
+
+/// Returns the payment PSBT for [`PAYMENT_AMOUNT`], signed by both parties.
+pub fn payment_psbt() -> Psbt {
+    let mut psbt = channel()
+        .next_payment(PAYMENT_AMOUNT, FEE)
+        .expect("test_vectors: internal invariant violated (payment must be valid)");
+
+    sign_channel_input(&mut psbt, &payer_key());
+    sign_channel_input(&mut psbt, &payee_key());
+
+    psbt
+}
+
+/// Returns the finalized payment transaction.
+pub fn payment_tx() -> Transaction {
+    let mut psbt = payment_psbt();
+    channel()
+        .finalize_payment_tx(&mut psbt)
+        .expect("test_vectors: internal invariant violated (payment must finalize)");
+
+    psbt.extract_tx_unchecked_fee_rate()
+}
+
+/// Returns the refund PSBT paying back to [`payer_address`], signed by the payer.
+pub fn refund_psbt() -> Psbt {
+    let mut psbt = channel()
+        .refund_psbt_to(&payer_address(), FEE)
+        .expect("test_vectors: internal invariant violated (refund must be valid)");
+
+    sign_channel_input(&mut psbt, &payer_key());
+
+    psbt
+}
+
+/// Returns the finalized refund transaction.
+pub fn refund_tx() -> Transaction {
+    let mut psbt = refund_psbt();
+    channel()
+        .finalize_refund_tx(&mut psbt)
+        .expect("test_vectors: internal invariant violated (refund must finalize)");
+
+    psbt.extract_tx_unchecked_fee_rate()
+}
+
+fn private_key(secret: [u8; 32]) -> PrivateKey {
+    let secret = SecretKey::from_secret_bytes(secret)
+        .expect("test_vectors: internal invariant violated (secret must be valid)");
+    PrivateKey::from_secp(secret, Network::Regtest)
+}
+
+fn sign(key: &PrivateKey, msg: Message) -> Signature {
+    Signature {
+        signature: secp256k1::ecdsa::sign(msg, key.as_inner()),
+        sighash_type: EcdsaSighashType::All,
+    }
+}
+
+fn sign_channel_input(psbt: &mut Psbt, key: &PrivateKey) {
+    let input = &psbt.inputs[0];
+    let witness_script = input
+        .witness_script
+        .as_ref()
+        .expect("test_vectors: internal invariant violated (witness script must be set)");
+    let amount = input
+        .witness_utxo
+        .as_ref()
+        .expect("test_vectors: internal invariant violated (witness utxo must be set)")
+        .amount;
+
+    let sighash = SighashCache::new(&psbt.unsigned_tx)
+        .p2wsh_signature_hash(0, witness_script, amount, EcdsaSighashType::All)
+        .expect("test_vectors: internal invariant violated (sign input 0)");
+    let signature = sign(key, Message::from_digest(sighash.to_byte_array()));
+
+    psbt.inputs[0]
+        .partial_sigs
+        .insert(key.public_key(), signature);
+}
@@ -0,0 +1,70 @@
+use bitcoin::{
+    EcdsaSighashType, Network, PrivateKey, Psbt, PublicKey,
+    ecdsa::Signature,
+    secp256k1::{Message, SecretKey, ecdsa},
+    sighash::SighashCache,
+};
+
+pub struct TestKey {
+    pub privkey: PrivateKey,
+    pub pubkey: PublicKey,
+}
+
+/// Deterministic key derived from `seed`, so tests don't depend on randomness.
+pub fn test_key(seed: u8) -> TestKey {
+    let secret =
+        SecretKey::from_secret_bytes([seed; 32]).expect("seed must give a valid secret key");
+    let privkey = PrivateKey::from_secp(secret, Network::Regtest);
+    let pubkey = privkey.public_key();
+
+    TestKey { privkey, pubkey }
+}
+
+/// Signs input 0 of a P2WSH channel PSBT with the given key and sighash type.
+pub fn sign_channel_input(psbt: &mut Psbt, key: &TestKey, sighash_type: EcdsaSighashType) {
+    let mut cache = SighashCache::new(&psbt.unsigned_tx);
+    let witness_utxo = psbt.inputs[0]
+        .witness_utxo
+        .as_ref()
+        .expect("failed to get witness_utxo from psbt");
+    let witness_script = psbt.inputs[0]
+        .witness_script
+        .as_ref()
+        .expect("failed to get witness_script from psbt");
+
+    let sighash = cache
+        .p2wsh_signature_hash(0, witness_script, witness_utxo.amount, sighash_type)
+        .expect("failed to compute sighash");
+    let msg = Message::from_digest(sighash.to_byte_array());
+
+    let sig = Signature {
+        signature: ecdsa::sign(msg, key.privkey.as_inner()),
+        sighash_type,
+    };
+
+    psbt.inputs[0].partial_sigs.insert(key.pubkey, sig);
+}
+
+/// Replaces the signature of `key` on input 0 by its high-S counterpart.
+///
+/// Replacing S with N - S gives an equally valid but non-standard signature.
+pub fn make_signature_high_s(psbt: &mut Psbt, key: &TestKey) {
+    const ORDER: [u8; 32] = [
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0xfe, 0xba, 0xae, 0xdc, 0xe6, 0xaf, 0x48, 0xa0, 0x3b, 0xbf, 0xd2, 0x5e, 0x8c, 0xd0, 0x36,
+        0x41, 0x41,
+    ];
+
+    let sig = psbt.inputs[0]
+        .partial_sigs
+        .get_mut(&key.pubkey)
+        .expect("signature must be present");
+    let mut compact = sig.signature.serialize_compact();
+    let mut borrow = 0;
+    for i in (0..32).rev() {
+        let diff = i16::from(ORDER[i]) - i16::from(compact[32 + i]) - borrow;
+        borrow = i16::from(diff < 0);
+        compact[32 + i] = diff.rem_euclid(256) as u8;
+    }
+    sig.signature = ecdsa::Signature::from_compact(&compact).expect("signature must be valid");
+}
@@ -1 +1,2 @@
 pub mod conversion_utils;
+pub mod keys;
@@ -0,0 +1,100 @@
+use std::time::{Duration, UNIX_EPOCH};
+
+use bitcoin::{Amount, EcdsaSighashType, FeeRate};
+use spill::{CloseContext, CloseReason, CloseRecommendation};
+
+use crate::{
+    common::keys::{sign_channel_input, test_key},
+    segwit::offline::{open_channel, test_params},
+};
+
+fn context_at(tip_height: u32) -> CloseContext {
+    CloseContext {
+        tip_height,
+        tip_mtp: 0,
+        funding_height: 100,
+        funding_mtp: 0,
+        feerate: FeeRate::from_sat_per_vb(2),
+    }
+}
+
+#[test]
+fn close_advice_follows_refund_maturity() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let mut channel = open_channel(&test_params(&payer, &payee));
+
+    assert_eq!(
+        channel.close_advice(context_at(105)),
+        CloseRecommendation::KeepOpen
+    );
+    assert_eq!(
+        channel.close_advice(context_at(200)),
+        CloseRecommendation::Refund
+    );
+    // A tip below the funding height never matures the refund.
+    assert_eq!(
+        channel.close_advice(context_at(50)),
+        CloseRecommendation::KeepOpen
+    );
+
+    let mut psbt = channel
+        .next_payment(Amount::from_sat_u32(10_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    sign_channel_input(&mut psbt, &payer, EcdsaSighashType::All);
+    channel
+        .apply_payment(&psbt)
+        .expect("failed to apply payment");
+
+    assert_eq!(
+        channel.close_advice(context_at(105)),
+        CloseRecommendation::KeepOpen
+    );
+    assert_eq!(
+        channel.close_advice(context_at(200)),
+        CloseRecommendation::CloseCooperatively(CloseReason::RefundMatured)
+    );
+}
+
+#[test]
+fn close_advice_detects_exhausted_channel() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let mut channel = open_channel(&test_params(&payer, &payee));
+
+    let mut psbt = channel
+        .next_payment(Amount::from_sat_u32(39_500), Amount::from_sat_u32(500))
+        .expect("failed to build payment");
+    sign_channel_input(&mut psbt, &payer, EcdsaSighashType::All);
+    channel
+        .apply_payment(&psbt)
+        .expect("failed to apply payment");
+
+    assert!(matches!(
+        channel.close_advice(context_at(105)),
+        CloseRecommendation::CloseCooperatively(CloseReason::Exhausted { close_fee })
+            if close_fee > Amount::ZERO
+    ));
+}
+
+#[test]
+fn stale_channel_should_close_before_refund() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let opened_at = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    // The refund lock time of 10 blocks is estimated at 6000 seconds.
+    let channel = open_channel(&test_params(&payer, &payee)).with_opened_at(opened_at);
+    let margin = Duration::from_secs(3_600);
+
+    assert_eq!(
+        channel.age(opened_at + Duration::from_secs(1_000)),
+        Duration::from_secs(1_000)
+    );
+    assert_eq!(
+        channel.age(opened_at - Duration::from_secs(1_000)),
+        Duration::ZERO
+    );
+
+    assert!(!channel.should_close_before_refund(opened_at + Duration::from_secs(2_399), margin));
+    assert!(channel.should_close_before_refund(opened_at + Duration::from_secs(2_400), margin));
+}
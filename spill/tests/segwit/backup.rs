@@ -0,0 +1,230 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use bitcoin::{
+    Amount, EcdsaSighashType, OutPoint, Txid, WitnessProgram, WitnessVersion,
+    script::{ScriptBuf, ScriptPubKeyBufExt},
+};
+use spill::{BackupError, ChannelParams, FundingError, SpillError};
+
+use crate::{
+    common::keys::{sign_channel_input, test_key},
+    segwit::offline::{open_channel, test_params},
+};
+
+#[test]
+fn backup_round_trip() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let mut channel = open_channel(&test_params(&payer, &payee));
+
+    let mut psbt = channel
+        .next_payment(Amount::from_sat_u32(10_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    sign_channel_input(&mut psbt, &payer, EcdsaSighashType::All);
+    channel
+        .apply_payment(&psbt)
+        .expect("failed to apply payment");
+
+    let backup = channel.to_backup();
+    let restored = ChannelParams::from_backup(&backup).expect("failed to restore backup");
+
+    assert_eq!(restored.id(), channel.id());
+    assert_eq!(restored.to_backup(), backup);
+
+    let next = restored
+        .next_payment(Amount::from_sat_u32(5_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    assert_eq!(
+        next.unsigned_tx.outputs[0].amount,
+        Amount::from_sat_u32(15_000)
+    );
+
+    let mut unknown = backup.clone();
+    unknown[0] = 4;
+    assert!(matches!(
+        ChannelParams::from_backup(&unknown),
+        Err(SpillError::Backup(BackupError::UnsupportedVersion {
+            version: 4
+        }))
+    ));
+
+    assert!(matches!(
+        ChannelParams::from_backup(&backup[..backup.len() - 1]),
+        Err(SpillError::Backup(BackupError::Malformed))
+    ));
+}
+
+#[test]
+fn bech32_backup_round_trip() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let mut channel = open_channel(&test_params(&payer, &payee));
+
+    let mut psbt = channel
+        .next_payment(Amount::from_sat_u32(10_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    sign_channel_input(&mut psbt, &payer, EcdsaSighashType::All);
+    channel
+        .apply_payment(&psbt)
+        .expect("failed to apply payment");
+
+    let backup = channel.to_bech32_backup().expect("failed to encode backup");
+    assert!(backup.starts_with("spillbak1"));
+
+    let restored = ChannelParams::from_bech32_backup(&backup).expect("failed to restore backup");
+    assert_eq!(restored.to_backup(), channel.to_backup());
+
+    let restored = ChannelParams::from_bech32_backup(&backup.to_uppercase())
+        .expect("failed to restore uppercase backup");
+    assert_eq!(restored.id(), channel.id());
+}
+
+#[test]
+fn bech32_backup_detects_corruption() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let channel = open_channel(&test_params(&payer, &payee));
+
+    let backup = channel.to_bech32_backup().expect("failed to encode backup");
+
+    let mut corrupted: Vec<char> = backup.chars().collect();
+    let index = "spillbak1".len() + 20;
+    corrupted[index] = if corrupted[index] == 'q' { 'p' } else { 'q' };
+    let corrupted: String = corrupted.into_iter().collect();
+
+    assert!(matches!(
+        ChannelParams::from_bech32_backup(&corrupted),
+        Err(SpillError::Backup(BackupError::InvalidEncoding))
+    ));
+}
+
+#[test]
+fn backup_preserves_opening_time() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let opened_at = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    let channel = open_channel(&test_params(&payer, &payee)).with_opened_at(opened_at);
+
+    let backup = channel.to_backup();
+    let restored = ChannelParams::from_backup(&backup).expect("failed to restore backup");
+    assert_eq!(restored.opened_at(), opened_at);
+
+    // Version 2 backups have no payee script.
+    let mut v2 = backup.clone();
+    v2[0] = 2;
+    v2.drain(69..71);
+    let restored = ChannelParams::from_backup(&v2).expect("failed to restore v2 backup");
+    assert_eq!(restored.opened_at(), opened_at);
+
+    // Version 1 backups have no opening time either.
+    let mut v1 = v2.clone();
+    v1[0] = 1;
+    v1.drain(61..69);
+    let before = SystemTime::now();
+    let restored = ChannelParams::from_backup(&v1).expect("failed to restore v1 backup");
+    assert_eq!(restored.id(), channel.id());
+    assert!(restored.opened_at() >= before);
+}
+
+#[test]
+fn backup_preserves_payee_script() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let program = WitnessProgram::new(WitnessVersion::V1, &[0x42; 32])
+        .expect("32-byte v1 program must be valid");
+    let p2tr = ScriptBuf::new_witness_program(&program);
+    let params = test_params(&payer, &payee)
+        .with_payee_script(p2tr.clone())
+        .expect("P2TR payee script must be accepted");
+    let channel = open_channel(&params);
+
+    let mut restored =
+        ChannelParams::from_backup(&channel.to_backup()).expect("failed to restore backup");
+    let mut psbt = channel
+        .next_payment(Amount::from_sat_u32(10_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    assert_eq!(psbt.unsigned_tx.outputs[0].script_pubkey, p2tr);
+    sign_channel_input(&mut psbt, &payer, EcdsaSighashType::All);
+    restored
+        .apply_payment(&psbt)
+        .expect("payment to the P2TR payee script must verify after restore");
+
+    let restored = ChannelParams::from_bech32_backup(
+        &restored
+            .to_bech32_backup()
+            .expect("failed to encode backup"),
+    )
+    .expect("failed to restore backup");
+    let next = restored
+        .next_payment(Amount::from_sat_u32(5_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    assert_eq!(next.unsigned_tx.outputs[0].script_pubkey, p2tr);
+    assert_eq!(
+        next.unsigned_tx.outputs[0].amount,
+        Amount::from_sat_u32(15_000)
+    );
+}
+
+#[test]
+fn resume_rejects_tampered_funding_utxo() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let params = test_params(&payer, &payee);
+    let outpoint = OutPoint {
+        txid: Txid::from_byte_array([0xcd; 32]),
+        vout: 0,
+    };
+    let sent = Amount::from_sat_u32(10_000);
+    let payer_change = Amount::from_sat_u32(29_000);
+
+    let mut utxo = params.expected_funding_output();
+    params
+        .resume_channel(outpoint, utxo.clone(), sent, payer_change)
+        .expect("untampered state must resume");
+
+    utxo.amount = Amount::from_sat_u32(50_000);
+    assert!(matches!(
+        params.resume_channel(outpoint, utxo.clone(), sent, payer_change),
+        Err(SpillError::Funding(FundingError::ValueTooHigh { .. }))
+    ));
+
+    utxo.amount = Amount::from_sat_u32(30_000);
+    assert!(matches!(
+        params.resume_channel(outpoint, utxo.clone(), sent, payer_change),
+        Err(SpillError::Funding(FundingError::ValueTooLow { .. }))
+    ));
+
+    let mut utxo = params.expected_funding_output();
+    utxo.script_pubkey = ScriptBuf::new_p2a();
+    assert!(matches!(
+        params.resume_channel(outpoint, utxo, sent, payer_change),
+        Err(SpillError::Funding(FundingError::ScriptMismatch))
+    ));
+}
+
+#[test]
+fn reconcile_returns_most_advanced_state() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let params = test_params(&payer, &payee);
+    let stale = open_channel(&params);
+    let mut primary = open_channel(&params);
+
+    let mut psbt = primary
+        .next_payment(Amount::from_sat_u32(10_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    sign_channel_input(&mut psbt, &payer, EcdsaSighashType::All);
+    primary
+        .apply_payment(&psbt)
+        .expect("failed to apply payment");
+
+    let sent = Amount::from_sat_u32(10_000);
+    assert_eq!(primary.reconcile(&stale).expect("same channel"), sent);
+    assert_eq!(stale.reconcile(&primary).expect("same channel"), sent);
+
+    let other = open_channel(&test_params(&payer, &test_key(3)));
+    assert!(matches!(
+        primary.reconcile(&other),
+        Err(SpillError::Funding(FundingError::ChannelMismatch))
+    ));
+}
@@ -0,0 +1,109 @@
+use bitcoin::{
+    Amount, EcdsaSighashType,
+    consensus::encode::serialize,
+    consensus_validation::ScriptPubKeyExt,
+    primitives::relative,
+    secp256k1::{self, Message},
+    sighash::SighashCache,
+};
+use spill::{ChannelParams, SegwitBackend};
+
+use crate::{
+    common::keys::{sign_channel_input, test_key},
+    segwit::offline::{open_channel, test_params},
+};
+
+/// Checks the finalized payment against the funding script with libbitcoinconsensus,
+/// which fails if the signatures are not in the order of the script keys.
+#[test]
+fn finalized_payment_satisfies_funding_script() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let params = test_params(&payer, &payee);
+    let channel = open_channel(&params);
+
+    let mut psbt = channel
+        .next_payment(Amount::from_sat_u32(10_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    // Sign in reverse order so the witness order doesn't follow insertion order.
+    sign_channel_input(&mut psbt, &payee, EcdsaSighashType::All);
+    sign_channel_input(&mut psbt, &payer, EcdsaSighashType::All);
+    channel
+        .finalize_payment_tx(&mut psbt)
+        .expect("failed to finalize payment");
+
+    let tx = psbt.extract_tx_unchecked_fee_rate();
+    params
+        .script_pubkey()
+        .verify(0, Amount::from_sat_u32(40_000), &serialize(&tx))
+        .expect("witness must satisfy the funding script");
+}
+
+#[test]
+fn finalized_threshold_payment_satisfies_funding_script() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let arbiter = test_key(3);
+    let params = ChannelParams::new_threshold(
+        payer.pubkey,
+        payee.pubkey,
+        vec![arbiter.pubkey],
+        2,
+        Amount::from_sat_u32(40_000),
+        relative::LockTime::from_height(10),
+        SegwitBackend::new(),
+    )
+    .expect("failed to create ChannelParams");
+    let channel = open_channel(&params);
+
+    let mut psbt = channel
+        .next_payment(Amount::from_sat_u32(10_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    sign_channel_input(&mut psbt, &arbiter, EcdsaSighashType::All);
+    sign_channel_input(&mut psbt, &payee, EcdsaSighashType::All);
+    channel
+        .finalize_payment_tx(&mut psbt)
+        .expect("failed to finalize payment");
+
+    let tx = psbt.extract_tx_unchecked_fee_rate();
+    params
+        .script_pubkey()
+        .verify(0, Amount::from_sat_u32(40_000), &serialize(&tx))
+        .expect("witness must satisfy the funding script");
+}
+
+/// Reproduces the payment sighash from the script code alone, as an auditor would.
+#[test]
+fn payment_sighash_reproduced_from_scriptcode() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let params = test_params(&payer, &payee);
+    let channel = open_channel(&params);
+
+    let mut psbt = channel
+        .next_payment(Amount::from_sat_u32(10_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    sign_channel_input(&mut psbt, &payer, EcdsaSighashType::All);
+
+    let scriptcode = params.sighash_scriptcode();
+    assert_eq!(psbt.inputs[0].witness_script.as_deref(), Some(scriptcode));
+
+    let sighash = SighashCache::new(&psbt.unsigned_tx)
+        .p2wsh_signature_hash(
+            0,
+            scriptcode,
+            Amount::from_sat_u32(40_000),
+            EcdsaSighashType::All,
+        )
+        .expect("input 0 must exist");
+    let signature = psbt.inputs[0].partial_sigs[&payer.pubkey].signature;
+
+    assert!(
+        secp256k1::ecdsa::verify(
+            &signature,
+            Message::from_digest(sighash.to_byte_array()),
+            &payer.pubkey.to_inner(),
+        )
+        .is_ok()
+    );
+}
@@ -0,0 +1,72 @@
+use bitcoin::{Amount, EcdsaSighashType};
+use spill::{BackupError, Channel, ChannelParams, SegwitBackend, SpillError};
+
+use crate::{
+    common::keys::{sign_channel_input, test_key},
+    segwit::offline::{open_channel, test_params},
+};
+
+const KEY: [u8; 32] = [0x42; 32];
+
+fn channel_with_payment() -> Channel<SegwitBackend> {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let mut channel = open_channel(&test_params(&payer, &payee));
+
+    let mut psbt = channel
+        .next_payment(Amount::from_sat_u32(10_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    sign_channel_input(&mut psbt, &payer, EcdsaSighashType::All);
+    channel
+        .apply_payment(&psbt)
+        .expect("failed to apply payment");
+
+    channel
+}
+
+#[test]
+fn encrypted_backup_round_trip() {
+    let channel = channel_with_payment();
+
+    let encrypted = channel.to_encrypted_backup(&KEY);
+    // The nonce is random, so encrypting twice never gives the same backup.
+    assert_ne!(encrypted, channel.to_encrypted_backup(&KEY));
+
+    let restored =
+        ChannelParams::from_encrypted_backup(&encrypted, &KEY).expect("failed to restore backup");
+    assert_eq!(restored.id(), channel.id());
+    assert_eq!(restored.to_backup(), channel.to_backup());
+}
+
+#[test]
+fn encrypted_backup_rejects_wrong_key() {
+    let encrypted = channel_with_payment().to_encrypted_backup(&KEY);
+
+    assert!(matches!(
+        ChannelParams::from_encrypted_backup(&encrypted, &[0x43; 32]),
+        Err(SpillError::Backup(BackupError::DecryptionFailed))
+    ));
+}
+
+#[test]
+fn encrypted_backup_rejects_tampering() {
+    let encrypted = channel_with_payment().to_encrypted_backup(&KEY);
+
+    for index in [0, 12, encrypted.len() - 1] {
+        let mut tampered = encrypted.clone();
+        tampered[index] ^= 0x01;
+        assert!(matches!(
+            ChannelParams::from_encrypted_backup(&tampered, &KEY),
+            Err(SpillError::Backup(BackupError::DecryptionFailed))
+        ));
+    }
+
+    assert!(matches!(
+        ChannelParams::from_encrypted_backup(&encrypted[..20], &KEY),
+        Err(SpillError::Backup(BackupError::DecryptionFailed))
+    ));
+    assert!(matches!(
+        ChannelParams::from_encrypted_backup(&encrypted[..11], &KEY),
+        Err(SpillError::Backup(BackupError::Malformed))
+    ));
+}
@@ -0,0 +1,68 @@
+use bitcoin::{Amount, EcdsaSighashType, Psbt};
+use spill::{PaymentError, SpillError};
+
+use crate::{
+    common::keys::{sign_channel_input, test_key},
+    segwit::offline::{open_channel, test_params},
+};
+
+#[test]
+fn base64_psbts_round_trip() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let params = test_params(&payer, &payee);
+    let mut channel = open_channel(&params);
+
+    let funding: Psbt = params
+        .funding_psbt_base64()
+        .parse()
+        .expect("funding PSBT must decode");
+    assert_eq!(funding, params.funding_psbt());
+
+    let refund: Psbt = channel
+        .refund_psbt_base64()
+        .parse()
+        .expect("refund PSBT must decode");
+    assert_eq!(refund, channel.refund_psbt());
+
+    let encoded = channel
+        .payment_psbt_base64(Amount::from_sat_u32(10_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    let mut payment: Psbt = encoded.parse().expect("payment PSBT must decode");
+    sign_channel_input(&mut payment, &payer, EcdsaSighashType::All);
+
+    let (decoded, info) = channel
+        .verify_payment_psbt_base64(&payment.to_string())
+        .expect("signed payment must verify");
+    assert_eq!(decoded, payment);
+    assert_eq!(info.total, Amount::from_sat_u32(10_000));
+    assert_eq!(info.fee, Amount::from_sat_u32(1_000));
+
+    channel
+        .apply_payment(&decoded)
+        .expect("failed to apply decoded payment");
+}
+
+#[test]
+fn malformed_base64_payment_is_rejected() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let channel = open_channel(&test_params(&payer, &payee));
+
+    // Neither valid base64, nor base64 of a PSBT.
+    for encoded in ["not base64!", "aGVsbG8gd29ybGQ=", ""] {
+        assert!(matches!(
+            channel.verify_payment_psbt_base64(encoded),
+            Err(SpillError::Payment(PaymentError::InvalidEncoding))
+        ));
+    }
+
+    // A truncated PSBT is rejected as well.
+    let encoded = channel
+        .payment_psbt_base64(Amount::from_sat_u32(10_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    assert!(matches!(
+        channel.verify_payment_psbt_base64(&encoded[..encoded.len() - 8]),
+        Err(SpillError::Payment(PaymentError::InvalidEncoding))
+    ));
+}
@@ -0,0 +1,667 @@
+use std::collections::BTreeMap;
+
+use bitcoin::{
+    Amount, EcdsaSighashType, OutPoint, Psbt, RedeemScriptBuf, ScriptPubKeyBuf, Sequence,
+    Transaction, TxIn, TxOut, Txid, Witness, absolute,
+    script::{ScriptBuf, ScriptBufExt, ScriptExt, ScriptPubKeyBufExt},
+    secp256k1::Message,
+    sighash::SighashCache,
+    transaction,
+};
+use spill::{ChannelPolicy, FundingError, SpillError};
+
+use crate::{
+    common::keys::test_key,
+    segwit::offline::{open_channel, test_params},
+};
+
+#[test]
+fn funding_value_mismatch_reports_amounts() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let params = test_params(&payer, &payee);
+
+    let mut funding_tx = params.funding_psbt().unsigned_tx;
+    funding_tx.outputs[0].amount = Amount::from_sat_u32(39_000);
+    let outpoint = OutPoint {
+        txid: funding_tx.compute_txid(),
+        vout: 0,
+    };
+
+    assert!(matches!(
+        params.verify_funding_tx(&funding_tx, outpoint),
+        Err(SpillError::Funding(FundingError::ValueTooLow { found, expected }))
+            if found == Amount::from_sat_u32(39_000) && expected == Amount::from_sat_u32(40_000)
+    ));
+
+    funding_tx.outputs[0].amount = Amount::from_sat_u32(41_000);
+    let outpoint = OutPoint {
+        txid: funding_tx.compute_txid(),
+        vout: 0,
+    };
+
+    assert!(matches!(
+        params.verify_funding_tx(&funding_tx, outpoint),
+        Err(SpillError::Funding(FundingError::ValueTooHigh { found, .. }))
+            if found == Amount::from_sat_u32(41_000)
+    ));
+}
+
+#[test]
+fn funding_output_is_merged_into_wallet_psbt() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let params = test_params(&payer, &payee);
+
+    let wallet_tx = Transaction {
+        version: transaction::Version::TWO,
+        lock_time: absolute::LockTime::ZERO,
+        inputs: vec![TxIn {
+            previous_output: OutPoint {
+                txid: Txid::from_byte_array([0xab; 32]),
+                vout: 0,
+            },
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::MAX,
+            witness: Witness::new(),
+        }],
+        outputs: vec![TxOut {
+            amount: Amount::from_sat_u32(9_000),
+            script_pubkey: ScriptBuf::new(),
+        }],
+    };
+    let mut wallet_psbt = Psbt::from_unsigned_tx(wallet_tx).expect("failed to create PSBT");
+
+    assert!(matches!(
+        params.merge_into_funding_psbt(wallet_psbt.clone()),
+        Err(SpillError::Funding(FundingError::MissingInputUtxo {
+            index: 0
+        }))
+    ));
+
+    wallet_psbt.inputs[0].witness_utxo = Some(TxOut {
+        amount: Amount::from_sat_u32(45_000),
+        script_pubkey: ScriptBuf::new(),
+    });
+
+    assert!(matches!(
+        params.merge_into_funding_psbt(wallet_psbt.clone()),
+        Err(SpillError::Funding(FundingError::InsufficientFunds { required, .. }))
+            if required == Amount::from_sat_u32(49_000)
+    ));
+
+    wallet_psbt.inputs[0]
+        .witness_utxo
+        .as_mut()
+        .expect("witness utxo was set")
+        .amount = Amount::from_sat_u32(50_000);
+    let psbt = params
+        .merge_into_funding_psbt(wallet_psbt)
+        .expect("failed to merge funding output");
+
+    assert_eq!(psbt.unsigned_tx.outputs.len(), 2);
+    assert_eq!(
+        psbt.unsigned_tx.outputs[0],
+        params.expected_funding_output()
+    );
+    assert!(psbt.outputs[0].witness_script.is_some());
+}
+
+#[test]
+fn funding_output_at_requested_index() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let params = test_params(&payer, &payee);
+
+    let psbt = params
+        .funding_psbt_at_index(2)
+        .expect("failed to build funding psbt");
+
+    assert_eq!(psbt.unsigned_tx.outputs.len(), 3);
+    assert_eq!(
+        psbt.unsigned_tx.outputs[2],
+        params.expected_funding_output()
+    );
+    assert!(psbt.outputs[2].witness_script.is_some());
+    assert!(psbt.outputs[0].witness_script.is_none());
+
+    assert!(matches!(
+        params.funding_psbt_at_index(usize::MAX),
+        Err(SpillError::Funding(FundingError::OutputIndexOutOfRange))
+    ));
+}
+
+#[test]
+fn dust_funding_change_is_dropped() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let params = test_params(&payer, &payee);
+    let change_script = ScriptPubKeyBuf::new_p2wpkh(
+        payer
+            .pubkey
+            .wpubkey_hash()
+            .expect("payer key must be compressed"),
+    );
+
+    let utxo = |amount| {
+        (
+            OutPoint {
+                txid: Txid::from_byte_array([0xab; 32]),
+                vout: 0,
+            },
+            TxOut {
+                amount: Amount::from_sat_u32(amount),
+                script_pubkey: change_script.clone(),
+            },
+        )
+    };
+    let fee = Amount::from_sat_u32(1_000);
+
+    let psbt = params
+        .fund_from_utxos(&[utxo(41_100)], change_script.clone(), fee)
+        .expect("failed to build funding psbt");
+    assert_eq!(psbt.unsigned_tx.outputs.len(), 1);
+    assert_eq!(psbt.inputs[0].witness_utxo, Some(utxo(41_100).1));
+
+    let psbt = params
+        .fund_from_utxos(&[utxo(50_000)], change_script.clone(), fee)
+        .expect("failed to build funding psbt");
+    assert_eq!(psbt.unsigned_tx.outputs.len(), 2);
+    assert_eq!(
+        psbt.unsigned_tx.outputs[1].amount,
+        Amount::from_sat_u32(9_000)
+    );
+
+    assert!(matches!(
+        params.fund_from_utxos(&[utxo(40_500)], change_script, fee),
+        Err(SpillError::Funding(FundingError::InsufficientFunds { .. }))
+    ));
+}
+
+#[test]
+fn funding_input_sighash_signs_p2wpkh_inputs() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let params = test_params(&payer, &payee);
+    let utxo = TxOut {
+        amount: Amount::from_sat_u32(50_000),
+        script_pubkey: ScriptPubKeyBuf::new_p2wpkh(
+            payer
+                .pubkey
+                .wpubkey_hash()
+                .expect("payer key must be compressed"),
+        ),
+    };
+    let outpoint = OutPoint {
+        txid: Txid::from_byte_array([0xab; 32]),
+        vout: 0,
+    };
+
+    let psbt = params
+        .fund_from_utxos(
+            &[(outpoint, utxo.clone())],
+            utxo.script_pubkey.clone(),
+            Amount::from_sat_u32(1_000),
+        )
+        .expect("failed to build funding psbt");
+
+    let expected = SighashCache::new(&psbt.unsigned_tx)
+        .p2wpkh_signature_hash(0, &utxo.script_pubkey, utxo.amount, EcdsaSighashType::All)
+        .expect("input must be p2wpkh");
+    assert_eq!(
+        params
+            .funding_input_sighash(&psbt, 0, &utxo)
+            .expect("failed to compute sighash"),
+        Message::from_digest(expected.to_byte_array())
+    );
+
+    assert!(matches!(
+        params.funding_input_sighash(&psbt, 1, &utxo),
+        Err(SpillError::Funding(FundingError::InputIndexOutOfRange {
+            index: 1
+        }))
+    ));
+
+    let p2wsh = TxOut {
+        amount: utxo.amount,
+        script_pubkey: params.script_pubkey().clone(),
+    };
+    assert!(matches!(
+        params.funding_input_sighash(&psbt, 0, &p2wsh),
+        Err(SpillError::Funding(FundingError::UnsupportedInputScript {
+            index: 0
+        }))
+    ));
+}
+
+#[test]
+fn funding_input_sighash_signs_wrapped_p2wpkh_inputs() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let params = test_params(&payer, &payee);
+    let redeem_script = RedeemScriptBuf::new_p2wpkh(
+        payer
+            .pubkey
+            .wpubkey_hash()
+            .expect("payer key must be compressed"),
+    );
+    let utxo = TxOut {
+        amount: Amount::from_sat_u32(50_000),
+        script_pubkey: redeem_script
+            .to_p2sh()
+            .expect("redeem script must be hashable"),
+    };
+    let outpoint = OutPoint {
+        txid: Txid::from_byte_array([0xab; 32]),
+        vout: 0,
+    };
+
+    let mut psbt = params
+        .fund_from_utxos(
+            &[(outpoint, utxo.clone())],
+            utxo.script_pubkey.clone(),
+            Amount::from_sat_u32(1_000),
+        )
+        .expect("failed to build funding psbt");
+
+    // The redeem script is needed to know the P2SH output wraps a P2WPKH.
+    assert!(matches!(
+        params.funding_input_sighash(&psbt, 0, &utxo),
+        Err(SpillError::Funding(FundingError::UnsupportedInputScript {
+            index: 0
+        }))
+    ));
+
+    psbt.inputs[0].redeem_script = Some(redeem_script.clone());
+    let expected = SighashCache::new(&psbt.unsigned_tx)
+        .p2wpkh_signature_hash(0, &redeem_script, utxo.amount, EcdsaSighashType::All)
+        .expect("input must be p2sh-p2wpkh");
+    assert_eq!(
+        params
+            .funding_input_sighash(&psbt, 0, &utxo)
+            .expect("failed to compute sighash"),
+        Message::from_digest(expected.to_byte_array())
+    );
+
+    let other = RedeemScriptBuf::new_p2wpkh(
+        payee
+            .pubkey
+            .wpubkey_hash()
+            .expect("payee key must be compressed"),
+    );
+    psbt.inputs[0].redeem_script = Some(other);
+    assert!(matches!(
+        params.funding_input_sighash(&psbt, 0, &utxo),
+        Err(SpillError::Funding(FundingError::UnsupportedInputScript {
+            index: 0
+        }))
+    ));
+}
+
+#[test]
+fn funding_input_sighash_rejects_legacy_and_taproot_inputs() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let params = test_params(&payer, &payee);
+    let outpoint = OutPoint {
+        txid: Txid::from_byte_array([0xab; 32]),
+        vout: 0,
+    };
+
+    let p2pkh = ScriptPubKeyBuf::new_p2pkh(payer.pubkey.pubkey_hash());
+    let mut p2tr = vec![0x51, 0x20];
+    p2tr.extend_from_slice(&[0x02; 32]);
+    let p2tr = ScriptPubKeyBuf::from_bytes(p2tr);
+
+    for script_pubkey in [p2pkh, p2tr] {
+        let utxo = TxOut {
+            amount: Amount::from_sat_u32(50_000),
+            script_pubkey,
+        };
+        let psbt = params
+            .fund_from_utxos(
+                &[(outpoint, utxo.clone())],
+                utxo.script_pubkey.clone(),
+                Amount::from_sat_u32(1_000),
+            )
+            .expect("failed to build funding psbt");
+
+        assert!(matches!(
+            params.funding_input_sighash(&psbt, 0, &utxo),
+            Err(SpillError::Funding(FundingError::UnsupportedInputScript {
+                index: 0
+            }))
+        ));
+    }
+}
+
+#[test]
+fn funding_fee_is_bounded() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let params = test_params(&payer, &payee);
+    let change_script = ScriptPubKeyBuf::new_p2wpkh(
+        payer
+            .pubkey
+            .wpubkey_hash()
+            .expect("payer key must be compressed"),
+    );
+    let utxo = (
+        OutPoint {
+            txid: Txid::from_byte_array([0xab; 32]),
+            vout: 0,
+        },
+        TxOut {
+            amount: Amount::from_sat_u32(50_000),
+            script_pubkey: change_script.clone(),
+        },
+    );
+
+    let mut psbt = params
+        .fund_from_utxos(&[utxo], change_script, Amount::from_sat_u32(1_000))
+        .expect("failed to build funding psbt");
+    assert_eq!(
+        params
+            .verify_funding_fee(&psbt, Amount::from_sat_u32(1_000))
+            .expect("fee must be within the maximum"),
+        Amount::from_sat_u32(1_000)
+    );
+
+    // Dropping the change output turns it into fee.
+    psbt.unsigned_tx.outputs.pop();
+    psbt.outputs.pop();
+    assert!(matches!(
+        params.verify_funding_fee(&psbt, Amount::from_sat_u32(1_000)),
+        Err(SpillError::Funding(FundingError::FeeTooHigh { fee, .. }))
+            if fee == Amount::from_sat_u32(10_000)
+    ));
+}
+
+#[test]
+fn funding_tx_prevouts_must_cover_outputs() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let params = test_params(&payer, &payee);
+    let prevout = |amount, vout| {
+        (
+            OutPoint {
+                txid: Txid::from_byte_array([0xab; 32]),
+                vout,
+            },
+            TxOut {
+                amount: Amount::from_sat_u32(amount),
+                script_pubkey: ScriptPubKeyBuf::new_p2wpkh(
+                    payer
+                        .pubkey
+                        .wpubkey_hash()
+                        .expect("payer key must be compressed"),
+                ),
+            },
+        )
+    };
+    let utxos = [prevout(25_000, 0), prevout(16_000, 1)];
+    let prevouts = utxos
+        .iter()
+        .map(|(_, utxo)| utxo.clone())
+        .collect::<Vec<_>>();
+
+    let funding_tx = params
+        .fund_from_utxos(&utxos, prevouts[0].script_pubkey.clone(), Amount::ZERO)
+        .expect("failed to build funding psbt")
+        .unsigned_tx;
+    let outpoint = OutPoint {
+        txid: funding_tx.compute_txid(),
+        vout: 0,
+    };
+
+    let channel = params
+        .verify_funding_tx_with_prevouts(&funding_tx, outpoint, &prevouts)
+        .expect("combined inputs must cover the funding tx");
+    assert_eq!(
+        channel.funding_info().capacity,
+        Amount::from_sat_u32(40_000)
+    );
+
+    assert!(matches!(
+        params.verify_funding_tx_with_prevouts(&funding_tx, outpoint, &prevouts[..1]),
+        Err(SpillError::Funding(FundingError::PrevoutCountMismatch {
+            inputs: 2,
+            prevouts: 1
+        }))
+    ));
+
+    let short = [prevouts[0].clone(), prevout(14_000, 1).1];
+    assert!(matches!(
+        params.verify_funding_tx_with_prevouts(&funding_tx, outpoint, &short),
+        Err(SpillError::Funding(FundingError::InsufficientFunds { .. }))
+    ));
+}
+
+#[test]
+fn duplicated_funding_output_is_ambiguous_in_strict_mode() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let params = test_params(&payer, &payee);
+
+    let mut funding_tx = params.funding_psbt().unsigned_tx;
+    funding_tx.outputs.push(params.expected_funding_output());
+    let outpoint = OutPoint {
+        txid: funding_tx.compute_txid(),
+        vout: 1,
+    };
+
+    params
+        .verify_funding_tx(&funding_tx, outpoint)
+        .expect("default policy must accept an explicit vout");
+
+    let mut policy = ChannelPolicy::default();
+    policy.reject_ambiguous_funding = true;
+    let strict = params.with_policy(policy);
+    assert!(matches!(
+        strict.verify_funding_tx(&funding_tx, outpoint),
+        Err(SpillError::Funding(FundingError::AmbiguousFundingOutput {
+            count: 2
+        }))
+    ));
+
+    funding_tx.outputs.pop();
+    let outpoint = OutPoint {
+        txid: funding_tx.compute_txid(),
+        vout: 0,
+    };
+    strict
+        .verify_funding_tx(&funding_tx, outpoint)
+        .expect("a single funding output is unambiguous");
+}
+
+#[test]
+fn funding_candidates_are_verified_positionally() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let params = test_params(&payer, &payee);
+
+    let original = params.funding_psbt().unsigned_tx;
+    let original_outpoint = OutPoint {
+        txid: original.compute_txid(),
+        vout: 0,
+    };
+
+    // A replacement that mistakenly shrinks the funding output.
+    let mut shrunk = original.clone();
+    shrunk.lock_time = absolute::LockTime::from_consensus(1);
+    shrunk.outputs[0].amount = Amount::from_sat_u32(39_000);
+    let shrunk_outpoint = OutPoint {
+        txid: shrunk.compute_txid(),
+        vout: 0,
+    };
+
+    let mut bumped = original.clone();
+    bumped.lock_time = absolute::LockTime::from_consensus(2);
+    let bumped_outpoint = OutPoint {
+        txid: bumped.compute_txid(),
+        vout: 0,
+    };
+
+    let results = params.verify_funding_candidates(&[
+        (original, original_outpoint),
+        (shrunk, shrunk_outpoint),
+        (bumped.clone(), original_outpoint),
+        (bumped, bumped_outpoint),
+    ]);
+
+    assert_eq!(results.len(), 4);
+    assert_eq!(
+        results[0]
+            .as_ref()
+            .expect("original must verify")
+            .id()
+            .outpoint(),
+        original_outpoint
+    );
+    assert!(matches!(
+        results[1],
+        Err(SpillError::Funding(FundingError::ValueTooLow { .. }))
+    ));
+    assert!(matches!(
+        results[2],
+        Err(SpillError::Funding(FundingError::TxidMismatch))
+    ));
+    assert_eq!(
+        results[3]
+            .as_ref()
+            .expect("replacement must verify")
+            .id()
+            .outpoint(),
+        bumped_outpoint
+    );
+}
+
+#[test]
+fn confirm_funding_counts_confirmations() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let channel = open_channel(&test_params(&payer, &payee));
+
+    // A funding height above the tip is rejected even if no confirmations are required.
+    assert!(matches!(
+        channel.confirm_funding(101, 100, 0),
+        Err(SpillError::Funding(FundingError::InvalidConfirmationHeight))
+    ));
+    assert_eq!(
+        channel
+            .confirm_funding(100, 100, 0)
+            .expect("no confirmations are required"),
+        1
+    );
+
+    assert_eq!(
+        channel
+            .confirm_funding(100, 100, 1)
+            .expect("one confirmation must be enough"),
+        1
+    );
+    assert!(matches!(
+        channel.confirm_funding(100, 100, 6),
+        Err(SpillError::Funding(
+            FundingError::InsufficientConfirmations {
+                confirmations: 1,
+                required: 6
+            }
+        ))
+    ));
+
+    assert_eq!(
+        channel
+            .confirm_funding(100, 105, 6)
+            .expect("six confirmations must be enough"),
+        6
+    );
+    assert_eq!(
+        channel
+            .confirm_funding(100, 1_000, 6)
+            .expect("many confirmations must be enough"),
+        901
+    );
+
+    assert!(matches!(
+        channel.confirm_funding(0, u32::MAX, 1),
+        Err(SpillError::Funding(FundingError::InvalidConfirmationHeight))
+    ));
+    assert_eq!(
+        channel
+            .confirm_funding(1, u32::MAX, 1)
+            .expect("the count must fit"),
+        u32::MAX
+    );
+}
+
+#[test]
+fn funding_info_summarizes_verified_output() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let params = test_params(&payer, &payee);
+
+    let mut funding_tx = params
+        .funding_psbt_at_index(1)
+        .expect("failed to build funding psbt")
+        .unsigned_tx;
+    funding_tx.inputs.push(TxIn {
+        previous_output: OutPoint {
+            txid: Txid::from_byte_array([0xab; 32]),
+            vout: 0,
+        },
+        script_sig: ScriptBuf::new(),
+        sequence: Sequence::MAX,
+        witness: Witness::new(),
+    });
+    let outpoint = OutPoint {
+        txid: funding_tx.compute_txid(),
+        vout: 1,
+    };
+
+    let channel = params
+        .verify_funding_tx(&funding_tx, outpoint)
+        .expect("funding output at index 1 must verify");
+    let info = channel.funding_info();
+    assert_eq!(info.outpoint, outpoint);
+    assert_eq!(info.outpoint, channel.id().outpoint());
+    assert_eq!(info.capacity, Amount::from_sat_u32(40_000));
+    assert_eq!(&info.funding_spk, params.script_pubkey());
+    assert_eq!(funding_tx.outputs[1].script_pubkey, info.funding_spk);
+}
+
+#[test]
+fn channel_id_keys_channels_by_funding_outpoint() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let params = test_params(&payer, &payee);
+    let funding_tx = params.funding_psbt().unsigned_tx;
+    let open_at = |vout| {
+        params
+            .resume_channel(
+                OutPoint {
+                    txid: funding_tx.compute_txid(),
+                    vout,
+                },
+                params.expected_funding_output(),
+                Amount::ZERO,
+                Amount::ZERO,
+            )
+            .expect("failed to resume channel")
+    };
+
+    let first = open_at(0);
+    let second = open_at(1);
+    assert_eq!(first.id().outpoint(), first.funding_info().outpoint);
+    assert_eq!(first.id(), open_at(0).id());
+    assert_ne!(first.id(), second.id());
+
+    // Ids order like their outpoints, so they can key sorted maps.
+    assert!(first.id() < second.id());
+    let channels = BTreeMap::from([(second.id(), "second"), (first.id(), "first")]);
+    assert_eq!(
+        channels.values().copied().collect::<Vec<_>>(),
+        ["first", "second"]
+    );
+    assert_eq!(channels[&open_at(1).id()], "second");
+}
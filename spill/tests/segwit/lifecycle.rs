@@ -0,0 +1,123 @@
+use bitcoin::{
+    Amount, EcdsaSighashType, OutPoint, ScriptPubKeyBuf, Sequence, TxOut, Txid, Witness,
+    ecdsa::Signature, script::ScriptBufExt, secp256k1::ecdsa,
+};
+
+use crate::{
+    common::keys::{sign_channel_input, test_key},
+    segwit::offline::test_params,
+};
+
+/// Runs the funding, payment and refund lifecycle end to end without a node.
+///
+/// Mirrors the node-backed settlement and refund flows with deterministic
+/// keys, checking the structure of every extracted transaction instead of
+/// broadcasting it.
+#[test]
+fn channel_lifecycle_without_node() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let params = test_params(&payer, &payee);
+    let fee = Amount::from_sat_u32(1_000);
+
+    // Funding: spend a deterministic P2WPKH UTXO of the payer.
+    let payer_script = ScriptPubKeyBuf::new_p2wpkh(
+        payer
+            .pubkey
+            .wpubkey_hash()
+            .expect("payer key must be compressed"),
+    );
+    let utxo = TxOut {
+        amount: Amount::from_sat_u32(50_000),
+        script_pubkey: payer_script.clone(),
+    };
+    let outpoint = OutPoint {
+        txid: Txid::from_byte_array([0xab; 32]),
+        vout: 0,
+    };
+
+    let mut funding_psbt = params
+        .fund_from_utxos(&[(outpoint, utxo.clone())], payer_script.clone(), fee)
+        .expect("failed to build funding psbt");
+    let sighash = params
+        .funding_input_sighash(&funding_psbt, 0, &utxo)
+        .expect("failed to compute funding sighash");
+    let signature = Signature {
+        signature: ecdsa::sign(sighash, payer.privkey.as_inner()),
+        sighash_type: EcdsaSighashType::All,
+    };
+    let mut witness = Witness::new();
+    witness.push(signature.to_vec());
+    witness.push(payer.pubkey.to_bytes());
+    funding_psbt.inputs[0].final_script_witness = Some(witness);
+
+    let funding_tx = funding_psbt.extract_tx_unchecked_fee_rate();
+    assert_eq!(funding_tx.inputs.len(), 1);
+    assert_eq!(funding_tx.outputs.len(), 2);
+    assert_eq!(funding_tx.inputs[0].witness.len(), 2);
+
+    let funding_outpoint = OutPoint {
+        txid: funding_tx.compute_txid(),
+        vout: 0,
+    };
+    let mut channel = params
+        .verify_funding_tx(&funding_tx, funding_outpoint)
+        .expect("failed to verify funding transaction");
+
+    // Payments: two increments, the latest countersigned and finalized by the payee.
+    let mut payment_psbt = channel
+        .next_payment(Amount::from_sat_u32(10_000), fee)
+        .expect("failed to build first payment");
+    sign_channel_input(&mut payment_psbt, &payer, EcdsaSighashType::All);
+    channel
+        .apply_payment(&payment_psbt)
+        .expect("failed to apply first payment");
+
+    let mut payment_psbt = channel
+        .next_payment(Amount::from_sat_u32(3_000), fee)
+        .expect("failed to build second payment");
+    sign_channel_input(&mut payment_psbt, &payer, EcdsaSighashType::All);
+    channel
+        .apply_payment(&payment_psbt)
+        .expect("failed to apply second payment");
+
+    sign_channel_input(&mut payment_psbt, &payee, EcdsaSighashType::All);
+    channel
+        .finalize_payment_tx(&mut payment_psbt)
+        .expect("failed to finalize payment");
+    let payment_tx = payment_psbt.extract_tx_unchecked_fee_rate();
+
+    assert_eq!(payment_tx.inputs.len(), 1);
+    assert_eq!(payment_tx.inputs[0].previous_output, funding_outpoint);
+    assert_eq!(payment_tx.inputs[0].sequence, Sequence::MAX);
+    // Multisig dummy, two signatures, branch selector and witness script.
+    assert_eq!(payment_tx.inputs[0].witness.len(), 5);
+    assert_eq!(payment_tx.outputs.len(), 2);
+    assert_eq!(payment_tx.outputs[0].amount, Amount::from_sat_u32(13_000));
+    assert_eq!(
+        channel
+            .settled_amounts(&payment_tx)
+            .expect("payment must settle"),
+        (Amount::from_sat_u32(13_000), Amount::from_sat_u32(26_000))
+    );
+
+    // Refund: the payer reclaims the capacity after the lock time instead.
+    let mut refund_psbt = channel.refund_psbt();
+    refund_psbt.unsigned_tx.outputs.push(TxOut {
+        amount: Amount::from_sat_u32(39_000),
+        script_pubkey: payer_script,
+    });
+    refund_psbt.outputs.push(Default::default());
+    sign_channel_input(&mut refund_psbt, &payer, EcdsaSighashType::All);
+    channel
+        .finalize_refund_tx(&mut refund_psbt)
+        .expect("failed to finalize refund");
+    let refund_tx = refund_psbt.extract_tx_unchecked_fee_rate();
+
+    assert_eq!(refund_tx.inputs.len(), 1);
+    assert_eq!(refund_tx.inputs[0].previous_output, funding_outpoint);
+    assert!(refund_tx.inputs[0].sequence.is_relative_lock_time());
+    // Signature, branch selector and witness script.
+    assert_eq!(refund_tx.inputs[0].witness.len(), 3);
+    assert_eq!(refund_tx.outputs.len(), 1);
+}
@@ -1,4 +1,24 @@
+mod advice;
+mod backup;
+#[cfg(feature = "bitcoinconsensus")]
+mod consensus;
+#[cfg(feature = "encrypt")]
+mod encrypt;
+#[cfg(feature = "base64")]
+mod export;
+mod funding;
+mod lifecycle;
+mod offline;
+mod params;
+mod payment;
 mod refund;
 mod settlement;
 mod setup;
+mod signer;
+mod spend;
+mod splice;
+mod standardness;
+mod stream;
+mod threshold;
 mod wallet;
+mod weight;
@@ -0,0 +1,43 @@
+use bitcoin::{
+    Amount, OutPoint, Sequence, TxIn, Txid, Witness, primitives::relative, script::ScriptBuf,
+};
+use spill::{Channel, ChannelParams, SegwitBackend};
+
+use crate::common::keys::TestKey;
+
+/// Opens a channel against a funding transaction spending a dummy outpoint.
+///
+/// The funding transaction is never broadcast, so it doesn't need to be signed.
+pub fn open_channel(params: &ChannelParams<SegwitBackend>) -> Channel<SegwitBackend> {
+    let mut funding_tx = params.funding_psbt().unsigned_tx;
+    funding_tx.inputs.push(TxIn {
+        previous_output: OutPoint {
+            txid: Txid::from_byte_array([0xab; 32]),
+            vout: 0,
+        },
+        script_sig: ScriptBuf::new(),
+        sequence: Sequence::MAX,
+        witness: Witness::new(),
+    });
+
+    let outpoint = OutPoint {
+        txid: funding_tx.compute_txid(),
+        vout: 0,
+    };
+
+    params
+        .verify_funding_tx(&funding_tx, outpoint)
+        .expect("failed to generate Channel")
+}
+
+/// Default parameters used by node-free tests.
+pub fn test_params(payer: &TestKey, payee: &TestKey) -> ChannelParams<SegwitBackend> {
+    ChannelParams::new(
+        payer.pubkey,
+        payee.pubkey,
+        Amount::from_sat_u32(40_000),
+        relative::LockTime::from_height(10),
+        SegwitBackend::new(),
+    )
+    .expect("failed to create ChannelParams")
+}
@@ -0,0 +1,381 @@
+use std::{str::FromStr, time::Duration};
+
+use bitcoin::{
+    Address, Amount, Network, OutPoint, PublicKey, ScriptPubKeyBuf, TestnetVersion,
+    WitnessScriptBuf,
+    primitives::relative,
+    script::{ScriptPubKeyBufExt, WitnessScriptExt},
+};
+use spill::{ChannelKind, ChannelParams, ConfigError, Role, SegwitBackend, SpillError};
+
+use crate::{common::keys::test_key, segwit::offline::test_params};
+
+fn params_with_arbiter() -> ChannelParams<SegwitBackend> {
+    ChannelParams::new_threshold(
+        test_key(1).pubkey,
+        test_key(2).pubkey,
+        vec![test_key(3).pubkey],
+        2,
+        Amount::from_sat_u32(40_000),
+        relative::LockTime::from_height(10),
+        SegwitBackend::new(),
+    )
+    .expect("failed to create ChannelParams")
+}
+
+#[test]
+fn role_of_each_key() {
+    let params = params_with_arbiter();
+
+    assert_eq!(params.role_of(&test_key(1).pubkey), Some(Role::Payer));
+    assert_eq!(params.role_of(&test_key(2).pubkey), Some(Role::Payee));
+    assert_eq!(params.role_of(&test_key(3).pubkey), Some(Role::Cosigner));
+    assert_eq!(params.role_of(&test_key(4).pubkey), None);
+}
+
+#[test]
+fn validate_proposal_policy_bounds() {
+    let params = params_with_arbiter();
+    let payee = test_key(2).pubkey;
+    let max_lock_time = relative::LockTime::from_height(144);
+
+    let role = params
+        .validate_proposal(&payee, Amount::from_sat_u32(10_000), max_lock_time)
+        .expect("proposal must be accepted");
+    assert_eq!(role, Role::Payee);
+
+    assert!(matches!(
+        params.validate_proposal(
+            &test_key(4).pubkey,
+            Amount::from_sat_u32(10_000),
+            max_lock_time
+        ),
+        Err(SpillError::Config(ConfigError::UnknownPublicKey))
+    ));
+
+    assert!(matches!(
+        params.validate_proposal(&payee, Amount::from_sat_u32(50_000), max_lock_time),
+        Err(SpillError::Config(ConfigError::CapacityBelowMinimum { .. }))
+    ));
+
+    assert!(matches!(
+        params.validate_proposal(
+            &payee,
+            Amount::from_sat_u32(10_000),
+            relative::LockTime::from_height(5)
+        ),
+        Err(SpillError::Config(ConfigError::RefundLockTimeTooLong))
+    ));
+}
+
+#[test]
+fn params_recovered_from_funding_script() {
+    let params = params_with_arbiter();
+    let witness_script = params.funding_psbt().outputs[0]
+        .witness_script
+        .clone()
+        .expect("funding psbt must carry the witness script");
+
+    let recovered =
+        ChannelParams::from_funding_script(&witness_script, Amount::from_sat_u32(40_000))
+            .expect("failed to recover params");
+
+    assert_eq!(recovered.script_pubkey(), params.script_pubkey());
+    assert_eq!(recovered.threshold(), 2);
+    assert_eq!(recovered.role_of(&test_key(3).pubkey), Some(Role::Cosigner));
+
+    let mut bytes = witness_script.to_vec();
+    bytes.push(bytes[bytes.len() - 1]);
+    let tampered = WitnessScriptBuf::from_bytes(bytes);
+
+    assert!(matches!(
+        ChannelParams::from_funding_script(&tampered, Amount::from_sat_u32(40_000)),
+        Err(SpillError::Config(ConfigError::UnrecognizedFundingScript))
+    ));
+}
+
+#[test]
+fn params_from_wire_keys() {
+    let payer = PublicKey::from_slice(&test_key(1).pubkey.to_bytes()).expect("valid payer key");
+    let payee = PublicKey::from_slice(&test_key(2).pubkey.to_bytes()).expect("valid payee key");
+
+    let params = ChannelParams::new(
+        payer,
+        payee,
+        Amount::from_sat_u32(40_000),
+        relative::LockTime::from_height(10),
+        SegwitBackend::new(),
+    )
+    .expect("failed to create ChannelParams");
+
+    let witness_script = params.funding_psbt().outputs[0]
+        .witness_script
+        .clone()
+        .expect("funding psbt must carry the witness script");
+    let recovered =
+        ChannelParams::from_funding_script(&witness_script, Amount::from_sat_u32(40_000))
+            .expect("funding script must be well formed");
+    assert_eq!(recovered.script_pubkey(), params.script_pubkey());
+
+    // Bytes that are not a point on the curve never become a `PublicKey`.
+    let mut invalid = [0xff; 33];
+    invalid[0] = 0x02;
+    assert!(PublicKey::from_slice(&invalid).is_err());
+    assert!(PublicKey::from_slice(&[0x00]).is_err());
+}
+
+#[test]
+fn funding_address_round_trips_on_regtest() {
+    let params = test_params(&test_key(1), &test_key(2));
+
+    let address = params.funding_address(Network::Regtest);
+    assert!(address.to_string().starts_with("bcrt1"));
+    assert_eq!(&address.script_pubkey(), params.script_pubkey());
+
+    let parsed = Address::from_str(&address.to_string())
+        .expect("funding address must parse")
+        .require_network(Network::Regtest)
+        .expect("funding address must be a regtest address");
+    assert_eq!(parsed, address);
+
+    let testnet = params.funding_address(Network::Testnet(TestnetVersion::V4));
+    assert!(testnet.to_string().starts_with("tb1"));
+    assert_eq!(&testnet.script_pubkey(), params.script_pubkey());
+}
+
+#[test]
+fn channel_funded_on_signet() {
+    let params = test_params(&test_key(1), &test_key(2));
+
+    let address = params.funding_address(Network::Signet);
+    assert!(address.to_string().starts_with("tb1q"));
+    assert_eq!(
+        address,
+        params.funding_address(Network::Testnet(TestnetVersion::V4))
+    );
+
+    let parsed = Address::from_str(&address.to_string())
+        .expect("funding address must parse")
+        .require_network(Network::Signet)
+        .expect("funding address must be a signet address");
+
+    let mut funding_tx = params.funding_psbt().unsigned_tx;
+    funding_tx.outputs[0].script_pubkey = parsed.script_pubkey();
+    let outpoint = OutPoint {
+        txid: funding_tx.compute_txid(),
+        vout: 0,
+    };
+    params
+        .verify_funding_tx(&funding_tx, outpoint)
+        .expect("output paying the signet address must fund the channel");
+}
+
+#[test]
+fn segwit_channels_are_csv_p2wsh() {
+    assert_eq!(
+        test_params(&test_key(1), &test_key(2)).kind(),
+        ChannelKind::CsvP2wsh
+    );
+    assert_eq!(params_with_arbiter().kind(), ChannelKind::CsvP2wsh);
+}
+
+#[test]
+fn params_id_is_stable_and_distinguishes_params() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let params = test_params(&payer, &payee);
+
+    assert_eq!(params.params_id(), test_params(&payer, &payee).params_id());
+    assert_ne!(params.params_id(), test_params(&payee, &payer).params_id());
+    assert_ne!(params.params_id(), params_with_arbiter().params_id());
+
+    let larger = ChannelParams::new(
+        payer.pubkey,
+        payee.pubkey,
+        Amount::from_sat_u32(50_000),
+        relative::LockTime::from_height(10),
+        SegwitBackend::new(),
+    )
+    .expect("failed to create ChannelParams");
+    assert_ne!(params.params_id(), larger.params_id());
+
+    let later = ChannelParams::new(
+        payer.pubkey,
+        payee.pubkey,
+        Amount::from_sat_u32(40_000),
+        relative::LockTime::from_height(11),
+        SegwitBackend::new(),
+    )
+    .expect("failed to create ChannelParams");
+    assert_ne!(params.params_id(), later.params_id());
+}
+
+#[test]
+fn near_miss_funding_scripts_are_rejected() {
+    let params = test_params(&test_key(1), &test_key(2));
+    let witness_script = params.funding_psbt().outputs[0]
+        .witness_script
+        .clone()
+        .expect("funding psbt must carry the witness script");
+    let bytes = witness_script.to_vec();
+    let len = bytes.len();
+
+    // OP_IF <2> <payer> <payee> <2> OP_CHECKMULTISIG OP_ELSE <lock time>
+    // OP_CSV OP_DROP <payer> OP_CHECKSIG OP_ENDIF
+    let else_index = 2 + 2 * 34 + 2;
+    let csv_index = len - 38;
+    let tampered: Vec<(&str, Vec<u8>)> = vec![
+        ("OP_NOTIF branch", {
+            let mut b = bytes.clone();
+            b[0] = 0x64;
+            b
+        }),
+        ("threshold above key count", {
+            let mut b = bytes.clone();
+            b[1] = 0x53;
+            b
+        }),
+        ("non-minimal threshold push", {
+            let mut b = bytes.clone();
+            b.splice(1..2, [0x01, 0x02]);
+            b
+        }),
+        ("key count mismatch", {
+            let mut b = bytes.clone();
+            b[else_index - 2] = 0x53;
+            b
+        }),
+        ("OP_CHECKMULTISIGVERIFY", {
+            let mut b = bytes.clone();
+            b[else_index - 1] = 0xaf;
+            b
+        }),
+        ("extra OP_NOP", {
+            let mut b = bytes.clone();
+            b.insert(else_index + 1, 0x61);
+            b
+        }),
+        ("OP_CLTV instead of OP_CSV", {
+            let mut b = bytes.clone();
+            b[csv_index] = 0xb1;
+            b
+        }),
+        ("missing OP_DROP", {
+            let mut b = bytes.clone();
+            b.remove(csv_index + 1);
+            b
+        }),
+        ("OP_CHECKSIGVERIFY", {
+            let mut b = bytes.clone();
+            b[len - 2] = 0xad;
+            b
+        }),
+        ("missing OP_ENDIF", bytes[..len - 1].to_vec()),
+    ];
+
+    for (name, bytes) in tampered {
+        let script = WitnessScriptBuf::from_bytes(bytes);
+        assert!(
+            matches!(
+                ChannelParams::from_funding_script(&script, Amount::from_sat_u32(40_000)),
+                Err(SpillError::Config(ConfigError::UnrecognizedFundingScript))
+            ),
+            "{name} must be rejected"
+        );
+    }
+}
+
+#[test]
+fn witness_script_hash_matches_funding_output() {
+    let params = params_with_arbiter();
+    let witness_script = params.funding_psbt().outputs[0]
+        .witness_script
+        .clone()
+        .expect("funding psbt must carry the witness script");
+
+    let hash = params.witness_script_hash();
+    assert_eq!(
+        Some(hash),
+        witness_script.wscript_hash().ok(),
+        "hash must commit to the funding witness script"
+    );
+    assert_eq!(&ScriptPubKeyBuf::new_p2wsh(hash), params.script_pubkey());
+}
+
+#[test]
+fn time_refund_rounds_up_to_512_second_intervals() {
+    let payer = test_key(1).pubkey;
+    let payee = test_key(2).pubkey;
+    let capacity = Amount::from_sat_u32(40_000);
+    let time_refund = |delay| {
+        ChannelParams::with_time_refund(payer, payee, capacity, delay, SegwitBackend::new())
+    };
+    let intervals = |n| {
+        ChannelParams::new(
+            payer,
+            payee,
+            capacity,
+            relative::LockTime::from_512_second_intervals(n),
+            SegwitBackend::new(),
+        )
+        .expect("failed to create ChannelParams")
+    };
+
+    for (delay, expected) in [
+        (Duration::from_nanos(1), 1),
+        (Duration::from_secs(512), 1),
+        (Duration::from_millis(512_001), 2),
+        (Duration::from_secs(513), 2),
+        (Duration::from_secs(24 * 60 * 60), 169),
+        (Duration::from_secs(512 * u64::from(u16::MAX)), u16::MAX),
+    ] {
+        let params = time_refund(delay).expect("delay must be representable");
+        assert_eq!(
+            params.script_pubkey(),
+            intervals(expected).script_pubkey(),
+            "{delay:?} must round up to {expected} intervals"
+        );
+    }
+
+    for delay in [
+        Duration::from_secs(512 * u64::from(u16::MAX) + 1),
+        Duration::from_secs(u64::from(u32::MAX) + 1),
+        Duration::MAX,
+    ] {
+        assert!(matches!(
+            time_refund(delay),
+            Err(SpillError::Config(ConfigError::RefundLockTimeOverflow))
+        ));
+    }
+}
+
+#[test]
+fn new_sats_matches_amount_constructor() {
+    let payer = test_key(1).pubkey;
+    let payee = test_key(2).pubkey;
+    let lock_time = relative::LockTime::from_height(10);
+
+    let from_sats = ChannelParams::new_sats(payer, payee, 40_000, lock_time, SegwitBackend::new())
+        .expect("failed to create ChannelParams");
+    let from_amount = ChannelParams::new(
+        payer,
+        payee,
+        Amount::from_sat_u32(40_000),
+        lock_time,
+        SegwitBackend::new(),
+    )
+    .expect("failed to create ChannelParams");
+    assert_eq!(from_sats.params_id(), from_amount.params_id());
+    assert_eq!(from_sats.funding_psbt(), from_amount.funding_psbt());
+
+    assert!(matches!(
+        ChannelParams::new_sats(
+            payer,
+            payee,
+            Amount::MAX.to_sat() + 1,
+            lock_time,
+            SegwitBackend::new()
+        ),
+        Err(SpillError::Config(ConfigError::CapacityOutOfRange))
+    ));
+}
@@ -0,0 +1,1352 @@
+use bitcoin::{
+    Amount, EcdsaSighashType, Sequence, TxOut, WitnessProgram, WitnessVersion, absolute,
+    amount::Denomination,
+    primitives::relative,
+    psbt::PsbtSighashType,
+    script::{ScriptBuf, ScriptPubKeyBufExt, ScriptPubKeyExt},
+    transaction,
+};
+use spill::{
+    ChannelParams, ChannelPolicy, CloseError, ConfigError, FinalizeError, PaymentError,
+    SegwitBackend, SigningResponse, SpillError, describe_payment_psbt, funding_outpoint_of,
+};
+
+use crate::{
+    common::keys::{make_signature_high_s, sign_channel_input, test_key},
+    segwit::offline::{open_channel, test_params},
+};
+
+#[test]
+fn version_one_payment_is_rejected() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let channel = open_channel(&test_params(&payer, &payee));
+
+    let mut psbt = channel
+        .next_payment(Amount::from_sat_u32(10_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    psbt.unsigned_tx.version = transaction::Version::ONE;
+    sign_channel_input(&mut psbt, &payer, EcdsaSighashType::All);
+
+    assert!(matches!(
+        channel.verify_payment_psbt(&psbt),
+        Err(SpillError::Payment(PaymentError::InvalidVersion))
+    ));
+}
+
+#[test]
+fn structural_verification_skips_signatures() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let channel = open_channel(&test_params(&payer, &payee));
+
+    let psbt = channel
+        .next_payment(Amount::from_sat_u32(10_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+
+    let info = channel
+        .verify_payment_psbt_structural(&psbt)
+        .expect("unsigned payment must be structurally valid");
+    assert_eq!(info.total, Amount::from_sat_u32(10_000));
+    assert_eq!(info.payer_change, Amount::from_sat_u32(29_000));
+
+    assert!(matches!(
+        channel.verify_payment_psbt(&psbt),
+        Err(SpillError::Payment(PaymentError::MissingSignature))
+    ));
+}
+
+#[test]
+fn own_payment_is_verified_before_signing() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let channel = open_channel(&test_params(&payer, &payee));
+
+    let mut psbt = channel
+        .next_payment(Amount::from_sat_u32(10_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+
+    let info = channel
+        .verify_own_payment(&psbt)
+        .expect("payer-built payment must be valid");
+    assert_eq!(info.current, Amount::from_sat_u32(10_000));
+    assert_eq!(info.fee, Amount::from_sat_u32(1_000));
+
+    psbt.unsigned_tx.version = transaction::Version::ONE;
+    assert!(matches!(
+        channel.verify_own_payment(&psbt),
+        Err(SpillError::Payment(PaymentError::InvalidVersion))
+    ));
+}
+
+#[test]
+fn payment_must_reduce_payer_change() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let mut channel = open_channel(&test_params(&payer, &payee));
+
+    let mut psbt = channel
+        .next_payment(Amount::from_sat_u32(10_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    sign_channel_input(&mut psbt, &payer, EcdsaSighashType::All);
+    channel
+        .apply_payment(&psbt)
+        .expect("failed to apply payment");
+
+    // Payee gains 1_000 but the fee drops by 1_000, leaving the change untouched.
+    let mut psbt = channel
+        .next_payment(Amount::from_sat_u32(1_000), Amount::ZERO)
+        .expect("failed to build payment");
+    sign_channel_input(&mut psbt, &payer, EcdsaSighashType::All);
+
+    assert!(matches!(
+        channel.verify_payment_psbt(&psbt),
+        Err(SpillError::Payment(PaymentError::InconsistentChange))
+    ));
+}
+
+#[test]
+fn third_party_outputs_are_not_payer_change() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let mut channel = open_channel(&test_params(&payer, &payee));
+
+    let mut psbt = channel
+        .next_payment(Amount::from_sat_u32(10_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    sign_channel_input(&mut psbt, &payer, EcdsaSighashType::All);
+    channel
+        .apply_payment(&psbt)
+        .expect("failed to apply payment");
+
+    // A fee bump must not move the change to another output.
+    let mut diverted = psbt.clone();
+    diverted.inputs[0].partial_sigs.clear();
+    diverted.unsigned_tx.outputs[1].amount = Amount::from_sat_u32(28_000);
+    diverted.unsigned_tx.outputs.push(TxOut {
+        amount: Amount::from_sat_u32(1_000),
+        script_pubkey: ScriptBuf::new_p2a(),
+    });
+    diverted.outputs.push(Default::default());
+    sign_channel_input(&mut diverted, &payer, EcdsaSighashType::All);
+    assert!(matches!(
+        channel.verify_fee_bump_psbt(&diverted),
+        Err(SpillError::Payment(PaymentError::FeeNotIncreased { .. }))
+    ));
+
+    let mut psbt = channel
+        .next_payment(Amount::from_sat_u32(1_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    psbt.unsigned_tx.outputs[1].amount = Amount::from_sat_u32(27_000);
+    psbt.unsigned_tx.outputs.push(TxOut {
+        amount: Amount::from_sat_u32(1_000),
+        script_pubkey: ScriptBuf::new_p2a(),
+    });
+    psbt.outputs.push(Default::default());
+    sign_channel_input(&mut psbt, &payer, EcdsaSighashType::All);
+
+    let info = channel
+        .verify_payment_psbt(&psbt)
+        .expect("failed to verify payment");
+    assert_eq!(info.total, Amount::from_sat_u32(11_000));
+    assert_eq!(info.fee, Amount::from_sat_u32(1_000));
+    assert_eq!(info.payer_change, Amount::from_sat_u32(27_000));
+}
+
+#[test]
+fn payment_info_reports_payer_change() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let mut channel = open_channel(&test_params(&payer, &payee));
+
+    let mut psbt = channel
+        .next_payment(Amount::from_sat_u32(10_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    sign_channel_input(&mut psbt, &payer, EcdsaSighashType::All);
+    let info = channel
+        .verify_payment_psbt(&psbt)
+        .expect("failed to verify payment");
+    assert_eq!(info.payer_change, Amount::from_sat_u32(29_000));
+    assert_eq!(info.payer_change, psbt.unsigned_tx.outputs[1].amount);
+    channel
+        .apply_payment(&psbt)
+        .expect("failed to apply payment");
+
+    // A higher fee comes out of the payer's change, not the payee's total.
+    let mut psbt = channel
+        .next_payment(Amount::from_sat_u32(5_000), Amount::from_sat_u32(2_000))
+        .expect("failed to build payment");
+    sign_channel_input(&mut psbt, &payer, EcdsaSighashType::All);
+    let info = channel
+        .verify_payment_psbt(&psbt)
+        .expect("failed to verify payment");
+    assert_eq!(info.total, Amount::from_sat_u32(15_000));
+    assert_eq!(info.payer_change, Amount::from_sat_u32(23_000));
+    channel
+        .apply_payment(&psbt)
+        .expect("failed to apply payment");
+
+    // A dust change is dropped and paid as fee.
+    let mut psbt = channel
+        .next_payment(Amount::from_sat_u32(22_900), Amount::from_sat_u32(2_000))
+        .expect("failed to build payment");
+    assert_eq!(psbt.unsigned_tx.outputs.len(), 1);
+    assert!(channel.is_canonical_payment(&psbt));
+    sign_channel_input(&mut psbt, &payer, EcdsaSighashType::All);
+    let info = channel
+        .verify_payment_psbt(&psbt)
+        .expect("failed to verify payment");
+    assert_eq!(info.total, Amount::from_sat_u32(37_900));
+    assert_eq!(info.fee, Amount::from_sat_u32(2_100));
+    assert_eq!(info.payer_change, Amount::ZERO);
+}
+
+#[test]
+fn channel_is_exhausted_once_no_payment_fits() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let mut channel = open_channel(&test_params(&payer, &payee));
+    let min_fee = Amount::from_sat_u32(1_000);
+
+    assert!(!channel.is_exhausted(min_fee));
+
+    let mut psbt = channel
+        .next_payment(Amount::from_sat_u32(38_800), min_fee)
+        .expect("failed to build payment");
+    sign_channel_input(&mut psbt, &payer, EcdsaSighashType::All);
+    channel
+        .apply_payment(&psbt)
+        .expect("failed to apply payment");
+
+    assert!(channel.is_exhausted(min_fee));
+    assert!(!channel.is_exhausted(Amount::ZERO));
+}
+
+#[test]
+fn payee_signature_is_verified_independently() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let channel = open_channel(&test_params(&payer, &payee));
+
+    let mut psbt = channel
+        .next_payment(Amount::from_sat_u32(10_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+
+    assert!(matches!(
+        channel.verify_payee_signature(&psbt),
+        Err(SpillError::Payment(PaymentError::MissingSignature))
+    ));
+
+    sign_channel_input(&mut psbt, &payee, EcdsaSighashType::All);
+    channel
+        .verify_payee_signature(&psbt)
+        .expect("payee signature must verify");
+}
+
+#[test]
+fn payment_verification_checks_payee_signature_when_present() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let other = test_key(3);
+    let channel = open_channel(&test_params(&payer, &payee));
+
+    let mut psbt = channel
+        .next_payment(Amount::from_sat_u32(10_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    sign_channel_input(&mut psbt, &payer, EcdsaSighashType::All);
+
+    // Absent payee signature.
+    channel
+        .verify_payment_psbt(&psbt)
+        .expect("payment without payee signature must verify");
+
+    // Present and valid payee signature.
+    let mut signed = psbt.clone();
+    sign_channel_input(&mut signed, &payee, EcdsaSighashType::All);
+    channel
+        .verify_payment_psbt(&signed)
+        .expect("payment with valid payee signature must verify");
+
+    // Present but invalid payee signature.
+    let mut forged = psbt.clone();
+    sign_channel_input(&mut forged, &other, EcdsaSighashType::All);
+    let sig = forged.inputs[0]
+        .partial_sigs
+        .remove(&other.pubkey)
+        .expect("missing signature");
+    forged.inputs[0].partial_sigs.insert(payee.pubkey, sig);
+
+    assert!(matches!(
+        channel.verify_payment_psbt(&forged),
+        Err(SpillError::Payment(PaymentError::InvalidSignature))
+    ));
+}
+
+#[test]
+fn policy_enforces_payee_index_and_output_count() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let mut policy = ChannelPolicy::default();
+    policy.expected_payee_index = Some(1);
+    policy.max_outputs = Some(2);
+    let channel = open_channel(&test_params(&payer, &payee).with_policy(policy));
+
+    let mut psbt = channel
+        .next_payment(Amount::from_sat_u32(10_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    sign_channel_input(&mut psbt, &payer, EcdsaSighashType::All);
+
+    assert!(matches!(
+        channel.verify_payment_psbt(&psbt),
+        Err(SpillError::Payment(PaymentError::PayeeOutputWrongIndex {
+            expected: 1
+        }))
+    ));
+
+    let mut extra = psbt.clone();
+    extra
+        .unsigned_tx
+        .outputs
+        .push(extra.unsigned_tx.outputs[1].clone());
+    extra.outputs.push(Default::default());
+
+    assert!(matches!(
+        channel.verify_payment_psbt(&extra),
+        Err(SpillError::Payment(PaymentError::TooManyOutputs {
+            count: 3,
+            max: 2
+        }))
+    ));
+}
+
+#[test]
+fn claim_transaction_requires_payee_signature() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let channel = open_channel(&test_params(&payer, &payee));
+
+    let mut psbt = channel
+        .next_payment(Amount::from_sat_u32(10_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    sign_channel_input(&mut psbt, &payer, EcdsaSighashType::All);
+
+    assert!(matches!(
+        channel.claim_transaction(&psbt),
+        Err(SpillError::Finalize(FinalizeError::MissingSignature { public_key }))
+            if public_key == payee.pubkey
+    ));
+
+    sign_channel_input(&mut psbt, &payee, EcdsaSighashType::All);
+    let tx = channel
+        .claim_transaction(&psbt)
+        .expect("failed to build claim transaction");
+
+    assert_eq!(tx.compute_txid(), psbt.unsigned_tx.compute_txid());
+    assert_eq!(tx.inputs[0].witness.len(), 5);
+}
+
+#[test]
+fn strict_policy_rejects_anyonecanpay() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+
+    let channel = open_channel(&test_params(&payer, &payee));
+
+    let mut psbt = channel
+        .next_payment(Amount::from_sat_u32(10_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    sign_channel_input(&mut psbt, &payer, EcdsaSighashType::AllPlusAnyoneCanPay);
+
+    channel
+        .verify_payment_psbt(&psbt)
+        .expect("ANYONECANPAY must be accepted by default");
+
+    let mut policy = ChannelPolicy::default();
+    policy.strict_sighash_all = true;
+    let channel = open_channel(&test_params(&payer, &payee).with_policy(policy));
+
+    assert!(matches!(
+        channel.verify_payment_psbt(&psbt),
+        Err(SpillError::Payment(PaymentError::InvalidSighash))
+    ));
+}
+
+#[test]
+fn cooperative_close_adds_anchor_output() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let mut channel = open_channel(&test_params(&payer, &payee));
+
+    assert!(matches!(
+        channel.cooperative_close_with_anchor(Amount::from_sat_u32(240), None),
+        Err(SpillError::Close(CloseError::NothingSent))
+    ));
+
+    let mut psbt = channel
+        .next_payment(Amount::from_sat_u32(10_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    sign_channel_input(&mut psbt, &payer, EcdsaSighashType::All);
+    channel
+        .apply_payment(&psbt)
+        .expect("failed to apply payment");
+
+    let close = channel
+        .cooperative_close_with_anchor(Amount::from_sat_u32(240), None)
+        .expect("failed to build close");
+    let outputs = &close.unsigned_tx.outputs;
+
+    assert_eq!(close.unsigned_tx.version, transaction::Version::THREE);
+    assert_eq!(outputs.len(), 3);
+    assert_eq!(close.outputs.len(), 3);
+    assert_eq!(outputs[0].amount, Amount::from_sat_u32(10_000));
+    assert_eq!(outputs[1].amount, Amount::from_sat_u32(29_760));
+    assert_eq!(outputs[2].amount, Amount::from_sat_u32(240));
+    assert!(outputs[2].script_pubkey.is_p2a());
+
+    let close = channel
+        .cooperative_close_with_anchor(Amount::from_sat_u32(240), Some(b"invoice-42".to_vec()))
+        .expect("failed to build close");
+    let outputs = &close.unsigned_tx.outputs;
+    assert_eq!(outputs.len(), 4);
+    assert!(outputs[3].script_pubkey.is_op_return());
+    assert_eq!(outputs[3].amount, Amount::ZERO);
+
+    assert!(matches!(
+        channel.cooperative_close_with_anchor(Amount::from_sat_u32(240), Some(vec![0; 81])),
+        Err(SpillError::Close(CloseError::OpReturnTooLarge { .. }))
+    ));
+}
+
+#[test]
+fn finalize_rejects_non_all_sighash() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let channel = open_channel(&test_params(&payer, &payee));
+
+    let mut psbt = channel
+        .next_payment(Amount::from_sat_u32(10_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    sign_channel_input(&mut psbt, &payer, EcdsaSighashType::All);
+    sign_channel_input(&mut psbt, &payee, EcdsaSighashType::Single);
+
+    assert!(matches!(
+        channel.finalize_payment_tx(&mut psbt),
+        Err(SpillError::Finalize(FinalizeError::InconsistentSighash { public_key }))
+            if public_key == payee.pubkey
+    ));
+}
+
+#[test]
+fn constant_time_verification_matches_fast_fail() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let channel = open_channel(&test_params(&payer, &payee));
+
+    let mut psbt = channel
+        .next_payment(Amount::from_sat_u32(10_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+
+    assert!(matches!(
+        channel.verify_payment_psbt_ct(&psbt),
+        Err(SpillError::Payment(PaymentError::MissingSignature))
+    ));
+
+    sign_channel_input(&mut psbt, &payer, EcdsaSighashType::All);
+    let info = channel
+        .verify_payment_psbt_ct(&psbt)
+        .expect("signed payment must verify");
+    assert_eq!(info.total, Amount::from_sat_u32(10_000));
+
+    psbt.unsigned_tx.version = transaction::Version::ONE;
+    assert!(matches!(
+        channel.verify_payment_psbt_ct(&psbt),
+        Err(SpillError::Payment(PaymentError::InvalidVersion))
+    ));
+}
+
+#[test]
+fn dust_payee_output_is_rejected() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let channel = open_channel(&test_params(&payer, &payee));
+
+    let mut psbt = channel
+        .next_payment(Amount::from_sat_u32(1), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    sign_channel_input(&mut psbt, &payer, EcdsaSighashType::All);
+
+    assert!(matches!(
+        channel.verify_payment_psbt(&psbt),
+        Err(SpillError::Payment(PaymentError::PayeeOutputDust { amount, .. }))
+            if amount == Amount::from_sat_u32(1)
+    ));
+
+    let mut policy = ChannelPolicy::default();
+    policy.dust_limit = Some(Amount::ZERO);
+    let channel = open_channel(&test_params(&payer, &payee).with_policy(policy));
+
+    channel
+        .verify_payment_psbt(&psbt)
+        .expect("payment must verify with a zero dust limit");
+}
+
+#[test]
+fn altered_refund_lock_time_is_reported() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let channel = open_channel(&test_params(&payer, &payee));
+
+    let altered = ChannelParams::new(
+        payer.pubkey,
+        payee.pubkey,
+        Amount::from_sat_u32(40_000),
+        relative::LockTime::from_height(20),
+        SegwitBackend::new(),
+    )
+    .expect("failed to create ChannelParams");
+    let altered_witness_script = altered.funding_psbt().outputs[0].witness_script.clone();
+
+    let mut psbt = channel
+        .next_payment(Amount::from_sat_u32(10_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    psbt.inputs[0].witness_script = altered_witness_script;
+    psbt.inputs[0]
+        .witness_utxo
+        .as_mut()
+        .expect("witness utxo must be set")
+        .script_pubkey = altered.script_pubkey().clone();
+
+    assert!(matches!(
+        channel.verify_payment_psbt(&psbt),
+        Err(SpillError::Payment(PaymentError::LocktimeMismatch { expected, found }))
+            if expected == relative::LockTime::from_height(10)
+                && found == relative::LockTime::from_height(20)
+    ));
+}
+
+#[test]
+fn funding_outpoint_of_routes_psbts_to_their_channel() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let params = test_params(&payer, &payee);
+    let channel = open_channel(&params);
+
+    let psbt = channel
+        .next_payment(Amount::from_sat_u32(10_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    assert_eq!(funding_outpoint_of(&psbt), Some(channel.id().outpoint()));
+    assert_eq!(
+        funding_outpoint_of(&channel.refund_psbt()),
+        Some(channel.id().outpoint())
+    );
+
+    // Nothing is verified: any outpoint spent by the first input is returned.
+    let mut foreign = psbt.clone();
+    foreign.unsigned_tx.inputs[0].previous_output.vout += 1;
+    assert_ne!(funding_outpoint_of(&foreign), Some(channel.id().outpoint()));
+
+    assert_eq!(funding_outpoint_of(&params.funding_psbt()), None);
+}
+
+#[test]
+fn describe_payment_psbt_lists_fields() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let channel = open_channel(&test_params(&payer, &payee));
+
+    let mut psbt = channel
+        .next_payment(Amount::from_sat_u32(10_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    sign_channel_input(&mut psbt, &payer, EcdsaSighashType::AllPlusAnyoneCanPay);
+
+    let summary = describe_payment_psbt(&psbt);
+    assert_eq!(summary.outpoint, funding_outpoint_of(&psbt));
+    assert_eq!(summary.outputs, psbt.unsigned_tx.outputs);
+    assert_eq!(
+        summary.signatures,
+        vec![(payer.pubkey, EcdsaSighashType::AllPlusAnyoneCanPay)]
+    );
+    assert!(summary.to_string().contains(&payer.pubkey.to_string()));
+}
+
+#[test]
+fn payment_chain_must_be_increasing() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let channel = open_channel(&test_params(&payer, &payee));
+
+    let mut first = channel
+        .next_payment(Amount::from_sat_u32(10_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    sign_channel_input(&mut first, &payer, EcdsaSighashType::All);
+    let mut second = channel
+        .next_payment(Amount::from_sat_u32(15_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    sign_channel_input(&mut second, &payer, EcdsaSighashType::All);
+
+    let payments = channel
+        .verify_payment_chain(&[first.clone(), second.clone()])
+        .expect("chain must be valid");
+    assert_eq!(payments.len(), 2);
+    assert_eq!(payments[0].current, Amount::from_sat_u32(10_000));
+    assert_eq!(payments[1].current, Amount::from_sat_u32(5_000));
+
+    assert!(matches!(
+        channel.verify_payment_chain(&[second.clone(), first.clone()]),
+        Err(SpillError::Payment(PaymentError::ChainOutOfOrder {
+            index: 1
+        }))
+    ));
+
+    second.inputs[0].partial_sigs.clear();
+    assert!(matches!(
+        channel.verify_payment_chain(&[first, second]),
+        Err(SpillError::Payment(PaymentError::ChainInvalidPayment { index: 1, error }))
+            if matches!(*error, SpillError::Payment(PaymentError::MissingSignature))
+    ));
+}
+
+#[test]
+fn next_payment_to_total_sets_cumulative_amount() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let mut channel = open_channel(&test_params(&payer, &payee));
+
+    let mut psbt = channel
+        .next_payment_to_total(Amount::from_sat_u32(10_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    sign_channel_input(&mut psbt, &payer, EcdsaSighashType::All);
+    channel
+        .apply_payment(&psbt)
+        .expect("failed to apply payment");
+
+    let psbt = channel
+        .next_payment_to_total(Amount::from_sat_u32(12_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    let expected = channel
+        .next_payment(Amount::from_sat_u32(2_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    assert_eq!(psbt.unsigned_tx, expected.unsigned_tx);
+
+    assert!(matches!(
+        channel.next_payment_to_total(Amount::from_sat_u32(10_000), Amount::from_sat_u32(1_000)),
+        Err(SpillError::Payment(PaymentError::PaymentNotIncremental))
+    ));
+}
+
+#[test]
+fn finalize_rejects_high_s_signature() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let channel = open_channel(&test_params(&payer, &payee));
+
+    let mut psbt = channel
+        .next_payment(Amount::from_sat_u32(10_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    sign_channel_input(&mut psbt, &payer, EcdsaSighashType::All);
+    sign_channel_input(&mut psbt, &payee, EcdsaSighashType::All);
+
+    make_signature_high_s(&mut psbt, &payee);
+
+    assert!(matches!(
+        channel.finalize_payment_tx(&mut psbt),
+        Err(SpillError::Finalize(FinalizeError::NonCanonicalSignature { public_key }))
+            if public_key == payee.pubkey
+    ));
+}
+
+#[test]
+fn finalize_refund_rejects_high_s_signature() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let channel = open_channel(&test_params(&payer, &payee));
+
+    let mut refund = channel.refund_psbt();
+    sign_channel_input(&mut refund, &payer, EcdsaSighashType::All);
+    make_signature_high_s(&mut refund, &payer);
+
+    assert!(matches!(
+        channel.finalize_refund_tx(&mut refund),
+        Err(SpillError::Finalize(FinalizeError::NonCanonicalSignature { public_key }))
+            if public_key == payer.pubkey
+    ));
+}
+
+#[test]
+fn finalize_refund_rejects_sighash_outside_all_family() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let channel = open_channel(&test_params(&payer, &payee));
+
+    let mut refund = channel.refund_psbt();
+    sign_channel_input(&mut refund, &payer, EcdsaSighashType::None);
+
+    assert!(matches!(
+        channel.finalize_refund_tx(&mut refund),
+        Err(SpillError::Finalize(FinalizeError::InconsistentSighash { public_key }))
+            if public_key == payer.pubkey
+    ));
+}
+
+#[test]
+fn finalize_rejects_undefined_recorded_sighash() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let channel = open_channel(&test_params(&payer, &payee));
+
+    let mut refund = channel.refund_psbt();
+    sign_channel_input(&mut refund, &payer, EcdsaSighashType::All);
+    refund.inputs[0].sighash_type = Some(PsbtSighashType::from_u32(0x04));
+    assert!(matches!(
+        channel.finalize_refund_tx(&mut refund),
+        Err(SpillError::Finalize(FinalizeError::UndefinedSighashType {
+            sighash_type: 0x04
+        }))
+    ));
+
+    let mut psbt = channel
+        .next_payment(Amount::from_sat_u32(10_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    sign_channel_input(&mut psbt, &payer, EcdsaSighashType::All);
+    sign_channel_input(&mut psbt, &payee, EcdsaSighashType::All);
+    psbt.inputs[0].sighash_type = Some(PsbtSighashType::from_u32(0x84));
+    assert!(matches!(
+        channel.finalize_payment_tx(&mut psbt),
+        Err(SpillError::Finalize(FinalizeError::UndefinedSighashType {
+            sighash_type: 0x84
+        }))
+    ));
+    assert!(psbt.inputs[0].final_script_witness.is_none());
+}
+
+#[test]
+fn signing_response_rejects_malformed_signature_encoding() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let channel = open_channel(&test_params(&payer, &payee));
+
+    let mut psbt = channel
+        .next_payment(Amount::from_sat_u32(10_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    sign_channel_input(&mut psbt, &payer, EcdsaSighashType::All);
+    let signature = psbt.inputs[0]
+        .partial_sigs
+        .remove(&payer.pubkey)
+        .expect("payer signature must be present");
+    let response = SigningResponse::new(payer.pubkey, signature);
+
+    // The sighash byte 0x84 is not a defined sighash type.
+    let mut undefined = response.clone();
+    *undefined
+        .signature
+        .last_mut()
+        .expect("signature must not be empty") = 0x84;
+    assert!(matches!(
+        channel.apply_signing_response(&mut psbt, &undefined),
+        Err(SpillError::Payment(PaymentError::InvalidSignature))
+    ));
+
+    // Padding R with a zero byte keeps its value but breaks strict DER.
+    let der = &response.signature[..response.signature.len() - 1];
+    let r_len = usize::from(der[3]);
+    let mut padded = vec![0x30, der[1] + 1, 0x02, der[3] + 1, 0x00];
+    padded.extend_from_slice(&der[4..]);
+    padded.push(EcdsaSighashType::All as u8);
+    let mut non_canonical = response.clone();
+    non_canonical.signature = padded;
+    assert_eq!(non_canonical.signature.len(), response.signature.len() + 1);
+    assert_eq!(&non_canonical.signature[5..5 + r_len], &der[4..4 + r_len]);
+    assert!(matches!(
+        channel.apply_signing_response(&mut psbt, &non_canonical),
+        Err(SpillError::Payment(PaymentError::InvalidSignature))
+    ));
+    assert!(psbt.inputs[0].partial_sigs.is_empty());
+
+    channel
+        .apply_signing_response(&mut psbt, &response)
+        .expect("well-formed response must apply");
+}
+
+#[test]
+fn payment_to_p2tr_payee_script() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let program = WitnessProgram::new(WitnessVersion::V1, &[0x42; 32])
+        .expect("32-byte v1 program must be valid");
+    let p2tr = ScriptBuf::new_witness_program(&program);
+
+    let params = test_params(&payer, &payee)
+        .with_payee_script(p2tr.clone())
+        .expect("P2TR payee script must be accepted");
+    let mut channel = open_channel(&params);
+
+    let mut psbt = channel
+        .next_payment(Amount::from_sat_u32(10_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    assert_eq!(psbt.unsigned_tx.outputs[0].script_pubkey, p2tr);
+    sign_channel_input(&mut psbt, &payer, EcdsaSighashType::All);
+    channel
+        .apply_payment(&psbt)
+        .expect("payment to the P2TR payee script must verify");
+
+    // A payment built for the default P2WPKH payee script no longer verifies.
+    let default_channel = open_channel(&test_params(&payer, &payee));
+    let mut psbt = default_channel
+        .next_payment(Amount::from_sat_u32(15_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    sign_channel_input(&mut psbt, &payer, EcdsaSighashType::All);
+    assert!(matches!(
+        channel.verify_payment_psbt(&psbt),
+        Err(SpillError::Payment(PaymentError::MissingPayeeOutput))
+    ));
+
+    assert!(matches!(
+        test_params(&payer, &payee).with_payee_script(ScriptBuf::new_p2a()),
+        Err(SpillError::Config(ConfigError::UnsupportedPayeeScript))
+    ));
+}
+
+#[test]
+fn payment_verifies_against_supplied_previous_total() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let channel = open_channel(&test_params(&payer, &payee));
+
+    let mut psbt = channel
+        .next_payment_to_total(Amount::from_sat_u32(15_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    sign_channel_input(&mut psbt, &payer, EcdsaSighashType::All);
+
+    // The stored state of a previous payment of 10_000 at a 1_000 fee.
+    let info = channel
+        .verify_payment_psbt_against(
+            &psbt,
+            Amount::from_sat_u32(10_000),
+            Amount::from_sat_u32(29_000),
+        )
+        .expect("payment must increase the stored total");
+    assert_eq!(info.total, Amount::from_sat_u32(15_000));
+    assert_eq!(info.current, Amount::from_sat_u32(5_000));
+
+    assert!(matches!(
+        channel.verify_payment_psbt_against(
+            &psbt,
+            Amount::from_sat_u32(15_000),
+            Amount::from_sat_u32(29_000),
+        ),
+        Err(SpillError::Payment(PaymentError::PaymentNotIncremental))
+    ));
+
+    // The change is checked against the supplied change, not the channel's.
+    assert!(matches!(
+        channel.verify_payment_psbt_against(
+            &psbt,
+            Amount::from_sat_u32(10_000),
+            Amount::from_sat_u32(24_000),
+        ),
+        Err(SpillError::Payment(PaymentError::InconsistentChange))
+    ));
+}
+
+#[test]
+fn recorded_sighash_type_is_honored() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let channel = open_channel(&test_params(&payer, &payee));
+
+    let psbt = channel
+        .next_payment_with_sighash(
+            Amount::from_sat_u32(10_000),
+            Amount::from_sat_u32(1_000),
+            EcdsaSighashType::AllPlusAnyoneCanPay,
+        )
+        .expect("failed to build payment");
+    assert_eq!(
+        psbt.inputs[0].sighash_type,
+        Some(EcdsaSighashType::AllPlusAnyoneCanPay.into())
+    );
+
+    let mut signed = psbt.clone();
+    sign_channel_input(&mut signed, &payer, EcdsaSighashType::AllPlusAnyoneCanPay);
+    channel
+        .verify_payment_psbt(&signed)
+        .expect("signature must match the recorded sighash type");
+
+    let mut signed = psbt;
+    sign_channel_input(&mut signed, &payer, EcdsaSighashType::All);
+    assert!(matches!(
+        channel.verify_payment_psbt(&signed),
+        Err(SpillError::Payment(PaymentError::InvalidSighash))
+    ));
+
+    assert!(matches!(
+        channel.next_payment_with_sighash(
+            Amount::from_sat_u32(10_000),
+            Amount::from_sat_u32(1_000),
+            EcdsaSighashType::Single,
+        ),
+        Err(SpillError::Payment(PaymentError::InvalidSighash))
+    ));
+}
+
+#[test]
+fn payment_info_display_in_denomination() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let channel = open_channel(&test_params(&payer, &payee));
+
+    let psbt = channel
+        .next_payment(Amount::from_sat_u32(10_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    let info = channel
+        .verify_payment_psbt_structural(&psbt)
+        .expect("unsigned payment must be structurally valid");
+
+    assert_eq!(
+        info.display(Denomination::Bitcoin),
+        "Paid 0.0001 BTC (total 0.0001 BTC), fee 0.00001 BTC, change 0.00029 BTC"
+    );
+    assert_eq!(
+        info.display(Denomination::Satoshi),
+        "Paid 10000 satoshi (total 10000 satoshi), fee 1000 satoshi, change 29000 satoshi"
+    );
+}
+
+#[test]
+fn timelocked_payments_require_policy_opt_in() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let lock_time = absolute::LockTime::from_height(1_000).expect("valid lock height");
+
+    let strict = open_channel(&test_params(&payer, &payee));
+    assert!(matches!(
+        strict.next_payment_with_lock_time(
+            Amount::from_sat_u32(10_000),
+            Amount::from_sat_u32(1_000),
+            lock_time
+        ),
+        Err(SpillError::Payment(PaymentError::NonZeroLockTime))
+    ));
+
+    let mut policy = ChannelPolicy::default();
+    policy.allow_timelocked_payments = true;
+    let channel = open_channel(&test_params(&payer, &payee).with_policy(policy));
+
+    let mut psbt = channel
+        .next_payment_with_lock_time(
+            Amount::from_sat_u32(10_000),
+            Amount::from_sat_u32(1_000),
+            lock_time,
+        )
+        .expect("failed to build timelocked payment");
+    assert_eq!(psbt.unsigned_tx.lock_time, lock_time);
+    assert_eq!(
+        psbt.unsigned_tx.inputs[0].sequence,
+        Sequence::ENABLE_LOCKTIME_NO_RBF
+    );
+    sign_channel_input(&mut psbt, &payer, EcdsaSighashType::All);
+
+    let info = channel
+        .verify_payment_psbt(&psbt)
+        .expect("timelocked payment must be accepted when allowed");
+    assert_eq!(info.total, Amount::from_sat_u32(10_000));
+
+    assert!(matches!(
+        strict.verify_payment_psbt(&psbt),
+        Err(SpillError::Payment(PaymentError::InvalidSequence))
+    ));
+
+    // A lock time without the sequence enabling it is never enforced.
+    psbt.unsigned_tx.inputs[0].sequence = Sequence::MAX;
+    assert!(matches!(
+        channel.verify_payment_psbt(&psbt),
+        Err(SpillError::Payment(PaymentError::NonZeroLockTime))
+    ));
+}
+
+#[test]
+fn timelocked_payments_respect_max_lock_time() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let max = absolute::LockTime::from_height(1_000).expect("valid lock height");
+    let too_late = absolute::LockTime::from_height(1_001).expect("valid lock height");
+
+    let mut policy = ChannelPolicy::default();
+    policy.allow_timelocked_payments = true;
+    let unbounded = open_channel(&test_params(&payer, &payee).with_policy(policy.clone()));
+    policy.max_payment_lock_time = Some(max);
+    let channel = open_channel(&test_params(&payer, &payee).with_policy(policy));
+
+    channel
+        .next_payment_with_lock_time(
+            Amount::from_sat_u32(10_000),
+            Amount::from_sat_u32(1_000),
+            max,
+        )
+        .expect("lock time at the maximum must be accepted");
+    assert!(matches!(
+        channel.next_payment_with_lock_time(
+            Amount::from_sat_u32(10_000),
+            Amount::from_sat_u32(1_000),
+            too_late
+        ),
+        Err(SpillError::Payment(PaymentError::LockTimeTooLate { lock_time, max: found }))
+            if lock_time == too_late && found == max
+    ));
+
+    let mut psbt = unbounded
+        .next_payment_with_lock_time(
+            Amount::from_sat_u32(10_000),
+            Amount::from_sat_u32(1_000),
+            too_late,
+        )
+        .expect("failed to build timelocked payment");
+    sign_channel_input(&mut psbt, &payer, EcdsaSighashType::All);
+    assert!(matches!(
+        channel.verify_payment_psbt(&psbt),
+        Err(SpillError::Payment(PaymentError::LockTimeTooLate { .. }))
+    ));
+
+    // A timestamp lock time is not comparable to a height maximum.
+    let timestamp = absolute::LockTime::from_mtp(1_700_000_000).expect("valid lock timestamp");
+    assert!(matches!(
+        channel.next_payment_with_lock_time(
+            Amount::from_sat_u32(10_000),
+            Amount::from_sat_u32(1_000),
+            timestamp
+        ),
+        Err(SpillError::Payment(PaymentError::LockTimeTooLate { .. }))
+    ));
+}
+
+#[test]
+fn next_payment_sats_matches_amount_builder() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let channel = open_channel(&test_params(&payer, &payee));
+
+    let from_sats = channel
+        .next_payment_sats(10_000, 1_000)
+        .expect("failed to build payment");
+    let from_amount = channel
+        .next_payment(Amount::from_sat_u32(10_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    assert_eq!(from_sats, from_amount);
+
+    let out_of_range = Amount::MAX.to_sat() + 1;
+    assert!(matches!(
+        channel.next_payment_sats(out_of_range, 1_000),
+        Err(SpillError::Payment(PaymentError::AmountOverflow))
+    ));
+    assert!(matches!(
+        channel.next_payment_sats(10_000, out_of_range),
+        Err(SpillError::Payment(PaymentError::AmountOverflow))
+    ));
+}
+
+#[test]
+fn remaining_payment_count_reserves_fee_once() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let mut channel = open_channel(&test_params(&payer, &payee));
+    let fee = Amount::from_sat_u32(1_000);
+
+    assert_eq!(
+        channel.remaining_payment_count(Amount::from_sat_u32(1_000), fee),
+        39
+    );
+    assert_eq!(channel.remaining_payment_count(Amount::ZERO, fee), 0);
+
+    let mut psbt = channel
+        .next_payment(Amount::from_sat_u32(10_000), fee)
+        .expect("failed to build payment");
+    sign_channel_input(&mut psbt, &payer, EcdsaSighashType::All);
+    channel
+        .apply_payment(&psbt)
+        .expect("failed to apply payment");
+
+    assert_eq!(
+        channel.remaining_payment_count(Amount::from_sat_u32(1_000), fee),
+        29
+    );
+    assert_eq!(
+        channel.remaining_payment_count(Amount::from_sat_u32(1_000), Amount::from_sat_u32(40_000)),
+        0
+    );
+}
+
+#[test]
+fn payment_increment_must_reach_policy_minimum() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let mut policy = ChannelPolicy::default();
+    policy.min_payment_increment = Amount::from_sat_u32(5_000);
+    let mut channel = open_channel(&test_params(&payer, &payee).with_policy(policy));
+
+    let mut psbt = channel
+        .next_payment(Amount::from_sat_u32(10_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    sign_channel_input(&mut psbt, &payer, EcdsaSighashType::All);
+    channel
+        .apply_payment(&psbt)
+        .expect("failed to apply payment");
+
+    let mut below = channel
+        .next_payment(Amount::from_sat_u32(4_999), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    sign_channel_input(&mut below, &payer, EcdsaSighashType::All);
+    assert!(matches!(
+        channel.verify_payment_psbt(&below),
+        Err(SpillError::Payment(PaymentError::IncrementTooSmall { increment, minimum }))
+            if increment == Amount::from_sat_u32(4_999)
+                && minimum == Amount::from_sat_u32(5_000)
+    ));
+
+    let mut at = channel
+        .next_payment(Amount::from_sat_u32(5_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    sign_channel_input(&mut at, &payer, EcdsaSighashType::All);
+    let info = channel
+        .verify_payment_psbt(&at)
+        .expect("payment at the minimum must verify");
+    assert_eq!(info.current, Amount::from_sat_u32(5_000));
+
+    let mut above = channel
+        .next_payment(Amount::from_sat_u32(5_001), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    sign_channel_input(&mut above, &payer, EcdsaSighashType::All);
+    let info = channel
+        .verify_payment_psbt(&above)
+        .expect("payment above the minimum must verify");
+    assert_eq!(info.current, Amount::from_sat_u32(5_001));
+}
+
+#[test]
+fn canonical_payment_shape_is_recognized() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let channel = open_channel(&test_params(&payer, &payee));
+
+    let mut psbt = channel
+        .next_payment(Amount::from_sat_u32(10_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    assert!(channel.is_canonical_payment(&psbt));
+
+    sign_channel_input(&mut psbt, &payer, EcdsaSighashType::All);
+    assert!(channel.is_canonical_payment(&psbt));
+
+    let recorded = channel
+        .next_payment_with_sighash(
+            Amount::from_sat_u32(10_000),
+            Amount::from_sat_u32(1_000),
+            EcdsaSighashType::AllPlusAnyoneCanPay,
+        )
+        .expect("failed to build payment");
+    assert!(channel.is_canonical_payment(&recorded));
+
+    // Still verifiable, since the payee output is located by its script.
+    let mut swapped = psbt.clone();
+    swapped.unsigned_tx.outputs.swap(0, 1);
+    swapped.outputs.swap(0, 1);
+    sign_channel_input(&mut swapped, &payer, EcdsaSighashType::All);
+    channel
+        .verify_payment_psbt(&swapped)
+        .expect("reordered payment must verify");
+    assert!(!channel.is_canonical_payment(&swapped));
+
+    let mut extra = psbt.clone();
+    extra.unsigned_tx.outputs[1].amount = Amount::from_sat_u32(28_000);
+    extra.unsigned_tx.outputs.push(TxOut {
+        amount: Amount::from_sat_u32(1_000),
+        script_pubkey: extra.unsigned_tx.outputs[1].script_pubkey.clone(),
+    });
+    extra.outputs.push(Default::default());
+    assert!(!channel.is_canonical_payment(&extra));
+
+    let mut stripped = psbt.clone();
+    stripped.inputs[0].witness_utxo = None;
+    assert!(!channel.is_canonical_payment(&stripped));
+}
+
+#[test]
+fn bip69_sorted_payment_verifies_and_finalizes() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let mut channel = open_channel(&test_params(&payer, &payee));
+
+    let mut psbt = channel
+        .next_payment(Amount::from_sat_u32(30_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+
+    // BIP69 sorts outputs by amount, then by script, moving the payee output last here.
+    let mut outputs: Vec<_> = psbt
+        .unsigned_tx
+        .outputs
+        .iter()
+        .cloned()
+        .zip(psbt.outputs.iter().cloned())
+        .collect();
+    outputs.sort_by(|(a, _), (b, _)| {
+        a.amount
+            .cmp(&b.amount)
+            .then_with(|| a.script_pubkey.as_bytes().cmp(b.script_pubkey.as_bytes()))
+    });
+    (psbt.unsigned_tx.outputs, psbt.outputs) = outputs.into_iter().unzip();
+    assert_eq!(
+        psbt.unsigned_tx.outputs[1].amount,
+        Amount::from_sat_u32(30_000)
+    );
+
+    sign_channel_input(&mut psbt, &payer, EcdsaSighashType::All);
+    let info = channel
+        .verify_payment_psbt(&psbt)
+        .expect("sorted payment must verify");
+    assert_eq!(info.total, Amount::from_sat_u32(30_000));
+    assert_eq!(info.payer_change, Amount::from_sat_u32(9_000));
+    assert!(!channel.is_canonical_payment(&psbt));
+
+    channel
+        .apply_payment(&psbt)
+        .expect("failed to apply payment");
+    sign_channel_input(&mut psbt, &payee, EcdsaSighashType::All);
+    channel
+        .finalize_payment_tx(&mut psbt)
+        .expect("failed to finalize sorted payment");
+    let tx = psbt.extract_tx_unchecked_fee_rate();
+
+    let (payee_amount, payer_amount) = channel
+        .settled_amounts(&tx)
+        .expect("failed to read settled amounts");
+    assert_eq!(payee_amount, Amount::from_sat_u32(30_000));
+    assert_eq!(payer_amount, Amount::from_sat_u32(9_000));
+}
+
+#[test]
+fn fee_bump_replaces_latest_payment() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let mut channel = open_channel(&test_params(&payer, &payee));
+
+    let mut psbt = channel
+        .next_payment(Amount::from_sat_u32(10_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    sign_channel_input(&mut psbt, &payer, EcdsaSighashType::All);
+    channel
+        .apply_payment(&psbt)
+        .expect("failed to apply payment");
+
+    assert!(matches!(
+        channel.bump_payment_fee(&psbt, Amount::from_sat_u32(1_000)),
+        Err(SpillError::Payment(PaymentError::FeeNotIncreased { fee, previous_fee }))
+            if fee == previous_fee
+    ));
+
+    let mut bumped = channel
+        .bump_payment_fee(&psbt, Amount::from_sat_u32(3_000))
+        .expect("failed to bump payment fee");
+    sign_channel_input(&mut bumped, &payer, EcdsaSighashType::All);
+
+    // A replacement doesn't increase the amount sent, so it is not a payment.
+    assert!(matches!(
+        channel.verify_payment_psbt(&bumped),
+        Err(SpillError::Payment(PaymentError::PaymentNotIncremental))
+    ));
+
+    let info = channel
+        .verify_fee_bump_psbt(&bumped)
+        .expect("fee bump must verify");
+    assert_eq!(info.total, Amount::from_sat_u32(10_000));
+    assert_eq!(info.current, Amount::ZERO);
+    assert_eq!(info.fee, Amount::from_sat_u32(3_000));
+    assert_eq!(info.payer_change, Amount::from_sat_u32(27_000));
+
+    channel
+        .apply_fee_bump(&bumped)
+        .expect("failed to apply fee bump");
+
+    // The original payment no longer pays a higher fee than the applied state.
+    assert!(matches!(
+        channel.verify_fee_bump_psbt(&psbt),
+        Err(SpillError::Payment(PaymentError::FeeNotIncreased { fee, previous_fee }))
+            if fee == Amount::from_sat_u32(1_000) && previous_fee == Amount::from_sat_u32(3_000)
+    ));
+
+    let mut next = channel
+        .next_payment(Amount::from_sat_u32(5_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    sign_channel_input(&mut next, &payer, EcdsaSighashType::All);
+    assert!(matches!(
+        channel.verify_fee_bump_psbt(&next),
+        Err(SpillError::Payment(PaymentError::ReplacementAmountMismatch { expected, found }))
+            if expected == Amount::from_sat_u32(10_000) && found == Amount::from_sat_u32(15_000)
+    ));
+
+    sign_channel_input(&mut bumped, &payee, EcdsaSighashType::All);
+    channel
+        .finalize_payment_tx(&mut bumped)
+        .expect("failed to finalize fee bump");
+    let tx = bumped.extract_tx_unchecked_fee_rate();
+    assert_eq!(tx.outputs[1].amount, Amount::from_sat_u32(27_000));
+}
+
+#[test]
+fn anchor_close_verifies_and_finalizes() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let mut channel = open_channel(&test_params(&payer, &payee));
+
+    let mut psbt = channel
+        .next_payment(Amount::from_sat_u32(10_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    sign_channel_input(&mut psbt, &payer, EcdsaSighashType::All);
+    channel
+        .apply_payment(&psbt)
+        .expect("failed to apply payment");
+
+    let mut close = channel
+        .cooperative_close_with_anchor(Amount::from_sat_u32(240), Some(b"invoice-42".to_vec()))
+        .expect("failed to build close");
+    assert!(matches!(
+        channel.verify_anchor_close_psbt(&close),
+        Err(SpillError::Payment(PaymentError::MissingSignature))
+    ));
+    sign_channel_input(&mut close, &payer, EcdsaSighashType::All);
+
+    // The close is a version 3 transaction that doesn't increase the amount
+    // sent, so it is not a payment.
+    assert!(matches!(
+        channel.verify_payment_psbt(&close),
+        Err(SpillError::Payment(PaymentError::InvalidVersion))
+    ));
+
+    let info = channel
+        .verify_anchor_close_psbt(&close)
+        .expect("anchor close must verify");
+    assert_eq!(info.total, Amount::from_sat_u32(10_000));
+    assert_eq!(info.current, Amount::ZERO);
+    assert_eq!(info.fee, Amount::ZERO);
+    assert_eq!(info.payer_change, Amount::from_sat_u32(29_760));
+
+    let mut underpaid = close.clone();
+    underpaid.unsigned_tx.outputs[0].amount = Amount::from_sat_u32(9_000);
+    underpaid.unsigned_tx.outputs[1].amount = Amount::from_sat_u32(30_760);
+    assert!(matches!(
+        channel.verify_anchor_close_psbt(&underpaid),
+        Err(SpillError::Close(CloseError::AnchorCloseMismatch))
+    ));
+
+    let mut version_two = close.clone();
+    version_two.unsigned_tx.version = transaction::Version::TWO;
+    assert!(matches!(
+        channel.verify_anchor_close_psbt(&version_two),
+        Err(SpillError::Close(CloseError::AnchorCloseMismatch))
+    ));
+
+    sign_channel_input(&mut close, &payee, EcdsaSighashType::All);
+    channel
+        .finalize_anchor_close_tx(&mut close)
+        .expect("failed to finalize anchor close");
+    let tx = close.extract_tx_unchecked_fee_rate();
+    assert_eq!(tx.version, transaction::Version::THREE);
+    assert_eq!(tx.inputs[0].witness.len(), 5);
+}
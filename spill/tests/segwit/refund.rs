@@ -1,8 +1,16 @@
-use bitcoin::{Amount, primitives::relative};
+use bitcoin::{
+    Address, Amount, CompressedPublicKey, EcdsaSighashType, Network, Sequence,
+    primitives::relative, script::ScriptPubKeyExt, transaction,
+};
+use spill::{CloseError, FundingError, PaymentError, RefundError, SpillError};
 
 use crate::{
-    common::conversion_utils::to_rpc_tx,
+    common::{
+        conversion_utils::to_rpc_tx,
+        keys::{TestKey, sign_channel_input, test_key},
+    },
     segwit::{
+        offline::{open_channel, test_params},
         setup::{TestContext, setup_test},
         wallet::get_balance,
     },
@@ -52,3 +60,182 @@ fn refund_flow() {
 
     assert_eq!(expected_balance, balance)
 }
+
+fn regtest_address(key: &TestKey) -> Address {
+    let pubkey = CompressedPublicKey::try_from(key.pubkey).expect("key must be compressed");
+    Address::p2wpkh(pubkey, Network::Regtest)
+}
+
+#[test]
+fn refund_splits_across_two_outputs() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let channel = open_channel(&test_params(&payer, &payee));
+
+    let cold = regtest_address(&test_key(3));
+    let hot = regtest_address(&test_key(4));
+    let psbt = channel
+        .refund_psbt_to_many(
+            &[
+                (cold, Amount::from_sat_u32(30_000)),
+                (hot, Amount::from_sat_u32(9_000)),
+            ],
+            Amount::from_sat_u32(1_000),
+            None,
+        )
+        .expect("failed to build refund");
+
+    let outputs = &psbt.unsigned_tx.outputs;
+    assert_eq!(psbt.outputs.len(), 2);
+    assert_eq!(outputs[0].script_pubkey, cold.script_pubkey());
+    assert_eq!(outputs[0].amount, Amount::from_sat_u32(30_000));
+    assert_eq!(outputs[1].script_pubkey, hot.script_pubkey());
+    assert_eq!(outputs[1].amount, Amount::from_sat_u32(9_000));
+}
+
+#[test]
+fn refund_to_many_must_allocate_exactly_the_capacity() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let channel = open_channel(&test_params(&payer, &payee));
+    let address = regtest_address(&payer);
+    let fee = Amount::from_sat_u32(1_000);
+
+    let result = channel.refund_psbt_to_many(
+        &[
+            (address, Amount::from_sat_u32(30_000)),
+            (address, Amount::from_sat_u32(10_000)),
+        ],
+        fee,
+        None,
+    );
+    assert!(matches!(
+        result,
+        Err(SpillError::Refund(RefundError::ExceedsCapacity { required, .. }))
+            if required == Amount::from_sat_u32(41_000)
+    ));
+
+    let result = channel.refund_psbt_to_many(&[(address, Amount::from_sat_u32(30_000))], fee, None);
+    assert!(matches!(
+        result,
+        Err(SpillError::Refund(RefundError::UnallocatedFunds { .. }))
+    ));
+
+    let result = channel.refund_psbt_to_many(
+        &[
+            (address, Amount::from_sat_u32(38_900)),
+            (address, Amount::from_sat_u32(100)),
+        ],
+        fee,
+        None,
+    );
+    assert!(matches!(
+        result,
+        Err(SpillError::Refund(RefundError::DustOutput { .. }))
+    ));
+}
+
+#[test]
+fn refund_signature_verifies_before_lock_time() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let channel = open_channel(&test_params(&payer, &payee));
+
+    let mut psbt = channel
+        .refund_psbt_to(&regtest_address(&payer), Amount::from_sat_u32(1_000))
+        .expect("failed to build refund");
+    assert!(matches!(
+        channel.verify_refund_signature(&psbt),
+        Err(SpillError::Payment(PaymentError::MissingSignature))
+    ));
+
+    sign_channel_input(&mut psbt, &payer, EcdsaSighashType::All);
+    channel
+        .verify_refund_signature(&psbt)
+        .expect("payer signature must be valid");
+
+    psbt.unsigned_tx.outputs[0].amount = Amount::from_sat_u32(38_000);
+    assert!(matches!(
+        channel.verify_refund_signature(&psbt),
+        Err(SpillError::Payment(PaymentError::InvalidSignature))
+    ));
+}
+
+#[test]
+fn refund_broadcastable_once_lock_time_matures() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let channel = open_channel(&test_params(&payer, &payee));
+
+    let tx = channel
+        .refund_psbt_to(&regtest_address(&payer), Amount::from_sat_u32(1_000))
+        .expect("failed to build refund")
+        .unsigned_tx;
+
+    // Funded at height 100 with a 10 block lock: includable from height 110.
+    assert!(matches!(
+        channel.verify_refund_broadcastable(&tx, 100, 105),
+        Err(SpillError::Refund(RefundError::LockTimeNotMatured {
+            remaining_blocks: 4
+        }))
+    ));
+    channel
+        .verify_refund_broadcastable(&tx, 100, 109)
+        .expect("refund must be includable in the next block");
+    assert!(matches!(
+        channel.verify_refund_broadcastable(&tx, 100, 99),
+        Err(SpillError::Funding(FundingError::InvalidConfirmationHeight))
+    ));
+
+    let mut early = tx.clone();
+    early.inputs[0].sequence = Sequence::MAX;
+    assert!(matches!(
+        channel.verify_refund_broadcastable(&early, 100, 200),
+        Err(SpillError::Refund(RefundError::InvalidSequence { .. }))
+    ));
+
+    let mut version_one = tx.clone();
+    version_one.version = transaction::Version::ONE;
+    assert!(matches!(
+        channel.verify_refund_broadcastable(&version_one, 100, 200),
+        Err(SpillError::Refund(RefundError::InvalidVersion))
+    ));
+
+    let mut unrelated = tx;
+    unrelated.inputs[0].previous_output.vout += 1;
+    assert!(matches!(
+        channel.verify_refund_broadcastable(&unrelated, 100, 200),
+        Err(SpillError::Refund(RefundError::MissingFundingInput))
+    ));
+}
+
+#[test]
+fn refund_carries_op_return_data() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let channel = open_channel(&test_params(&payer, &payee));
+    let address = regtest_address(&payer);
+    let outputs = [(address, Amount::from_sat_u32(39_000))];
+    let fee = Amount::from_sat_u32(1_000);
+
+    let psbt = channel
+        .refund_psbt_to_many(&outputs, fee, Some(b"invoice-42".to_vec()))
+        .expect("failed to build refund");
+    assert_eq!(psbt.unsigned_tx.outputs.len(), 2);
+    assert_eq!(psbt.outputs.len(), 2);
+    let op_return = &psbt.unsigned_tx.outputs[1];
+    assert!(op_return.script_pubkey.is_op_return());
+    assert_eq!(op_return.amount, Amount::ZERO);
+    assert!(op_return.script_pubkey.as_bytes().ends_with(b"invoice-42"));
+
+    channel
+        .refund_psbt_to_many(&outputs, fee, Some(vec![0; 80]))
+        .expect("80 bytes of data must be accepted");
+    assert!(matches!(
+        channel.refund_psbt_to_many(&outputs, fee, Some(vec![0; 81])),
+        Err(SpillError::Close(CloseError::OpReturnTooLarge {
+            size: 81,
+            max: 80
+        }))
+    ));
+}
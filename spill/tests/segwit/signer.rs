@@ -0,0 +1,210 @@
+use bitcoin::{
+    Address, Amount, CompressedPublicKey, EcdsaSighashType, Network,
+    secp256k1::{Message, ecdsa},
+};
+use spill::{
+    ConfigError, PaymentError, PrivateKeySigner, Signer, SigningRequest, SigningResponse,
+    SpillError,
+};
+
+use crate::{
+    common::keys::{TestKey, test_key},
+    segwit::offline::{open_channel, test_params},
+};
+
+#[test]
+fn low_r_signer_produces_short_signatures() {
+    let key = test_key(1);
+    let signer = PrivateKeySigner::new(key.privkey).with_low_r(true);
+    assert_eq!(signer.public_key(), key.pubkey);
+
+    for i in 0..32u8 {
+        let msg = Message::from_digest([i; 32]);
+        let signature = signer.sign_ecdsa(msg);
+
+        assert!(signature.serialize_der().len() <= 70);
+        assert!(ecdsa::verify(&signature, msg, &key.pubkey.to_inner()).is_ok());
+    }
+}
+
+#[test]
+fn witness_estimates_stay_worst_case_for_low_r_signers() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let params = test_params(&payer, &payee);
+    let channel = open_channel(&params);
+    let signer = PrivateKeySigner::new(payer.privkey).with_low_r(true);
+
+    let mut refund = channel.refund_psbt();
+    channel
+        .sign_refund(&mut refund, &signer)
+        .expect("failed to sign refund");
+    channel
+        .finalize_refund_tx(&mut refund)
+        .expect("failed to finalize refund");
+    let witness = &refund.extract_tx_unchecked_fee_rate().inputs[0].witness;
+
+    // The low-R signature is at least 2 bytes below the assumed maximum.
+    assert!(witness.size() + 2 <= params.refund_witness_size());
+}
+
+#[test]
+fn payment_and_refund_signed_through_signer() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let mut channel = open_channel(&test_params(&payer, &payee));
+    let payer_signer = PrivateKeySigner::new(payer.privkey);
+
+    let mut psbt = channel
+        .next_payment(Amount::from_sat_u32(10_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    channel
+        .sign_payment_as_payer(&mut psbt, &payer_signer)
+        .expect("failed to sign payment");
+    channel
+        .apply_payment(&psbt)
+        .expect("payment signed through the signer must verify");
+
+    let payee_signer = PrivateKeySigner::new(payee.privkey);
+    assert!(matches!(
+        channel.sign_payment_as_payer(&mut psbt, &payee_signer),
+        Err(SpillError::Payment(PaymentError::SignerKeyMismatch { .. }))
+    ));
+
+    let mut refund = channel
+        .refund_psbt_to(
+            &Address::p2wpkh(
+                CompressedPublicKey::try_from(payer.pubkey).expect("payer key must be compressed"),
+                Network::Regtest,
+            ),
+            Amount::from_sat_u32(1_000),
+        )
+        .expect("failed to build refund");
+    channel
+        .sign_refund(&mut refund, &payer_signer)
+        .expect("failed to sign refund");
+    channel
+        .verify_refund_signature(&refund)
+        .expect("refund signed through the signer must verify");
+    channel
+        .finalize_refund_tx(&mut refund)
+        .expect("failed to finalize refund");
+    assert_eq!(
+        refund.extract_tx_unchecked_fee_rate().inputs[0]
+            .witness
+            .len(),
+        3
+    );
+}
+
+#[test]
+fn signer_uses_recorded_sighash_type() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let channel = open_channel(&test_params(&payer, &payee));
+
+    let mut psbt = channel
+        .next_payment_with_sighash(
+            Amount::from_sat_u32(10_000),
+            Amount::from_sat_u32(1_000),
+            EcdsaSighashType::AllPlusAnyoneCanPay,
+        )
+        .expect("failed to build payment");
+    channel
+        .sign_payment_as_payer(&mut psbt, &PrivateKeySigner::new(payer.privkey))
+        .expect("failed to sign payment");
+
+    assert_eq!(
+        psbt.inputs[0].partial_sigs[&payer.pubkey].sighash_type,
+        EcdsaSighashType::AllPlusAnyoneCanPay
+    );
+    channel
+        .verify_payment_psbt(&psbt)
+        .expect("payment must verify");
+}
+
+#[test]
+fn payment_signatures_exchanged_through_signing_requests() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let channel = open_channel(&test_params(&payer, &payee));
+
+    let mut psbt = channel
+        .next_payment(Amount::from_sat_u32(10_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    let request = channel
+        .payment_signing_request(&psbt)
+        .expect("failed to build signing request");
+
+    // The request crosses the wire as JSON.
+    let json = serde_json::to_string(&request).expect("failed to serialize request");
+    let received: SigningRequest = serde_json::from_str(&json).expect("failed to parse request");
+    assert_eq!(received, request);
+    assert_eq!(
+        EcdsaSighashType::from_consensus(received.sighash_type),
+        EcdsaSighashType::All
+    );
+
+    let respond = |key: &TestKey, sighash: [u8; 32]| {
+        let signer = PrivateKeySigner::new(key.privkey);
+        SigningResponse::new(
+            signer.public_key(),
+            bitcoin::ecdsa::Signature {
+                signature: signer.sign_ecdsa(Message::from_digest(sighash)),
+                sighash_type: EcdsaSighashType::All,
+            },
+        )
+    };
+
+    assert!(matches!(
+        channel.apply_signing_response(&mut psbt, &respond(&payee, [0x01; 32])),
+        Err(SpillError::Payment(PaymentError::InvalidSignature))
+    ));
+    assert!(psbt.inputs[0].partial_sigs.is_empty());
+    assert!(matches!(
+        channel.apply_signing_response(&mut psbt, &respond(&test_key(3), received.sighash)),
+        Err(SpillError::Config(ConfigError::UnknownPublicKey))
+    ));
+
+    let mut anyonecanpay = respond(&payee, received.sighash);
+    let last = anyonecanpay.signature.len() - 1;
+    anyonecanpay.signature[last] = EcdsaSighashType::AllPlusAnyoneCanPay as u8;
+    assert!(matches!(
+        channel.apply_signing_response(&mut psbt, &anyonecanpay),
+        Err(SpillError::Payment(PaymentError::InvalidSighash))
+    ));
+
+    channel
+        .apply_signing_response(&mut psbt, &respond(&payer, received.sighash))
+        .expect("payer response must apply");
+    channel
+        .apply_signing_response(&mut psbt, &respond(&payee, received.sighash))
+        .expect("payee response must apply");
+
+    channel
+        .claim_transaction(&psbt)
+        .expect("exchanged signatures must finalize");
+}
+
+#[test]
+fn signing_without_psbt_input_is_rejected() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let channel = open_channel(&test_params(&payer, &payee));
+    let signer = PrivateKeySigner::new(payer.privkey);
+
+    // The transaction keeps its input, but the PSBT input map is missing.
+    let mut psbt = channel
+        .next_payment(Amount::from_sat_u32(10_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    psbt.inputs.clear();
+
+    assert!(matches!(
+        channel.payment_signing_request(&psbt),
+        Err(SpillError::Payment(PaymentError::MissingInput))
+    ));
+    assert!(matches!(
+        channel.sign_payment_as_payer(&mut psbt, &signer),
+        Err(SpillError::Payment(PaymentError::MissingInput))
+    ));
+}
@@ -0,0 +1,170 @@
+use std::cmp::Ordering;
+
+use bitcoin::{Amount, EcdsaSighashType};
+use spill::{CloseError, PaymentError, SpendKind, SpillError};
+
+use crate::{
+    common::keys::{sign_channel_input, test_key},
+    segwit::offline::{open_channel, test_params},
+};
+
+#[test]
+fn spends_are_classified_by_branch() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let channel = open_channel(&test_params(&payer, &payee));
+
+    let mut payment = channel
+        .next_payment(Amount::from_sat_u32(10_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    sign_channel_input(&mut payment, &payer, EcdsaSighashType::All);
+    sign_channel_input(&mut payment, &payee, EcdsaSighashType::All);
+    let payment_tx = channel
+        .claim_transaction(&payment)
+        .expect("failed to build claim transaction");
+
+    assert_eq!(
+        channel.classify_spend(&payment_tx),
+        Some(SpendKind::Payment)
+    );
+
+    let mut refund = channel.refund_psbt();
+    sign_channel_input(&mut refund, &payer, EcdsaSighashType::All);
+    channel
+        .finalize_refund_tx(&mut refund)
+        .expect("failed to finalize refund");
+    let refund_tx = refund.extract_tx_unchecked_fee_rate();
+
+    assert_eq!(channel.classify_spend(&refund_tx), Some(SpendKind::Refund));
+
+    let mut unrelated = refund_tx.clone();
+    unrelated.inputs[0].previous_output.vout += 1;
+
+    assert_eq!(channel.classify_spend(&unrelated), None);
+}
+
+#[test]
+fn settled_amounts_report_final_split() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let channel = open_channel(&test_params(&payer, &payee));
+
+    let mut payment = channel
+        .next_payment(Amount::from_sat_u32(10_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    sign_channel_input(&mut payment, &payer, EcdsaSighashType::All);
+    sign_channel_input(&mut payment, &payee, EcdsaSighashType::All);
+    let payment_tx = channel
+        .claim_transaction(&payment)
+        .expect("failed to build claim transaction");
+
+    let (payee_amount, payer_amount) = channel
+        .settled_amounts(&payment_tx)
+        .expect("failed to read settled amounts");
+    assert_eq!(payee_amount, Amount::from_sat_u32(10_000));
+    assert_eq!(payer_amount, Amount::from_sat_u32(29_000));
+
+    let mut unrelated = payment_tx.clone();
+    unrelated.inputs[0].previous_output.vout += 1;
+
+    assert!(matches!(
+        channel.settled_amounts(&unrelated),
+        Err(SpillError::Close(CloseError::NotChannelSpend))
+    ));
+
+    // Outputs summing above the supply cannot be valid, but must not panic.
+    let mut overflowing = payment_tx.clone();
+    overflowing.outputs[0].amount = Amount::MAX;
+    overflowing.outputs.push(overflowing.outputs[0].clone());
+    assert!(matches!(
+        channel.settled_amounts(&overflowing),
+        Err(SpillError::Payment(PaymentError::AmountOverflow))
+    ));
+}
+
+#[test]
+fn payment_info_is_recovered_from_transaction() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let mut channel = open_channel(&test_params(&payer, &payee));
+
+    let mut payment = channel
+        .next_payment(Amount::from_sat_u32(10_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    sign_channel_input(&mut payment, &payer, EcdsaSighashType::All);
+    sign_channel_input(&mut payment, &payee, EcdsaSighashType::All);
+    let payment_tx = channel
+        .claim_transaction(&payment)
+        .expect("failed to build claim transaction");
+
+    let info = channel
+        .payment_info_from_tx(&payment_tx)
+        .expect("failed to recover payment info");
+    assert_eq!(info.total, Amount::from_sat_u32(10_000));
+    assert_eq!(info.current, Amount::from_sat_u32(10_000));
+    assert_eq!(info.fee, Amount::from_sat_u32(1_000));
+    assert_eq!(info.payer_change, Amount::from_sat_u32(29_000));
+
+    channel
+        .apply_payment(&payment)
+        .expect("failed to apply payment");
+    let info = channel
+        .payment_info_from_tx(&payment_tx)
+        .expect("failed to recover payment info");
+    assert_eq!(info.current, Amount::ZERO);
+
+    let mut refund = channel.refund_psbt();
+    sign_channel_input(&mut refund, &payer, EcdsaSighashType::All);
+    channel
+        .finalize_refund_tx(&mut refund)
+        .expect("failed to finalize refund");
+    let refund_tx = refund.extract_tx_unchecked_fee_rate();
+
+    assert!(matches!(
+        channel.payment_info_from_tx(&refund_tx),
+        Err(SpillError::Close(CloseError::NotPaymentClose))
+    ));
+}
+
+#[test]
+fn funding_double_spend_is_detected() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let channel = open_channel(&test_params(&payer, &payee));
+
+    // An unsigned transaction is enough: only the spent outpoints matter.
+    let mut conflict = channel.refund_psbt().unsigned_tx;
+    assert!(channel.funding_outpoint_spent_by(&conflict));
+
+    conflict.inputs[0].previous_output.vout += 1;
+    assert!(!channel.funding_outpoint_spent_by(&conflict));
+}
+
+#[test]
+fn payments_conflict_and_compare_by_total() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let channel = open_channel(&test_params(&payer, &payee));
+
+    let small = channel
+        .next_payment(Amount::from_sat_u32(5_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    let large = channel
+        .next_payment(Amount::from_sat_u32(10_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+
+    assert!(channel.payments_conflict(&small, &large));
+    assert_eq!(
+        channel.compare_payment_totals(&large, &small),
+        Some(Ordering::Greater)
+    );
+    assert_eq!(
+        channel.compare_payment_totals(&small, &small),
+        Some(Ordering::Equal)
+    );
+
+    let mut unrelated = large.clone();
+    unrelated.unsigned_tx.inputs[0].previous_output.vout += 1;
+    assert!(!channel.payments_conflict(&small, &unrelated));
+    assert_eq!(channel.compare_payment_totals(&small, &unrelated), None);
+}
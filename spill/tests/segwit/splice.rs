@@ -0,0 +1,177 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use bitcoin::{
+    Address, Amount, CompressedPublicKey, EcdsaSighashType, Network, OutPoint, TxOut, Txid,
+    script::ScriptBuf,
+};
+use spill::{FundingError, SpillError};
+
+use crate::{
+    common::keys::{sign_channel_input, test_key},
+    segwit::offline::{open_channel, test_params},
+};
+
+#[test]
+fn splice_in_increases_capacity() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let opened_at = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    let mut channel = open_channel(&test_params(&payer, &payee)).with_opened_at(opened_at);
+
+    let mut psbt = channel
+        .next_payment(Amount::from_sat_u32(10_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    sign_channel_input(&mut psbt, &payer, EcdsaSighashType::All);
+    channel
+        .apply_payment(&psbt)
+        .expect("failed to apply payment");
+
+    let new_input = (
+        OutPoint {
+            txid: Txid::from_byte_array([0xcd; 32]),
+            vout: 1,
+        },
+        TxOut {
+            amount: Amount::from_sat_u32(20_500),
+            script_pubkey: ScriptBuf::new(),
+        },
+    );
+
+    assert!(matches!(
+        channel.splice_in_psbt(
+            Amount::from_sat_u32(30_000),
+            std::slice::from_ref(&new_input)
+        ),
+        Err(SpillError::Funding(FundingError::InsufficientFunds { .. }))
+    ));
+
+    let splice = channel
+        .splice_in_psbt(Amount::from_sat_u32(20_000), &[new_input])
+        .expect("failed to build splice");
+    assert_eq!(splice.unsigned_tx.inputs.len(), 2);
+    assert_eq!(
+        splice.unsigned_tx.outputs[0].amount,
+        Amount::from_sat_u32(60_000)
+    );
+
+    let splice_tx = splice.unsigned_tx;
+
+    let mut unrelated = splice_tx.clone();
+    unrelated.inputs.remove(0);
+    assert!(matches!(
+        channel.verify_splice(&unrelated, 0),
+        Err(SpillError::Funding(FundingError::SpliceMissingFundingInput))
+    ));
+
+    let mut unchanged = splice_tx.clone();
+    unchanged.outputs[0].amount = Amount::from_sat_u32(40_000);
+    assert!(matches!(
+        channel.verify_splice(&unchanged, 0),
+        Err(SpillError::Funding(FundingError::NoCapacityIncrease { found, capacity }))
+            if found == Amount::from_sat_u32(40_000) && capacity == Amount::from_sat_u32(40_000)
+    ));
+
+    let before = SystemTime::now();
+    channel
+        .apply_splice(&splice_tx, 0)
+        .expect("failed to apply splice");
+    assert_eq!(channel.id().outpoint().txid, splice_tx.compute_txid());
+    // The refund lock time restarts with the new funding output.
+    assert!(channel.opened_at() >= before);
+
+    let mut psbt = channel
+        .next_payment(Amount::from_sat_u32(30_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    assert_eq!(
+        psbt.inputs[0].witness_utxo.as_ref().map(|utxo| utxo.amount),
+        Some(Amount::from_sat_u32(60_000))
+    );
+    assert_eq!(
+        channel.funding_info().capacity,
+        Amount::from_sat_u32(60_000)
+    );
+    sign_channel_input(&mut psbt, &payer, EcdsaSighashType::All);
+    let info = channel
+        .verify_payment_psbt(&psbt)
+        .expect("payment on spliced channel must verify");
+
+    assert_eq!(info.total, Amount::from_sat_u32(40_000));
+    assert_eq!(info.payer_change, Amount::from_sat_u32(19_000));
+}
+
+#[test]
+fn capacity_reduction_refunds_payer() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let opened_at = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    let mut channel = open_channel(&test_params(&payer, &payee)).with_opened_at(opened_at);
+
+    let mut psbt = channel
+        .next_payment(Amount::from_sat_u32(10_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    sign_channel_input(&mut psbt, &payer, EcdsaSighashType::All);
+    channel
+        .apply_payment(&psbt)
+        .expect("failed to apply payment");
+
+    let refund_address = Address::p2wpkh(
+        CompressedPublicKey::try_from(payer.pubkey).expect("payer key must be compressed"),
+        Network::Regtest,
+    );
+    let fee = Amount::from_sat_u32(1_000);
+
+    assert!(matches!(
+        channel.reduce_capacity_psbt(Amount::from_sat_u32(40_000), &refund_address, fee),
+        Err(SpillError::Funding(FundingError::ValueTooHigh { .. }))
+    ));
+    // The latest payment leaves only 29_000 sat to the payer.
+    assert!(matches!(
+        channel.reduce_capacity_psbt(Amount::from_sat_u32(5_000), &refund_address, fee),
+        Err(SpillError::Funding(FundingError::InsufficientFunds { .. }))
+    ));
+
+    let reduction = channel
+        .reduce_capacity_psbt(Amount::from_sat_u32(25_000), &refund_address, fee)
+        .expect("failed to build capacity reduction");
+    let reduction_tx = reduction.unsigned_tx;
+    assert_eq!(reduction_tx.outputs[0].amount, Amount::from_sat_u32(25_000));
+    assert_eq!(reduction_tx.outputs[1].amount, Amount::from_sat_u32(14_000));
+    assert_eq!(
+        reduction_tx.outputs[1].script_pubkey,
+        refund_address.script_pubkey()
+    );
+
+    let mut unrelated = reduction_tx.clone();
+    unrelated.inputs[0].previous_output.vout += 1;
+    assert!(matches!(
+        channel.verify_capacity_reduction(&unrelated, 0),
+        Err(SpillError::Funding(FundingError::SpliceMissingFundingInput))
+    ));
+
+    let before = SystemTime::now();
+    channel
+        .apply_capacity_reduction(&reduction_tx, 0)
+        .expect("failed to apply capacity reduction");
+    assert_eq!(channel.id().outpoint().txid, reduction_tx.compute_txid());
+    // The refund lock time restarts with the new funding output.
+    assert!(channel.opened_at() >= before);
+
+    let mut psbt = channel
+        .next_payment(Amount::from_sat_u32(5_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    assert_eq!(
+        psbt.inputs[0].witness_utxo.as_ref().map(|utxo| utxo.amount),
+        Some(Amount::from_sat_u32(25_000))
+    );
+    assert_eq!(
+        channel.funding_info().capacity,
+        Amount::from_sat_u32(25_000)
+    );
+    sign_channel_input(&mut psbt, &payer, EcdsaSighashType::All);
+    let info = channel
+        .verify_payment_psbt(&psbt)
+        .expect("payment on reduced channel must verify");
+
+    assert_eq!(info.total, Amount::from_sat_u32(15_000));
+    assert_eq!(info.payer_change, Amount::from_sat_u32(9_000));
+}
@@ -0,0 +1,71 @@
+use bitcoin::{Amount, EcdsaSighashType, FeeRate, Transaction};
+use spill::{SpillError, StandardnessError, check_standardness};
+
+use crate::{
+    common::keys::{sign_channel_input, test_key},
+    segwit::offline::{open_channel, test_params},
+};
+
+fn payment_tx() -> Transaction {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let params = test_params(&payer, &payee);
+    let channel = open_channel(&params);
+
+    let mut psbt = channel
+        .next_payment(Amount::from_sat_u32(10_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    sign_channel_input(&mut psbt, &payer, EcdsaSighashType::All);
+    sign_channel_input(&mut psbt, &payee, EcdsaSighashType::All);
+    channel
+        .claim_transaction(&psbt)
+        .expect("failed to build claim transaction")
+}
+
+#[test]
+fn payment_transaction_is_standard() {
+    check_standardness(
+        &payment_tx(),
+        FeeRate::from_sat_per_vb(1),
+        Amount::from_sat_u32(40_000),
+    )
+    .expect("payment must be standard");
+}
+
+#[test]
+fn dust_output_is_rejected() {
+    let mut tx = payment_tx();
+    let payee_output = tx
+        .outputs
+        .iter()
+        .position(|output| output.amount == Amount::from_sat_u32(10_000))
+        .expect("missing payee output");
+    tx.outputs[payee_output].amount = Amount::from_sat_u32(100);
+
+    assert!(matches!(
+        check_standardness(&tx, FeeRate::from_sat_per_vb(1), Amount::from_sat_u32(40_000)),
+        Err(SpillError::Standardness(StandardnessError::DustOutput { index, .. }))
+            if index == payee_output
+    ));
+}
+
+#[test]
+fn low_fee_is_rejected() {
+    let tx = payment_tx();
+
+    assert!(matches!(
+        check_standardness(&tx, FeeRate::from_sat_per_vb(10), Amount::from_sat_u32(40_000)),
+        Err(SpillError::Standardness(StandardnessError::FeeTooLow { fee, .. }))
+            if fee == Amount::from_sat_u32(1_000)
+    ));
+    assert!(matches!(
+        check_standardness(
+            &tx,
+            FeeRate::from_sat_per_vb(1),
+            Amount::from_sat_u32(30_000)
+        ),
+        Err(SpillError::Standardness(
+            StandardnessError::OutputsExceedInput
+        ))
+    ));
+}
@@ -0,0 +1,120 @@
+use bitcoin::{Amount, EcdsaSighashType, FeeRate};
+use spill::{PaymentError, PaymentStream, PrivateKeySigner, ReceiveStream, SpillError};
+
+use crate::{
+    common::keys::{sign_channel_input, test_key},
+    segwit::offline::{open_channel, test_params},
+};
+
+#[test]
+fn stream_tracks_cumulative_total() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let params = test_params(&payer, &payee);
+    let mut channel = open_channel(&params);
+    let signer = PrivateKeySigner::new(payer.privkey);
+
+    let feerate = FeeRate::from_sat_per_vb(2);
+    let mut stream = PaymentStream::new(&mut channel, &signer, feerate);
+    assert_eq!(
+        stream.fee(),
+        params.estimate_lifecycle_cost(feerate, 0).close_fee
+    );
+
+    stream
+        .pay(Amount::from_sat_u32(10_000))
+        .expect("failed to pay");
+    let psbt = stream
+        .pay(Amount::from_sat_u32(5_000))
+        .expect("failed to pay");
+    assert_eq!(stream.total_paid(), Amount::from_sat_u32(15_000));
+    assert_eq!(
+        psbt.unsigned_tx.outputs[0].amount,
+        Amount::from_sat_u32(15_000)
+    );
+
+    let err = stream
+        .pay(Amount::from_sat_u32(25_000))
+        .expect_err("the channel must be exhausted");
+    assert!(matches!(
+        err,
+        SpillError::Payment(PaymentError::ExceedsCapacity { .. })
+    ));
+    assert_eq!(stream.total_paid(), Amount::from_sat_u32(15_000));
+
+    // The payments were signed and applied, so the channel rejects a
+    // replay of the latest one and the payee accepts it.
+    assert!(matches!(
+        channel.verify_payment_psbt(&psbt),
+        Err(SpillError::Payment(PaymentError::PaymentNotIncremental))
+    ));
+    let mut payee_channel = open_channel(&params);
+    payee_channel
+        .apply_payment(&psbt)
+        .expect("failed to apply the latest payment");
+}
+
+#[test]
+fn stream_rejects_foreign_signer() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let params = test_params(&payer, &payee);
+    let mut channel = open_channel(&params);
+    let signer = PrivateKeySigner::new(payee.privkey);
+
+    let mut stream = PaymentStream::new(&mut channel, &signer, FeeRate::from_sat_per_vb(2));
+    assert!(matches!(
+        stream.pay(Amount::from_sat_u32(10_000)),
+        Err(SpillError::Payment(PaymentError::SignerKeyMismatch { .. }))
+    ));
+    assert_eq!(stream.total_paid(), Amount::ZERO);
+}
+
+#[test]
+fn receive_stream_keeps_latest_payment() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let params = test_params(&payer, &payee);
+    let payer_channel = open_channel(&params);
+    let mut payee_channel = open_channel(&params);
+
+    let fee = Amount::from_sat_u32(1_000);
+    let payments = [10_000, 15_000, 20_000].map(|total| {
+        let mut psbt = payer_channel
+            .next_payment_to_total(Amount::from_sat_u32(total), fee)
+            .expect("failed to build payment");
+        sign_channel_input(&mut psbt, &payer, EcdsaSighashType::All);
+        psbt
+    });
+
+    let mut stream = ReceiveStream::new(&mut payee_channel);
+    assert!(stream.best_payment().is_none());
+    assert_eq!(stream.claimable(), Amount::ZERO);
+
+    let info = stream
+        .receive(&payments[0])
+        .expect("failed to receive payment");
+    assert_eq!(info.current, Amount::from_sat_u32(10_000));
+
+    // The third payment arrives before the second one.
+    let info = stream
+        .receive(&payments[2])
+        .expect("failed to receive payment");
+    assert_eq!(info.current, Amount::from_sat_u32(10_000));
+    assert_eq!(stream.claimable(), Amount::from_sat_u32(20_000));
+
+    for stale in [&payments[1], &payments[2], &payments[0]] {
+        assert!(matches!(
+            stream.receive(stale),
+            Err(SpillError::Payment(PaymentError::PaymentNotIncremental))
+        ));
+    }
+
+    assert_eq!(stream.claimable(), Amount::from_sat_u32(20_000));
+    assert_eq!(
+        stream
+            .best_payment()
+            .map(|psbt| psbt.unsigned_tx.compute_txid()),
+        Some(payments[2].unsigned_tx.compute_txid())
+    );
+}
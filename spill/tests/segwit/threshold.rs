@@ -0,0 +1,240 @@
+use bitcoin::{Amount, EcdsaSighashType, primitives::relative};
+use spill::{
+    ChannelParams, CloseError, ConfigError, FinalizeError, PaymentError, SegwitBackend, SpillError,
+};
+
+use crate::{
+    common::keys::{sign_channel_input, test_key},
+    segwit::offline::{open_channel, test_params},
+};
+
+#[test]
+fn two_of_three_finalizes_with_payee_and_arbiter() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let arbiter = test_key(3);
+
+    let params = ChannelParams::new_threshold(
+        payer.pubkey,
+        payee.pubkey,
+        vec![arbiter.pubkey],
+        2,
+        Amount::from_sat_u32(40_000),
+        relative::LockTime::from_height(10),
+        SegwitBackend::new(),
+    )
+    .expect("failed to create ChannelParams");
+    let channel = open_channel(&params);
+
+    let mut psbt = channel
+        .next_payment(Amount::from_sat_u32(10_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+
+    sign_channel_input(&mut psbt, &payee, EcdsaSighashType::All);
+
+    let err = channel
+        .finalize_payment_tx(&mut psbt.clone())
+        .expect_err("a single signature must not satisfy a 2-of-3");
+    assert!(matches!(
+        err,
+        SpillError::Finalize(FinalizeError::MissingSignature { public_key }) if public_key == payer.pubkey
+    ));
+
+    sign_channel_input(&mut psbt, &arbiter, EcdsaSighashType::All);
+    let payee_sig = psbt.inputs[0].partial_sigs[&payee.pubkey].signature;
+    let arbiter_sig = psbt.inputs[0].partial_sigs[&arbiter.pubkey].signature;
+
+    channel
+        .finalize_payment_tx(&mut psbt)
+        .expect("failed to finalize payment");
+
+    let witness = psbt.inputs[0]
+        .final_script_witness
+        .as_ref()
+        .expect("witness must be set");
+    // dummy, payee sig, arbiter sig, branch selector, witness script
+    assert_eq!(witness.len(), 5);
+    let witness: Vec<&[u8]> = witness.iter().collect();
+    assert!(witness[1].starts_with(&payee_sig.serialize_der()));
+    assert!(witness[2].starts_with(&arbiter_sig.serialize_der()));
+}
+
+#[test]
+fn threshold_out_of_range_is_rejected() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let arbiter = test_key(3);
+
+    for (cosigners, threshold) in [
+        (vec![arbiter.pubkey], 1),
+        (vec![arbiter.pubkey], 4),
+        (vec![], 3),
+    ] {
+        let result = ChannelParams::new_threshold(
+            payer.pubkey,
+            payee.pubkey,
+            cosigners,
+            threshold,
+            Amount::from_sat_u32(40_000),
+            relative::LockTime::from_height(10),
+            SegwitBackend::new(),
+        );
+        assert!(matches!(
+            result,
+            Err(SpillError::Config(ConfigError::InvalidThreshold))
+        ));
+    }
+
+    let result = ChannelParams::new_threshold(
+        payer.pubkey,
+        payee.pubkey,
+        vec![payee.pubkey],
+        2,
+        Amount::from_sat_u32(40_000),
+        relative::LockTime::from_height(10),
+        SegwitBackend::new(),
+    );
+    assert!(matches!(
+        result,
+        Err(SpillError::Config(ConfigError::DuplicatePublicKey))
+    ));
+}
+
+#[test]
+fn arbiter_close_finalizes_with_payer_and_arbiter() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let arbiter = test_key(3);
+
+    let params = ChannelParams::new_threshold(
+        payer.pubkey,
+        payee.pubkey,
+        vec![arbiter.pubkey],
+        2,
+        Amount::from_sat_u32(40_000),
+        relative::LockTime::from_height(10),
+        SegwitBackend::new(),
+    )
+    .expect("failed to create ChannelParams");
+    let channel = open_channel(&params);
+
+    let mut psbt = channel
+        .arbiter_close_psbt((Amount::from_sat_u32(5_000), Amount::from_sat_u32(34_000)))
+        .expect("failed to build arbiter close");
+    assert_eq!(
+        psbt.unsigned_tx.outputs[0].amount,
+        Amount::from_sat_u32(5_000)
+    );
+    assert_eq!(
+        psbt.unsigned_tx.outputs[1].amount,
+        Amount::from_sat_u32(34_000)
+    );
+
+    sign_channel_input(&mut psbt, &payer, EcdsaSighashType::All);
+    let err = channel
+        .finalize_arbiter_close_tx(&mut psbt.clone())
+        .expect_err("the arbiter's signature must be required");
+    assert!(matches!(
+        err,
+        SpillError::Finalize(FinalizeError::MissingSignature { public_key }) if public_key == arbiter.pubkey
+    ));
+
+    sign_channel_input(&mut psbt, &arbiter, EcdsaSighashType::All);
+    channel
+        .finalize_arbiter_close_tx(&mut psbt)
+        .expect("failed to finalize arbiter close");
+    let witness = psbt.inputs[0]
+        .final_script_witness
+        .as_ref()
+        .expect("witness must be set");
+    assert_eq!(witness.len(), 5);
+
+    let err = channel
+        .arbiter_close_psbt((Amount::from_sat_u32(20_000), Amount::from_sat_u32(20_001)))
+        .expect_err("the split must not exceed the capacity");
+    assert!(matches!(err, SpillError::Payment(_)));
+}
+
+#[test]
+fn arbiter_close_requires_an_arbiter() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let channel = open_channel(&test_params(&payer, &payee));
+
+    assert!(matches!(
+        channel.arbiter_close_psbt((Amount::from_sat_u32(5_000), Amount::from_sat_u32(34_000))),
+        Err(SpillError::Close(CloseError::NoArbiter))
+    ));
+}
+
+#[test]
+fn arbiter_close_rejects_dust_outputs() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let arbiter = test_key(3);
+
+    let params = ChannelParams::new_threshold(
+        payer.pubkey,
+        payee.pubkey,
+        vec![arbiter.pubkey],
+        2,
+        Amount::from_sat_u32(40_000),
+        relative::LockTime::from_height(10),
+        SegwitBackend::new(),
+    )
+    .expect("failed to create ChannelParams");
+    let channel = open_channel(&params);
+
+    assert!(matches!(
+        channel.arbiter_close_psbt((Amount::from_sat_u32(100), Amount::from_sat_u32(38_000))),
+        Err(SpillError::Payment(PaymentError::PayeeOutputDust { amount, .. }))
+            if amount == Amount::from_sat_u32(100)
+    ));
+    assert!(matches!(
+        channel.arbiter_close_psbt((Amount::from_sat_u32(38_000), Amount::from_sat_u32(100))),
+        Err(SpillError::Close(CloseError::PayerOutputDust { amount, .. }))
+            if amount == Amount::from_sat_u32(100)
+    ));
+
+    // Awarding everything to the payee drops the payer output.
+    let mut psbt = channel
+        .arbiter_close_psbt((Amount::from_sat_u32(39_000), Amount::ZERO))
+        .expect("failed to build arbiter close");
+    assert_eq!(psbt.unsigned_tx.outputs.len(), 1);
+    assert_eq!(psbt.outputs.len(), 1);
+    assert_eq!(
+        psbt.unsigned_tx.outputs[0].amount,
+        Amount::from_sat_u32(39_000)
+    );
+
+    sign_channel_input(&mut psbt, &payee, EcdsaSighashType::All);
+    sign_channel_input(&mut psbt, &arbiter, EcdsaSighashType::All);
+    channel
+        .finalize_arbiter_close_tx(&mut psbt)
+        .expect("failed to finalize arbiter close");
+}
+
+#[test]
+fn arbiter_close_requires_reachable_threshold() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+
+    let params = ChannelParams::new_threshold(
+        payer.pubkey,
+        payee.pubkey,
+        vec![test_key(3).pubkey, test_key(4).pubkey],
+        3,
+        Amount::from_sat_u32(40_000),
+        relative::LockTime::from_height(10),
+        SegwitBackend::new(),
+    )
+    .expect("failed to create ChannelParams");
+    let channel = open_channel(&params);
+
+    assert!(matches!(
+        channel.arbiter_close_psbt((Amount::from_sat_u32(20_000), Amount::from_sat_u32(19_000))),
+        Err(SpillError::Close(CloseError::ArbiterThresholdTooHigh {
+            threshold: 3
+        }))
+    ));
+}
@@ -0,0 +1,150 @@
+use bitcoin::{
+    Amount, EcdsaSighashType, FeeRate, TxOut, script::ScriptBuf, transaction::TransactionExt,
+};
+use spill::{PaymentError, SpillError};
+
+use crate::{
+    common::keys::{sign_channel_input, test_key},
+    segwit::offline::{open_channel, test_params},
+};
+
+/// Low-S DER signatures are 70 to 72 bytes, so a finalized witness may be
+/// up to 3 bytes smaller per signature than the upper bound.
+const MAX_SLACK_PER_SIGNATURE: usize = 3;
+
+#[test]
+fn witness_sizes_bound_finalized_witnesses() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let params = test_params(&payer, &payee);
+    let channel = open_channel(&params);
+
+    let mut payment = channel
+        .next_payment(Amount::from_sat_u32(10_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    sign_channel_input(&mut payment, &payer, EcdsaSighashType::All);
+    sign_channel_input(&mut payment, &payee, EcdsaSighashType::All);
+    channel
+        .finalize_payment_tx(&mut payment)
+        .expect("failed to finalize payment");
+
+    let size = payment.inputs[0]
+        .final_script_witness
+        .as_ref()
+        .expect("witness must be set")
+        .size();
+    assert!(size <= params.payment_witness_size());
+    assert!(params.payment_witness_size() - size <= 2 * MAX_SLACK_PER_SIGNATURE);
+
+    let mut refund = channel.refund_psbt();
+    refund.outputs.push(Default::default());
+    refund.unsigned_tx.outputs.push(TxOut {
+        amount: Amount::from_sat_u32(39_000),
+        script_pubkey: ScriptBuf::new(),
+    });
+    sign_channel_input(&mut refund, &payer, EcdsaSighashType::All);
+    channel
+        .finalize_refund_tx(&mut refund)
+        .expect("failed to finalize refund");
+
+    let size = refund.inputs[0]
+        .final_script_witness
+        .as_ref()
+        .expect("witness must be set")
+        .size();
+    assert!(size <= params.refund_witness_size());
+    assert!(params.refund_witness_size() - size <= MAX_SLACK_PER_SIGNATURE);
+}
+
+#[test]
+fn lifecycle_cost_bounds_close_fee() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let params = test_params(&payer, &payee);
+    let channel = open_channel(&params);
+
+    let mut payment = channel
+        .next_payment(Amount::from_sat_u32(10_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    sign_channel_input(&mut payment, &payer, EcdsaSighashType::All);
+    sign_channel_input(&mut payment, &payee, EcdsaSighashType::All);
+    let close_tx = channel
+        .claim_transaction(&payment)
+        .expect("failed to build claim transaction");
+
+    let feerate = FeeRate::from_sat_per_vb(10);
+    let cost = params.estimate_lifecycle_cost(feerate, 1);
+
+    let actual = feerate.to_fee(close_tx.weight());
+    assert!(cost.close_fee >= actual);
+    assert!(cost.close_fee.to_sat() - actual.to_sat() <= 20);
+    assert!(cost.funding_fee > Amount::ZERO);
+    assert_eq!(
+        cost.total().to_sat(),
+        cost.funding_fee.to_sat() + cost.close_fee.to_sat()
+    );
+}
+
+#[test]
+fn relay_feasibility_checks_fee_against_weight() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let channel = open_channel(&test_params(&payer, &payee));
+
+    let mut payment = channel
+        .next_payment(Amount::from_sat_u32(10_000), Amount::from_sat_u32(1_000))
+        .expect("failed to build payment");
+    let unsigned = payment.unsigned_tx.clone();
+
+    channel
+        .check_relay_feasible(&unsigned, FeeRate::from_sat_per_vb(1))
+        .expect("1000 sat must cover 1 sat/vB");
+    assert!(matches!(
+        channel.check_relay_feasible(&unsigned, FeeRate::from_sat_per_vb(10)),
+        Err(SpillError::Payment(PaymentError::FeeTooLow { fee, .. }))
+            if fee == Amount::from_sat_u32(1_000)
+    ));
+
+    sign_channel_input(&mut payment, &payer, EcdsaSighashType::All);
+    sign_channel_input(&mut payment, &payee, EcdsaSighashType::All);
+    let tx = channel
+        .claim_transaction(&payment)
+        .expect("failed to build claim transaction");
+
+    channel
+        .check_relay_feasible(&tx, FeeRate::from_sat_per_vb(1))
+        .expect("1000 sat must cover 1 sat/vB");
+    assert!(matches!(
+        channel.check_relay_feasible(&tx, FeeRate::from_sat_per_vb(10)),
+        Err(SpillError::Payment(PaymentError::FeeTooLow { min_fee, .. }))
+            if min_fee == FeeRate::from_sat_per_vb(10).to_fee(tx.weight())
+    ));
+}
+
+#[test]
+fn optimal_close_fee_covers_final_payment() {
+    let payer = test_key(1);
+    let payee = test_key(2);
+    let params = test_params(&payer, &payee);
+    let channel = open_channel(&params);
+    let feerate = FeeRate::from_sat_per_vb(5);
+
+    let fee = channel.optimal_close_fee(feerate);
+    assert_eq!(fee, params.estimate_lifecycle_cost(feerate, 0).close_fee);
+
+    let mut payment = channel
+        .next_payment(Amount::from_sat_u32(10_000), fee)
+        .expect("failed to build payment");
+    sign_channel_input(&mut payment, &payer, EcdsaSighashType::All);
+    sign_channel_input(&mut payment, &payee, EcdsaSighashType::All);
+    let tx = channel
+        .claim_transaction(&payment)
+        .expect("failed to build claim transaction");
+
+    channel
+        .check_relay_feasible(&tx, feerate)
+        .expect("close fee must cover the final payment");
+    let actual = feerate.to_fee(tx.weight());
+    assert!(fee >= actual);
+    assert!(fee.to_sat() - actual.to_sat() <= 5 * 2);
+}
@@ -0,0 +1,45 @@
+#![cfg(feature = "test-vectors")]
+
+use bitcoin::consensus::encode::serialize_hex;
+use spill::test_vectors;
+
+#[test]
+fn finalized_transactions_match_vectors() {
+    assert_eq!(
+        serialize_hex(&test_vectors::funding_tx()),
+        test_vectors::FUNDING_TX_HEX
+    );
+    assert_eq!(
+        serialize_hex(&test_vectors::payment_tx()),
+        test_vectors::PAYMENT_TX_HEX
+    );
+    assert_eq!(
+        serialize_hex(&test_vectors::refund_tx()),
+        test_vectors::REFUND_TX_HEX
+    );
+}
+
+#[test]
+fn payment_vector_verifies() {
+    let channel = test_vectors::channel();
+    let info = channel
+        .verify_payment_psbt(&test_vectors::payment_psbt())
+        .expect("payment vector must verify");
+
+    assert_eq!(info.total, test_vectors::PAYMENT_AMOUNT);
+    assert_eq!(info.fee, test_vectors::FEE);
+}
+
+#[test]
+fn new_for_test_matches_vector_params() {
+    let params = spill::ChannelParams::new_for_test();
+
+    assert_eq!(
+        params.script_pubkey(),
+        test_vectors::channel_params().script_pubkey()
+    );
+    assert_eq!(
+        params.funding_psbt().unsigned_tx.outputs[0].amount,
+        test_vectors::CAPACITY
+    );
+}